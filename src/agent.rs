@@ -1,11 +1,19 @@
 use crate::config::Config;
-use crate::events::{BindrMode, ConversationRole, ConversationEntry, ProjectState};
+use crate::events::{BindrMode, ConversationRole, ConversationEntry, FlaggedMessage, ProjectState};
+use crate::handoff::ModeHandoff;
 use crate::llm::{LlmClient, LlmRequest, LlmMessage, LlmEvent};
+use crate::pricing;
 use crate::prompts;
+use crate::redact::Redactor;
 use crate::session::SessionManager;
-use crate::tools::{ToolDispatcher, ToolInvocation, ToolRequestOutcome};
+use crate::tools::{self, ToolDispatcher, ToolInvocation, ToolRequestOutcome};
 use anyhow::Result;
+use std::collections::HashMap;
 use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+
+/// Longest brainstorm summary carried into later modes' prompts, in characters
+const BRAINSTORM_CONTEXT_CHARS: usize = 500;
 
 /// Agent orchestrator that manages different modes and their interactions
 #[derive(Clone)]
@@ -17,25 +25,68 @@ pub struct AgentOrchestrator {
     session_manager: SessionManager,
     current_mode: BindrMode,
     conversation_history: Vec<ConversationEntry>,
+    /// Conversations of the modes not currently active, kept for switching back without a project open
+    mode_histories: HashMap<BindrMode, Vec<ConversationEntry>>,
     current_provider: String,
     current_model: String,
+    /// Per-project /lang choice; falls back to `[ui].response_locale`
+    response_locale: Option<String>,
+    flagged_messages: Vec<FlaggedMessage>,
+    /// Latest handoff into each mode, carried into that mode's system prompt
+    handoffs: Vec<ModeHandoff>,
+    /// /temperature choice for this session; replaces the mode's temperature until cleared
+    temperature_override: Option<f32>,
+    /// /maxtokens choice for this session; replaces the mode's reply limit until cleared
+    max_tokens_override: Option<u32>,
+    redactor: Option<Redactor>,
+    /// Task relaying the reply currently streaming, aborted when the user interrupts it
+    stream_task: Option<AbortHandle>,
+    /// Model the latest reply was requested from, recorded with it once it completes
+    reply_model: Option<String>,
 }
 
 impl AgentOrchestrator {
     pub fn new(config: Config, session_manager: SessionManager) -> Self {
         let llm_client = LlmClient::new(config.clone());
         
-        let current_provider = config.selected_provider.clone();
-        let current_model = config.default_model.clone();
+        let (current_provider, current_model) = config.mode_model(BindrMode::Brainstorm);
+        let response_locale = session_manager
+            .current_session()
+            .and_then(|session| session.project_state.response_locale.clone());
+        let flagged_messages = session_manager
+            .current_session()
+            .map(|session| session.project_state.flagged_messages.clone())
+            .unwrap_or_default();
+        let handoffs = session_manager
+            .current_session()
+            .map(|session| session.project_state.handoffs.clone())
+            .unwrap_or_default();
+
+        let redactor = Redactor::from_config(&config.redaction).ok().flatten();
+        // A reopened project picks up where its first mode left off; a damaged file starts it fresh
+        let conversation_history = session_manager
+            .load_mode_history(BindrMode::Brainstorm)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
 
         Self {
             config,
             llm_client,
             session_manager,
             current_mode: BindrMode::Brainstorm,
-            conversation_history: Vec::new(),
+            conversation_history,
+            mode_histories: HashMap::new(),
             current_provider,
             current_model,
+            response_locale,
+            flagged_messages,
+            handoffs,
+            temperature_override: None,
+            max_tokens_override: None,
+            redactor,
+            stream_task: None,
+            reply_model: None,
         }
     }
 
@@ -66,9 +117,9 @@ impl AgentOrchestrator {
     pub async fn continue_conversation(
         &mut self,
         user_message: String,
-    ) -> Result<mpsc::UnboundedReceiver<String>> {
+    ) -> Result<mpsc::UnboundedReceiver<LlmEvent>> {
         // Add user message to history
-        self.add_to_history(ConversationRole::User, user_message.clone());
+        self.add_to_history(ConversationRole::User, user_message, None);
 
         // Build conversation context
         let mut messages = vec![LlmMessage {
@@ -76,7 +127,7 @@ impl AgentOrchestrator {
             content: self.get_system_prompt_for_mode(self.current_mode),
         }];
 
-        // Add conversation history
+        // Add conversation history (already ending with the new user message)
         for entry in &self.conversation_history {
             messages.push(LlmMessage {
                 role: entry.role.to_string(),
@@ -84,12 +135,6 @@ impl AgentOrchestrator {
             });
         }
 
-        // Add current user message
-        messages.push(LlmMessage {
-            role: "user".to_string(),
-            content: user_message,
-        });
-
         let provider_id = if self.current_provider.is_empty() {
             self.config.selected_provider.clone()
         } else {
@@ -102,38 +147,45 @@ impl AgentOrchestrator {
         };
 
         let request = LlmRequest::new(messages, self.current_mode)
-            .with_temperature(0.4)
-            .with_max_tokens(2000)
+            .with_temperature(self.temperature())
+            .with_max_tokens(self.max_tokens())
             .with_provider(provider_id.clone())
             .with_model(model_id.clone());
         let mut llm_rx = self.llm_client.stream_response(request).await?;
         
-        // Convert LLM events to simple string chunks
+        // Forward deltas only: the reply is assembled from them, so also forwarding
+        // ResponseComplete would append the full text a second time
         let (tx, rx) = mpsc::unbounded_channel();
         
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             while let Some(event) = llm_rx.recv().await {
                 match event {
-                    LlmEvent::TextDelta(chunk) => {
-                        let _ = tx.send(chunk);
-                    }
-                    LlmEvent::ResponseComplete(_content) => {
-                        // We've already forwarded incremental chunks; no need to resend the full text
-                    }
-                    LlmEvent::ReasoningDelta(_reasoning) => {
-                        // Optionally forward reasoning content; currently ignored to avoid UX clutter
+                    LlmEvent::TextDelta(_)
+                    | LlmEvent::ReasoningDelta(_)
+                    | LlmEvent::KeyRejected(_)
+                    | LlmEvent::FallbackUsed { .. }
+                    | LlmEvent::Retrying(_)
+                    | LlmEvent::Usage(_) => {
+                        // The conversation dropped its receiver: stop pulling the reply
+                        if tx.send(event).is_err() {
+                            break;
+                        }
                     }
+                    LlmEvent::ResponseComplete(_content) => {}
                     LlmEvent::StreamComplete => {
                         break;
                     }
-                    LlmEvent::Error(error) => {
-                        let _ = tx.send(format!("Error: {}", error));
+                    LlmEvent::Error(_) => {
+                        let _ = tx.send(event);
                         break;
                     }
                 }
             }
         });
         
+        self.stream_task = Some(task.abort_handle());
+        self.reply_model = Some(model_id.clone());
+
         // Store last selections for subsequent requests
         self.current_provider = provider_id;
         self.current_model = model_id;
@@ -141,17 +193,27 @@ impl AgentOrchestrator {
         Ok(rx)
     }
 
+    /// Stop relaying the reply in progress; dropping its channel cancels the provider request
+    pub fn cancel_stream(&mut self) {
+        if let Some(task) = self.stream_task.take() {
+            task.abort();
+        }
+    }
+
     /// Switch to a different mode
     pub async fn switch_mode(&mut self, new_mode: BindrMode) -> Result<()> {
         if new_mode == self.current_mode {
             return Ok(());
         }
 
+        self.record_handoff(new_mode)?;
+
         // Save current conversation state
         self.save_conversation_state().await?;
 
-        // Switch mode
+        // Switch mode, and to its provider and model if it has its own
         self.current_mode = new_mode;
+        (self.current_provider, self.current_model) = self.config.mode_model(new_mode);
 
         // Load conversation state for new mode
         self.load_conversation_state().await?;
@@ -160,35 +222,153 @@ impl AgentOrchestrator {
     }
 
     /// Get the current mode
-    #[allow(dead_code)]
     pub fn current_mode(&self) -> BindrMode {
         self.current_mode
     }
 
+    /// Model the next request goes to
+    pub fn current_model(&self) -> &str {
+        &self.current_model
+    }
+
+    /// Estimate the input tokens a turn with `draft` as the next user message would send
+    pub fn estimate_input_tokens(&self, draft: &str) -> usize {
+        pricing::estimate_tokens(&self.get_system_prompt_for_mode(self.current_mode))
+            + self.conversation_history
+                .iter()
+                .map(|entry| pricing::estimate_tokens(&entry.content))
+                .sum::<usize>()
+            + pricing::estimate_tokens(draft)
+    }
+
     /// Get conversation history
     #[allow(dead_code)]
     pub fn conversation_history(&self) -> &[ConversationEntry] {
         &self.conversation_history
     }
 
-    /// Add an entry to conversation history
-    pub fn add_to_history(&mut self, role: ConversationRole, content: String) {
+    /// Model the latest reply was requested from, fixed when it was sent
+    pub fn reply_model(&self) -> Option<&str> {
+        self.reply_model.as_deref()
+    }
+
+    /// A `[fallback]` provider took the latest reply over, so it is recorded as that model's
+    pub fn reply_fell_back_to(&mut self, model_id: String) {
+        self.reply_model = Some(model_id);
+    }
+
+    /// Add an entry to conversation history, and to the open project's until the next save
+    pub fn add_to_history(&mut self, role: ConversationRole, content: String, model_id: Option<String>) {
+        // Only fails if the session can't be reached, which auto-save reports on its own
+        let _ = self.session_manager.add_conversation_entry(
+            role.clone(),
+            content.clone(),
+            self.current_mode,
+            model_id.clone(),
+        );
         self.conversation_history.push(ConversationEntry {
             mode: self.current_mode,
             role,
             content,
             timestamp: chrono::Utc::now(),
+            model_id,
         });
     }
 
+    /// Save the open project if anything changed since the last save; returns whether it saved
+    pub fn save_if_dirty(&mut self) -> Result<bool> {
+        if !self.session_manager.is_dirty() {
+            return Ok(false);
+        }
+        self.session_manager.save_current_session()?;
+        self.session_manager.save_mode_history(self.current_mode, &self.conversation_history)?;
+        Ok(true)
+    }
+
     /// Update orchestrator configuration and refresh LLM client
     pub fn update_config(&mut self, config: Config) {
         self.llm_client = LlmClient::new(config.clone());
-        self.current_provider = config.selected_provider.clone();
-        self.current_model = config.default_model.clone();
+        (self.current_provider, self.current_model) = config.mode_model(self.current_mode);
+        self.redactor = Redactor::from_config(&config.redaction).ok().flatten();
         self.config = config;
     }
 
+    /// Language the assistant is asked to answer in, if any
+    pub fn response_locale(&self) -> Option<&str> {
+        self.response_locale
+            .as_deref()
+            .or(self.config.ui.response_locale.as_deref())
+    }
+
+    /// Override the response language for this project; `None` restores the configured default
+    pub fn set_response_locale(&mut self, locale: Option<String>) -> Result<()> {
+        self.response_locale = locale.clone();
+        self.session_manager.set_response_locale(locale)
+    }
+
+    /// Temperature for the next request: the /temperature override, else the current mode's
+    pub fn temperature(&self) -> f32 {
+        self.temperature_override
+            .unwrap_or_else(|| self.config.mode_sampling.temperature(self.current_mode))
+    }
+
+    pub fn temperature_override(&self) -> Option<f32> {
+        self.temperature_override
+    }
+
+    /// Override the temperature for every mode this session; `None` returns to the per-mode values
+    pub fn set_temperature_override(&mut self, temperature: Option<f32>) {
+        self.temperature_override = temperature;
+    }
+
+    /// Reply length limit for the next request: the /maxtokens override, else the current mode's
+    pub fn max_tokens(&self) -> u32 {
+        self.max_tokens_override
+            .unwrap_or_else(|| self.config.mode_sampling.max_tokens(self.current_mode))
+    }
+
+    pub fn max_tokens_override(&self) -> Option<u32> {
+        self.max_tokens_override
+    }
+
+    /// Override the reply length limit for every mode this session; `None` returns to the per-mode values
+    pub fn set_max_tokens_override(&mut self, max_tokens: Option<u32>) {
+        self.max_tokens_override = max_tokens;
+    }
+
+    /// Name of the open project, if a session is loaded
+    pub fn project_name(&self) -> Option<&str> {
+        self.session_manager
+            .current_session()
+            .map(|session| session.project_state.name.as_str())
+    }
+
+    /// Save `entries` as the open project's conversation, creating a project first if none is open.
+    ///
+    /// Returns the project's name.
+    pub fn save_conversation(&mut self, entries: Vec<ConversationEntry>) -> Result<String> {
+        if self.session_manager.current_session().is_none() {
+            let name = format!("conversation-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            let path = self.config.project_dir(&name);
+            self.session_manager.create_project(name, path)?;
+        }
+        self.session_manager.set_conversation_history(entries, self.current_mode)?;
+        // Also what the assistant remembers of this mode, so reopening the project restores it
+        self.session_manager.save_mode_history(self.current_mode, &self.conversation_history)?;
+        Ok(self.project_name().unwrap_or_default().to_string())
+    }
+
+    /// Messages flagged for review in this project, oldest first
+    pub fn flagged_messages(&self) -> &[FlaggedMessage] {
+        &self.flagged_messages
+    }
+
+    /// Replace the project's flagged messages and save them
+    pub fn set_flagged_messages(&mut self, flagged: Vec<FlaggedMessage>) -> Result<()> {
+        self.flagged_messages = flagged.clone();
+        self.session_manager.set_flagged_messages(flagged)
+    }
+
     /// Review a tool invocation against the current mode's capabilities
     pub fn review_tool_invocation(&self, invocation: ToolInvocation) -> Result<ToolRequestOutcome> {
         ToolDispatcher::review(self.current_mode, invocation)
@@ -196,7 +376,7 @@ impl AgentOrchestrator {
 
     /// Get system prompt for current mode
     fn get_system_prompt(&self) -> String {
-        self.build_system_prompt(self.current_mode)
+        self.get_system_prompt_for_mode(self.current_mode)
     }
 
     /// Get context from previous modes
@@ -223,8 +403,12 @@ impl AgentOrchestrator {
 
     /// Get brainstorm context summary
     fn get_brainstorm_context(&self) -> Option<String> {
-        // Look for brainstorm entries in conversation history
-        let brainstorm_entries: Vec<_> = self.conversation_history
+        let brainstorm_history = if self.current_mode == BindrMode::Brainstorm {
+            &self.conversation_history
+        } else {
+            self.mode_histories.get(&BindrMode::Brainstorm)?
+        };
+        let brainstorm_entries: Vec<_> = brainstorm_history
             .iter()
             .filter(|entry| matches!(entry.role, ConversationRole::Assistant))
             .collect();
@@ -240,9 +424,9 @@ impl AgentOrchestrator {
             .collect::<Vec<_>>()
             .join(" ");
 
-        // Truncate if too long
-        if summary.len() > 500 {
-            Some(format!("{}...", &summary[..500]))
+        // Truncate if too long, on a character boundary so non-ASCII replies can't split a character
+        if summary.chars().count() > BRAINSTORM_CONTEXT_CHARS {
+            Some(format!("{}...", summary.chars().take(BRAINSTORM_CONTEXT_CHARS).collect::<String>()))
         } else {
             Some(summary)
         }
@@ -262,23 +446,59 @@ impl AgentOrchestrator {
         None
     }
 
-    /// Save conversation state for current mode
+    /// Summarize the current mode for `next` and keep it with the project, replacing the previous handoff into `next`
+    fn record_handoff(&mut self, next: BindrMode) -> Result<()> {
+        let Some(handoff) = ModeHandoff::from_history(
+            self.current_mode,
+            next,
+            self.project_name(),
+            &self.conversation_history,
+        ) else {
+            return Ok(());
+        };
+        self.handoffs.retain(|existing| existing.mode_to != next);
+        self.handoffs.push(handoff);
+        self.session_manager.set_handoffs(self.handoffs.clone())
+    }
+
+    /// Handoff the previous mode left for `mode`, if any
+    pub fn handoff_into(&self, mode: BindrMode) -> Option<&ModeHandoff> {
+        self.handoffs.iter().find(|handoff| handoff.mode_to == mode)
+    }
+
+    /// Set the current mode's conversation aside, writing it to the project when one is open
     async fn save_conversation_state(&mut self) -> Result<()> {
-        // This would save the conversation to the appropriate mode file
-        // For now, we'll just keep it in memory
+        self.session_manager.save_mode_history(self.current_mode, &self.conversation_history)?;
+        let entries = std::mem::take(&mut self.conversation_history);
+        self.mode_histories.insert(self.current_mode, entries);
         Ok(())
     }
 
-    /// Load conversation state for current mode
+    /// Restore the current mode's conversation: from memory if it was visited this run, else from the project
     async fn load_conversation_state(&mut self) -> Result<()> {
-        // This would load the conversation from the appropriate mode file
-        // For now, we'll just keep it in memory
+        self.conversation_history = match self.mode_histories.remove(&self.current_mode) {
+            Some(entries) => entries,
+            None => self.session_manager
+                .load_mode_history(self.current_mode)?
+                .unwrap_or_default(),
+        };
         Ok(())
     }
 
-    /// Process a complete response and add it to history
-    pub fn process_complete_response(&mut self, response: String) {
-        self.add_to_history(ConversationRole::Assistant, response);
+    /// Process a complete response and add it to history, returning it as redacted for display
+    pub fn process_complete_response(&mut self, response: String) -> String {
+        let response = self.redact(&response).into_owned();
+        let model_id = self.reply_model.clone();
+        self.add_to_history(ConversationRole::Assistant, response.clone(), model_id);
+        response
+    }
+
+    /// Apply `[redaction]` to assistant output; unchanged when redaction is off
+    pub fn redact<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        match &self.redactor {
+            Some(redactor) => redactor.redact(text),
+            None => std::borrow::Cow::Borrowed(text),
+        }
     }
 
     /// Get project state summary
@@ -294,6 +514,9 @@ impl AgentOrchestrator {
             conversation_history: self.conversation_history.clone(),
             conversation_count: self.conversation_history.len(),
             last_activity: chrono::Utc::now(),
+            response_locale: self.response_locale.clone(),
+            flagged_messages: self.flagged_messages.clone(),
+            handoffs: self.handoffs.clone(),
         }
     }
 
@@ -332,20 +555,39 @@ impl AgentOrchestrator {
         }
     }
 
-    /// Get system prompt for a specific mode
+    /// System prompt for a mode: the canonical prompt from `prompts`, then project instructions,
+    /// earlier context, tool instructions, and the response language
     fn get_system_prompt_for_mode(&self, mode: BindrMode) -> String {
-        self.build_system_prompt(mode)
-    }
-
-    fn build_system_prompt(&self, mode: BindrMode) -> String {
         let mut prompt = prompts::mode_prompt(mode).to_string();
 
+        if let Some(instructions) = &self.config.user_instructions {
+            prompt.push_str("\n\nProject instructions from AGENTS.md:\n");
+            prompt.push_str(instructions.trim());
+        }
+
         let context = self.get_mode_context();
         if !context.is_empty() {
             prompt.push_str("\n\nContext from previous work:\n");
             prompt.push_str(&context);
         }
 
+        if let Some(handoff) = self.handoff_into(mode) {
+            prompt.push_str("\n\n");
+            prompt.push_str(&handoff.prompt_section());
+        }
+
+        if let Ok(capabilities) = ToolDispatcher::capabilities_for(mode)
+            && let Some(instructions) = tools::calls::instructions(&capabilities.allowed_tools)
+        {
+            prompt.push_str("\n\n");
+            prompt.push_str(&instructions);
+        }
+
+        if let Some(locale) = self.response_locale() {
+            prompt.push_str("\n\n");
+            prompt.push_str(&prompts::locale_instruction(locale));
+        }
+
         prompt
     }
 
@@ -420,3 +662,199 @@ impl AgentManager {
         self.orchestrator.review_tool_invocation(invocation)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_provider::{openai_reply, MockProvider};
+
+    fn orchestrator() -> AgentOrchestrator {
+        let config = Config::default();
+        AgentOrchestrator::new(config.clone(), SessionManager::new(config))
+    }
+
+    #[test]
+    fn brainstorm_context_truncates_on_a_character_boundary() {
+        let mut orchestrator = orchestrator();
+        // Two-byte characters put byte 500 in the middle of one
+        orchestrator.add_to_history(ConversationRole::Assistant, "é".repeat(300) + &"ü".repeat(300), None);
+
+        let context = orchestrator.get_brainstorm_context().unwrap();
+        assert_eq!(context.chars().count(), BRAINSTORM_CONTEXT_CHARS + 3);
+        assert!(context.starts_with(&"é".repeat(300)));
+        assert!(context.ends_with("ü..."));
+    }
+
+    #[test]
+    fn short_brainstorm_context_is_kept_whole() {
+        let mut orchestrator = orchestrator();
+        orchestrator.add_to_history(ConversationRole::User, "ideas?".to_string(), None);
+        orchestrator.add_to_history(ConversationRole::Assistant, "A garden planner".to_string(), None);
+
+        assert_eq!(orchestrator.get_brainstorm_context().as_deref(), Some("A garden planner"));
+    }
+
+    /// Orchestrator with `project` open on a throwaway Bindr home
+    fn orchestrator_with_project(home: &std::path::Path, project: &str) -> AgentOrchestrator {
+        let config = Config::load_from(home.to_path_buf()).unwrap();
+        let mut session_manager = SessionManager::new(config.clone());
+        session_manager.load_sessions().unwrap();
+        if session_manager.open_project(project).is_err() {
+            session_manager.create_project(project.to_string(), config.project_dir(project)).unwrap();
+        }
+        AgentOrchestrator::new(config, session_manager)
+    }
+
+    fn history(orchestrator: &AgentOrchestrator) -> Vec<&str> {
+        orchestrator.conversation_history().iter().map(|entry| entry.content.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn switching_back_restores_a_modes_history() {
+        let home = tempfile::tempdir().unwrap();
+        let mut orchestrator = orchestrator_with_project(home.path(), "garden");
+        orchestrator.add_to_history(ConversationRole::User, "ideas?".to_string(), None);
+        orchestrator.add_to_history(ConversationRole::Assistant, "A garden planner".to_string(), None);
+
+        orchestrator.switch_mode(BindrMode::Plan).await.unwrap();
+        assert!(history(&orchestrator).is_empty());
+        orchestrator.add_to_history(ConversationRole::User, "steps?".to_string(), None);
+
+        orchestrator.switch_mode(BindrMode::Brainstorm).await.unwrap();
+        assert_eq!(history(&orchestrator), ["ideas?", "A garden planner"]);
+        orchestrator.switch_mode(BindrMode::Plan).await.unwrap();
+        assert_eq!(history(&orchestrator), ["steps?"]);
+    }
+
+    #[tokio::test]
+    async fn reopened_project_restores_each_modes_history() {
+        let home = tempfile::tempdir().unwrap();
+        let mut orchestrator = orchestrator_with_project(home.path(), "garden");
+        orchestrator.add_to_history(ConversationRole::User, "ideas?".to_string(), None);
+        orchestrator.switch_mode(BindrMode::Plan).await.unwrap();
+        orchestrator.add_to_history(ConversationRole::User, "steps?".to_string(), None);
+        orchestrator.switch_mode(BindrMode::Brainstorm).await.unwrap();
+
+        let mut reopened = orchestrator_with_project(home.path(), "garden");
+        assert_eq!(history(&reopened), ["ideas?"]);
+        reopened.switch_mode(BindrMode::Plan).await.unwrap();
+        assert_eq!(history(&reopened), ["steps?"]);
+    }
+
+    /// Orchestrator whose OpenAI provider is `server`, defaulting to gpt-4o-mini, after `configure` has its say
+    fn orchestrator_against(
+        home: &std::path::Path,
+        server: &MockProvider,
+        configure: impl FnOnce(&mut Config),
+    ) -> AgentOrchestrator {
+        let mut config = Config::load_from(home.to_path_buf()).unwrap();
+        config.switch_provider("openai");
+        config.default_model = "gpt-4o-mini".to_string();
+        config.model_providers.get_mut("openai").unwrap().base_url = server.base_url.clone();
+        config.set_api_key("openai".to_string(), "test-key".to_string());
+        configure(&mut config);
+        AgentOrchestrator::new(config.clone(), SessionManager::new(config))
+    }
+
+    /// Send `message`, wait for the reply to finish, and return the body of the request it made
+    async fn send(orchestrator: &mut AgentOrchestrator, server: &MockProvider, message: &str) -> serde_json::Value {
+        let mut rx = orchestrator.continue_conversation(message.to_string()).await.unwrap();
+        while rx.recv().await.is_some() {}
+        server.requests().last().unwrap().body.clone()
+    }
+
+    #[tokio::test]
+    async fn mode_default_model_is_used_for_the_next_request() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Sure"))]).await;
+        let mut orchestrator = orchestrator_against(home.path(), &server, |config| {
+            config.mode_sampling.for_mode_mut(BindrMode::Execute).model = Some("gpt-4.1".to_string());
+        });
+
+        assert_eq!(send(&mut orchestrator, &server, "ideas?").await["model"], "gpt-4o-mini");
+
+        orchestrator.switch_mode(BindrMode::Execute).await.unwrap();
+        assert_eq!(orchestrator.current_model(), "gpt-4.1");
+        assert_eq!(send(&mut orchestrator, &server, "build it").await["model"], "gpt-4.1");
+
+        // Modes without their own model go back to the global default
+        orchestrator.switch_mode(BindrMode::Plan).await.unwrap();
+        assert_eq!(send(&mut orchestrator, &server, "steps?").await["model"], "gpt-4o-mini");
+    }
+
+    #[test]
+    fn agents_md_appears_in_the_system_prompt() {
+        let home = tempfile::tempdir().unwrap();
+        let workspace = home.path().join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        std::fs::write(workspace.join("AGENTS.md"), "Always use tabs for indentation.\n").unwrap();
+
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+        config.set_workspace(&workspace).unwrap();
+        let orchestrator = AgentOrchestrator::new(config.clone(), SessionManager::new(config));
+
+        for mode in BindrMode::ALL {
+            let prompt = orchestrator.get_system_prompt_for_mode(mode);
+            assert!(
+                prompt.contains("Project instructions from AGENTS.md:\nAlways use tabs for indentation."),
+                "{:?}",
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn global_agents_md_is_used_when_the_workspace_has_none() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("AGENTS.md"), "Answer briefly.").unwrap();
+
+        let config = Config::load_from(home.path().to_path_buf()).unwrap();
+        assert_eq!(config.user_instructions.as_deref(), Some("Answer briefly."));
+        let orchestrator = AgentOrchestrator::new(config.clone(), SessionManager::new(config));
+        assert!(orchestrator.get_system_prompt_for_mode(BindrMode::Plan).contains("AGENTS.md:\nAnswer briefly."));
+    }
+
+    #[tokio::test]
+    async fn brainstorm_request_carries_the_file_restriction() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Sure"))]).await;
+        let mut orchestrator = orchestrator_against(home.path(), &server, |_| {});
+
+        let prompt = orchestrator.get_system_prompt_for_mode(BindrMode::Brainstorm);
+        assert!(prompt.starts_with(prompts::mode_prompt(BindrMode::Brainstorm)));
+        assert!(prompt.contains("Do **not** create, modify, or delete files."));
+
+        let body = send(&mut orchestrator, &server, "ideas?").await;
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert!(body["messages"][0]["content"].as_str().unwrap().contains("create, modify, or delete files"));
+    }
+
+    #[test]
+    fn each_mode_gets_its_own_canonical_prompt() {
+        let orchestrator = orchestrator();
+        for mode in BindrMode::ALL {
+            assert!(orchestrator.get_system_prompt_for_mode(mode).starts_with(prompts::mode_prompt(mode)), "{:?}", mode);
+        }
+    }
+
+    #[tokio::test]
+    async fn brainstorm_to_plan_stores_a_handoff_for_the_plan_prompt() {
+        let home = tempfile::tempdir().unwrap();
+        let mut orchestrator = orchestrator_with_project(home.path(), "garden");
+        orchestrator.add_to_history(ConversationRole::User, "An app for plants".to_string(), None);
+        orchestrator.add_to_history(ConversationRole::Assistant, "Features:\n- Watering reminders".to_string(), None);
+
+        orchestrator.switch_mode(BindrMode::Plan).await.unwrap();
+
+        let handoff = orchestrator.handoff_into(BindrMode::Plan).unwrap().clone();
+        assert_eq!(handoff.mode_from, BindrMode::Brainstorm);
+        assert_eq!(handoff.summary.project_name, "garden");
+        assert_eq!(handoff.summary.key_features, ["Watering reminders"]);
+        assert!(orchestrator.get_system_prompt_for_mode(BindrMode::Plan).contains(&handoff.prompt_section()));
+        assert!(!orchestrator.get_system_prompt_for_mode(BindrMode::Execute).contains("Handoff from"));
+
+        // Kept with the project
+        let reopened = orchestrator_with_project(home.path(), "garden");
+        assert_eq!(reopened.handoff_into(BindrMode::Plan), Some(&handoff));
+    }
+}
@@ -1,15 +1,97 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
 use dirs;
 
+use crate::events::BindrMode;
+use crate::persist::{is_unwritable, probe_writable, write_atomic, write_if_missing, FileLock};
+use crate::pricing::Rates;
+use crate::prompts;
+use crate::secrets;
+use crate::redact::Redactor;
+use crate::usage::DailyUsage;
+
 const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api";
 const LEGACY_OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
 
+/// Commented `config.toml` written by `bindr init`; every setting is left at its default
+const STARTER_CONFIG: &str = r#"# Bindr configuration
+#
+# Every setting below is commented out and shows its default. Uncomment what you
+# want to change. Bindr rewrites this file when you change settings in the app
+# (picking a model, saving a key), and comments are not kept when it does.
+
+# Provider and model used for new conversations. Providers: openai, anthropic,
+# google, xai, openrouter, mistral.
+# selected_provider = "openai"
+# default_model = "gpt-5"
+
+# API keys. You can also leave them out of this file and export the provider's
+# variable instead: OPENAI_API_KEY, ANTHROPIC_API_KEY, GOOGLE_API_KEY,
+# XAI_API_KEY, OPENROUTER_API_KEY, or MISTRAL_API_KEY.
+# [api_keys]
+# openai = "sk-..."
+#
+# Or point a provider at a file holding only its key:
+# [model_providers.openai]
+# api_key_file = "~/.secrets/openai"
+
+# Save API keys encrypted with the passphrase in BINDR_PASSPHRASE instead of in
+# plain text. Keys already in [api_keys] are encrypted on the next save.
+# encrypt_api_keys = true
+
+[ui]
+# density = "comfortable"          # or "compact"; Ctrl+L switches for the session
+# submit_key = "enter"             # "ctrl-enter", "alt-enter", or "ctrl-s" make Enter insert a newline
+# reduced_motion = false           # no blinking cursor or animated dots
+# restore_last_session = true      # start in the mode you last used
+# terminal_title = true            # show project and mode in the window title
+# require_api_key = false          # hide the free tier and insist on your own key
+# auto_save_interval = 30          # seconds between saves of an open project; 0 turns it off
+# max_history_lines = 100          # messages kept in the conversation view (at least 10)
+# request_timeout_secs = 60        # give up on a provider that stays silent this long
+# request_retries = 2               # resend after a 429 or 5xx, waiting longer each time
+# retry_base_delay_ms = 1000
+# model_catalog_ttl_secs = 300     # how long a fetched OpenRouter model list is reused
+# debug_log = false                # trace provider requests to logs/requests.log (or set BINDR_LOG=1)
+# daily_message_limit = 100        # free-tier messages per day without an API key
+# syntax_highlighting = true       # color code blocks in replies; turn off for limited terminals
+# show_reasoning = true            # show model thinking while it streams, folded under the reply after
+# response_locale = "de"           # language the assistant answers in
+# confirm_cost_above = 0.50        # ask before sending a turn estimated above this many USD
+# auto_continue_max_steps = 10     # most steps one /auto run may take
+
+# Sampling temperature per mode (defaults: brainstorm 0.9, plan 0.6, execute 0.2, document 0.4),
+# reply length limit in tokens (default 2000), and the provider and model the mode talks to
+# (default: the ones selected in the app)
+# [mode_sampling.execute]
+# temperature = 0.2
+# max_tokens = 4000
+# provider = "anthropic"
+# model = "claude-3-5-sonnet-20241022"
+
+# Providers to try, in order, when the selected one fails
+# [[fallback.providers]]
+# provider = "openrouter"
+# model = "openai/gpt-4o-mini"
+
+# Mask secrets in assistant output before it is shown or saved
+# [redaction]
+# enabled = false
+# builtin_detectors = true
+# patterns = []
+
+# Stop replies that repeat the same text over and over
+# [loop_guard]
+# enabled = true
+# min_repeats = 10
+# max_tool_round_trips = 10    # automatic tool calls in a row before Bindr waits for you
+"#;
+
 /// Main application configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Selected provider
     pub selected_provider: String,
@@ -17,6 +99,9 @@ pub struct Config {
     /// API keys for different providers
     pub api_keys: HashMap<String, String>,
     
+    /// Save `api_keys` encrypted with the `BINDR_PASSPHRASE` passphrase
+    pub encrypt_api_keys: bool,
+    
     /// Default model to use
     pub default_model: String,
     
@@ -37,10 +122,56 @@ pub struct Config {
     
     /// UI preferences
     pub ui: UiConfig,
+    
+    /// Favorite model ids, keyed by provider id
+    pub favorite_models: HashMap<String, Vec<String>>,
+    
+    /// Masking of secrets in assistant output
+    pub redaction: RedactionConfig,
+    
+    /// Providers to try, in order, when the selected one fails
+    pub fallback: FallbackConfig,
+    
+    /// Per-mode sampling overrides
+    pub mode_sampling: ModeSamplingConfig,
+    
+    /// Stopping replies that get stuck repeating themselves
+    pub loop_guard: LoopGuardConfig,
+
+    /// Providers whose key this instance removed, so saving doesn't restore them from disk
+    #[serde(skip)]
+    removed_api_keys: HashSet<String>,
+}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let api_keys: HashMap<&String, String> = self.api_keys
+            .iter()
+            .map(|(provider, key)| (provider, mask_key(key)))
+            .collect();
+        f.debug_struct("Config")
+            .field("selected_provider", &self.selected_provider)
+            .field("api_keys", &api_keys)
+            .field("encrypt_api_keys", &self.encrypt_api_keys)
+            .field("default_model", &self.default_model)
+            .field("model_providers", &self.model_providers)
+            .field("user_instructions", &self.user_instructions)
+            .field("bindr_home", &self.bindr_home)
+            .field("projects_dir", &self.projects_dir)
+            .field("cwd", &self.cwd)
+            .field("ui", &self.ui)
+            .field("favorite_models", &self.favorite_models)
+            .field("redaction", &self.redaction)
+            .field("fallback", &self.fallback)
+            .field("mode_sampling", &self.mode_sampling)
+            .field("loop_guard", &self.loop_guard)
+            .field("removed_api_keys", &self.removed_api_keys)
+            .finish()
+    }
 }
 
 /// Configuration file structure for TOML
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ConfigToml {
     /// Selected provider
     pub selected_provider: Option<String>,
@@ -51,20 +182,238 @@ pub struct ConfigToml {
     /// API keys for different providers
     pub api_keys: Option<HashMap<String, String>>,
     
+    /// Save API keys encrypted instead of in `api_keys`
+    pub encrypt_api_keys: Option<bool>,
+    
+    /// API keys encrypted with the `BINDR_PASSPHRASE` passphrase
+    pub encrypted_api_keys: Option<String>,
+    
     /// Model provider configuration
     pub model_providers: Option<HashMap<String, ModelProviderToml>>,
     
     /// UI preferences
     pub ui: Option<UiConfigToml>,
+    
+    /// Favorite model ids, keyed by provider id
+    pub favorite_models: Option<HashMap<String, Vec<String>>>,
+    
+    /// Masking of secrets in assistant output
+    pub redaction: Option<RedactionConfig>,
+    
+    /// Providers to try, in order, when the selected one fails
+    pub fallback: Option<FallbackConfig>,
+    
+    /// Per-mode sampling overrides
+    pub mode_sampling: Option<ModeSamplingConfig>,
+    
+    /// Stopping replies that get stuck repeating themselves
+    pub loop_guard: Option<LoopGuardConfig>,
+}
+
+impl std::fmt::Debug for ConfigToml {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let api_keys: Option<HashMap<&String, String>> = self.api_keys.as_ref().map(|keys| {
+            keys.iter()
+                .map(|(provider, key)| (provider, mask_key(key)))
+                .collect()
+        });
+        f.debug_struct("ConfigToml")
+            .field("selected_provider", &self.selected_provider)
+            .field("default_model", &self.default_model)
+            .field("api_keys", &api_keys)
+            .field("encrypt_api_keys", &self.encrypt_api_keys)
+            .field("encrypted_api_keys", &self.encrypted_api_keys)
+            .field("model_providers", &self.model_providers)
+            .field("ui", &self.ui)
+            .field("favorite_models", &self.favorite_models)
+            .field("redaction", &self.redaction)
+            .field("fallback", &self.fallback)
+            .field("mode_sampling", &self.mode_sampling)
+            .field("loop_guard", &self.loop_guard)
+            .finish()
+    }
+}
+
+/// `[loop_guard]`: stop a reply once the same text repeats back to back too many times
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoopGuardConfig {
+    /// On by default; turn off if your work legitimately repeats long runs of text
+    pub enabled: bool,
+    /// Back-to-back copies of a span that count as a loop
+    pub min_repeats: usize,
+    /// Tool results sent back to the model in one turn, without the user stepping in, before it stops
+    pub max_tool_round_trips: usize,
+}
+
+/// Automatic tool round-trips allowed per turn unless `[loop_guard]` sets `max_tool_round_trips`
+pub const DEFAULT_MAX_TOOL_ROUND_TRIPS: usize = 10;
+
+impl Default for LoopGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_repeats: 10,
+            max_tool_round_trips: DEFAULT_MAX_TOOL_ROUND_TRIPS,
+        }
+    }
+}
+
+/// `[mode_sampling]`: sampling settings per mode, e.g. `[mode_sampling.execute] temperature = 0.1`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModeSamplingConfig {
+    pub brainstorm: ModeSampling,
+    pub plan: ModeSampling,
+    pub execute: ModeSampling,
+    pub document: ModeSampling,
+}
+
+/// Sampling settings for one mode; unset fields use the mode's built-in default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModeSampling {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Longest reply, in tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Provider id this mode talks to instead of the selected one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model this mode uses instead of the default one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Reply length limit for modes that don't set `max_tokens`
+pub const DEFAULT_MAX_TOKENS: u32 = 2000;
+
+/// Largest `max_tokens` accepted from config or /maxtokens
+pub const MAX_TOKENS_LIMIT: u32 = 200_000;
+
+impl ModeSamplingConfig {
+    pub fn for_mode(&self, mode: BindrMode) -> &ModeSampling {
+        match mode {
+            BindrMode::Brainstorm => &self.brainstorm,
+            BindrMode::Plan => &self.plan,
+            BindrMode::Execute => &self.execute,
+            BindrMode::Document => &self.document,
+        }
+    }
+
+    pub fn for_mode_mut(&mut self, mode: BindrMode) -> &mut ModeSampling {
+        match mode {
+            BindrMode::Brainstorm => &mut self.brainstorm,
+            BindrMode::Plan => &mut self.plan,
+            BindrMode::Execute => &mut self.execute,
+            BindrMode::Document => &mut self.document,
+        }
+    }
+
+    /// Configured temperature for a mode, or its built-in default
+    pub fn temperature(&self, mode: BindrMode) -> f32 {
+        self.for_mode(mode)
+            .temperature
+            .unwrap_or_else(|| mode.default_temperature())
+    }
+
+    /// Configured reply length limit for a mode, or the default
+    pub fn max_tokens(&self, mode: BindrMode) -> u32 {
+        self.for_mode(mode).max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for mode in BindrMode::ALL {
+            if let Some(max_tokens) = self.for_mode(mode).max_tokens
+                && !(1..=MAX_TOKENS_LIMIT).contains(&max_tokens)
+            {
+                anyhow::bail!(
+                    "{} max_tokens must be between 1 and {}, got {}",
+                    mode.display_name(),
+                    MAX_TOKENS_LIMIT,
+                    max_tokens
+                );
+            }
+            if let Some(temperature) = self.for_mode(mode).temperature
+                && !(0.0..=2.0).contains(&temperature)
+            {
+                anyhow::bail!(
+                    "{} temperature must be between 0 and 2, got {}",
+                    mode.display_name(),
+                    temperature
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[fallback]`: providers that take over a turn the selected provider couldn't serve
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FallbackConfig {
+    /// Tried in order; entries without an API key are skipped
+    pub providers: Vec<FallbackTarget>,
+}
+
+/// One provider/model pair in `[fallback].providers`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackTarget {
+    pub provider: String,
+    pub model: String,
+}
+
+/// `[redaction]`: patterns masked in assistant output before it is displayed, saved, or exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Off by default
+    pub enabled: bool,
+    /// Also mask AWS keys, private keys, API tokens, and email addresses
+    pub builtin_detectors: bool,
+    /// Extra regular expressions to mask
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            builtin_detectors: true,
+            patterns: Vec::new(),
+        }
+    }
 }
 
 /// Model provider configuration for TOML
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ModelProviderToml {
+    /// Built-in providers may leave out everything but the settings they change, e.g. `api_key_file`
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub base_url: String,
     pub api_key_env: Option<String>,
+    pub api_key_file: Option<PathBuf>,
+    #[serde(default)]
     pub models: Vec<ModelInfoToml>,
+    pub extra_headers: Option<HashMap<String, String>>,
+    pub extra_query: Option<HashMap<String, String>>,
+}
+
+impl std::fmt::Debug for ModelProviderToml {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelProviderToml")
+            .field("name", &self.name)
+            .field("base_url", &self.base_url)
+            .field("api_key_env", &self.api_key_env)
+            .field("api_key_file", &self.api_key_file)
+            .field("models", &self.models)
+            .field("extra_headers", &self.extra_headers.as_ref().map(redact_map))
+            .field("extra_query", &self.extra_query.as_ref().map(redact_map))
+            .finish()
+    }
 }
 
 /// Model information for TOML
@@ -73,23 +422,154 @@ pub struct ModelInfoToml {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    pub is_premium: Option<bool>,
+    pub input_cost: Option<f64>,
+    pub output_cost: Option<f64>,
 }
 
 /// UI configuration for TOML
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiConfigToml {
     pub theme: Option<String>,
+    pub show_usage_counter: Option<bool>,
+    /// Older name of `show_usage_counter`, still read so existing configs keep their setting
     pub show_emojis: Option<bool>,
     pub max_history_lines: Option<usize>,
+    pub greetings: Option<HashMap<String, String>>,
+    pub reduced_motion: Option<bool>,
+    pub restore_last_session: Option<bool>,
+    pub confirm_cost_above: Option<f64>,
+    pub density: Option<Density>,
+    pub response_locale: Option<String>,
+    pub auto_continue_max_steps: Option<usize>,
+    pub submit_key: Option<SubmitKey>,
+    pub terminal_title: Option<bool>,
+    pub require_api_key: Option<bool>,
+    pub syntax_highlighting: Option<bool>,
+    pub show_reasoning: Option<bool>,
+    pub auto_save_interval: Option<u64>,
+    pub daily_message_limit: Option<u32>,
+    pub request_timeout_secs: Option<u64>,
+    pub request_retries: Option<u32>,
+    pub retry_base_delay_ms: Option<u64>,
+    pub model_catalog_ttl_secs: Option<u64>,
+    pub debug_log: Option<bool>,
 }
 
 /// Model provider configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ModelProvider {
     pub name: String,
     pub base_url: String,
     pub api_key_env: Option<String>,
+    /// File whose contents are the API key; `~/` and relative paths resolve from the home and Bindr home
+    #[serde(default)]
+    pub api_key_file: Option<PathBuf>,
     pub models: Vec<ModelInfo>,
+    /// Additional HTTP headers sent with every request to this provider
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+    /// Additional query parameters appended to every request URL
+    #[serde(default)]
+    pub extra_query: HashMap<String, String>,
+}
+
+impl std::fmt::Debug for ModelProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelProvider")
+            .field("name", &self.name)
+            .field("base_url", &self.base_url)
+            .field("api_key_env", &self.api_key_env)
+            .field("api_key_file", &self.api_key_file)
+            .field("models", &self.models)
+            .field("extra_headers", &redact_map(&self.extra_headers))
+            .field("extra_query", &redact_map(&self.extra_query))
+            .finish()
+    }
+}
+
+impl ModelProvider {
+    /// URL of a `/v1` endpoint, whether or not `base_url` already ends in `/v1`
+    pub fn v1_url(&self, endpoint: &str) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        format!("{}/v1/{}", base, endpoint)
+    }
+
+    /// OpenAI-compatible chat completions endpoint
+    pub fn chat_completions_url(&self) -> String {
+        self.v1_url("chat/completions")
+    }
+}
+
+/// Config id for a custom provider's display name, e.g. `my-ollama` for "My Ollama"
+fn provider_id_for(name: &str) -> String {
+    let lowered: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    lowered.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+/// Why `id` can't be an OpenRouter model id, if it doesn't have the `vendor/model[:tag]` shape
+pub fn openrouter_model_id_problem(id: &str) -> Option<String> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    let (name, tag) = match id.split_once(':') {
+        Some((name, tag)) => (name, Some(tag)),
+        None => (id, None),
+    };
+    let Some((vendor, model)) = name.split_once('/') else {
+        return Some(format!("'{}' needs a vendor prefix, like openai/gpt-4o or meta-llama/llama-3.1-8b-instruct.", id));
+    };
+    if !valid_part(vendor) || !valid_part(model) {
+        return Some(format!(
+            "'{}' isn't a model id: use vendor/model with letters, digits, '-', '_', or '.' only.",
+            id
+        ));
+    }
+    if let Some(tag) = tag
+        && !valid_part(tag)
+    {
+        return Some(format!("'{}' has an empty or malformed tag after ':', e.g. :free or :beta.", id));
+    }
+    None
+}
+
+/// Whether a header or query parameter name looks like it carries a credential
+pub fn is_secret_key(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["auth", "key", "token", "secret", "password", "signature"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Copy of a header/query map with secret-looking values masked for display
+pub fn redact_map(map: &HashMap<String, String>) -> HashMap<String, String> {
+    map.iter()
+        .map(|(name, value)| {
+            let shown = if is_secret_key(name) {
+                "<redacted>".to_string()
+            } else {
+                value.clone()
+            };
+            (name.clone(), shown)
+        })
+        .collect()
+}
+
+/// An API key shown by its last four characters only, e.g. `****a1b2`
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    // Short keys would give too much of themselves away
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let suffix: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", suffix)
 }
 
 /// Model information
@@ -99,6 +579,10 @@ pub struct ModelInfo {
     pub name: String,
     pub description: String,
     pub is_premium: bool,
+    /// USD per million input tokens, overriding Bindr's built-in price list
+    pub input_cost: Option<f64>,
+    /// USD per million output tokens
+    pub output_cost: Option<f64>,
 }
 
 /// UI configuration
@@ -106,7 +590,120 @@ pub struct ModelInfo {
 pub struct UiConfig {
     pub theme: String,
     pub show_usage_counter: bool,
-    pub auto_save_interval: u64, // seconds
+    /// Seconds between automatic saves of an open project with unsaved changes; 0 turns it off
+    pub auto_save_interval: u64,
+    /// Most messages the conversation view keeps; older ones drop off the top
+    pub max_history_lines: usize,
+    /// Per-mode greeting overrides keyed by lowercase mode name
+    pub greetings: HashMap<String, String>,
+    /// Replace blinking cursors and animated indicators with static output
+    pub reduced_motion: bool,
+    /// Start new conversations in the mode that was active when Bindr last exited
+    pub restore_last_session: bool,
+    /// Ask for confirmation before sending a turn estimated to cost more than this many USD
+    pub confirm_cost_above: Option<f64>,
+    /// How much vertical space the conversation history spends per message; Ctrl+L switches it for the session
+    pub density: Density,
+    /// Language the assistant answers in (e.g. "de", "pt-BR"); mode prompts stay in English
+    pub response_locale: Option<String>,
+    /// Most checklist steps one /auto run may take before handing control back
+    pub auto_continue_max_steps: usize,
+    /// Key that sends a message; with anything but `enter`, Enter inserts a newline
+    pub submit_key: SubmitKey,
+    /// Show the project, mode, and streaming state in the terminal's window title
+    pub terminal_title: bool,
+    /// Hide free-tier messaging and don't start a conversation until the selected provider has a key
+    pub require_api_key: bool,
+    /// Color keywords, strings, and comments in code blocks of assistant replies
+    pub syntax_highlighting: bool,
+    /// Show the thinking some models stream ahead of a reply, collapsed once the reply is done
+    pub show_reasoning: bool,
+    /// Free-tier messages allowed per day without an API key
+    pub daily_message_limit: u32,
+    /// Seconds to wait for a provider to connect, and between events of a streamed reply
+    pub request_timeout_secs: u64,
+    /// Times a request turned away with 429 or a 5xx is sent again before giving up
+    pub request_retries: u32,
+    /// Milliseconds before the first retry, doubled for each one after, unless the provider sends `Retry-After`
+    pub retry_base_delay_ms: u64,
+    /// Seconds a fetched OpenRouter model list is reused before asking OpenRouter again
+    pub model_catalog_ttl_secs: u64,
+    /// Trace provider requests to `logs/requests.log` under the Bindr home, without keys or message text
+    pub debug_log: bool,
+}
+
+/// Key combination that sends the composer's contents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubmitKey {
+    /// Enter sends, Shift+Enter inserts a newline
+    #[default]
+    Enter,
+    /// Enter inserts a newline, Ctrl+Enter sends
+    CtrlEnter,
+    /// Enter inserts a newline, Alt+Enter sends
+    AltEnter,
+    /// Enter inserts a newline, Ctrl+S sends
+    CtrlS,
+}
+
+impl SubmitKey {
+    /// Short description for on-screen hints
+    pub fn hint(self) -> &'static str {
+        match self {
+            SubmitKey::Enter => "Press Enter to send, Shift+Enter for new line.",
+            SubmitKey::CtrlEnter => "Press Ctrl+Enter to send, Enter for new line.",
+            SubmitKey::AltEnter => "Press Alt+Enter to send, Enter for new line.",
+            SubmitKey::CtrlS => "Press Ctrl+S to send, Enter for new line.",
+        }
+    }
+}
+
+/// Default seconds to wait on a quiet provider
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Default retries for a busy provider
+pub const DEFAULT_REQUEST_RETRIES: u32 = 2;
+
+/// Default pause before the first retry
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 1000;
+
+/// Default lifetime of a fetched model catalog
+pub const DEFAULT_MODEL_CATALOG_TTL_SECS: u64 = 300;
+
+/// Default free-tier messages per day
+pub const DEFAULT_DAILY_MESSAGE_LIMIT: u32 = 100;
+
+/// Default seconds between auto-saves
+pub const DEFAULT_AUTO_SAVE_INTERVAL: u64 = 30;
+
+/// Default number of messages kept in the conversation view
+pub const DEFAULT_MAX_HISTORY_LINES: usize = 100;
+
+/// Fewest messages the conversation view may be limited to, so a reply never scrolls off as it lands
+pub const MIN_HISTORY_LINES: usize = 10;
+
+/// Default cap on /auto steps per run
+pub const DEFAULT_AUTO_CONTINUE_MAX_STEPS: usize = 10;
+
+/// Conversation history layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// Decorated header line and a blank line between messages
+    #[default]
+    Comfortable,
+    /// Inline prefix and no spacing between messages
+    Compact,
+}
+
+impl Density {
+    pub fn toggled(self) -> Self {
+        match self {
+            Density::Comfortable => Density::Compact,
+            Density::Compact => Density::Comfortable,
+        }
+    }
 }
 
 impl Default for Config {
@@ -122,38 +719,51 @@ impl Default for Config {
             name: "OpenAI".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key_env: Some("OPENAI_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "gpt-5".to_string(),
                     name: "GPT-5".to_string(),
                     description: "Latest flagship model with advanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-5-codex".to_string(),
                     name: "GPT-5 Codex".to_string(),
                     description: "Specialized for code generation and analysis".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-4o".to_string(),
                     name: "GPT-4o".to_string(),
                     description: "Multimodal model with vision capabilities".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-4o-mini".to_string(),
                     name: "GPT-4o Mini".to_string(),
                     description: "Fast and cost-effective".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-3.5-turbo".to_string(),
                     name: "GPT-3.5 Turbo".to_string(),
                     description: "Free tier model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // Anthropic
@@ -161,32 +771,43 @@ impl Default for Config {
             name: "Anthropic".to_string(),
             base_url: "https://api.anthropic.com".to_string(),
             api_key_env: Some("ANTHROPIC_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "claude-3-5-sonnet-4.5".to_string(),
                     name: "Claude Sonnet 4.5".to_string(),
                     description: "Latest Claude with enhanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "claude-3-opus-4".to_string(),
                     name: "Claude Opus 4".to_string(),
                     description: "Most powerful Claude model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "claude-3-5-sonnet-20241022".to_string(),
                     name: "Claude 3.5 Sonnet".to_string(),
                     description: "Previous generation flagship".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "claude-3-5-haiku-20241022".to_string(),
                     name: "Claude 3.5 Haiku".to_string(),
                     description: "Fast and efficient".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // Google
@@ -194,20 +815,27 @@ impl Default for Config {
             name: "Google".to_string(),
             base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
             api_key_env: Some("GOOGLE_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "gemini-2.5-pro".to_string(),
                     name: "Gemini 2.5 Pro".to_string(),
                     description: "Latest flagship with massive context".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gemini-2.5-flash".to_string(),
                     name: "Gemini 2.5 Flash".to_string(),
                     description: "Fast and efficient latest model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // xAI
@@ -215,26 +843,35 @@ impl Default for Config {
             name: "xAI".to_string(),
             base_url: "https://api.x.ai/v1".to_string(),
             api_key_env: Some("XAI_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "grok-4".to_string(),
                     name: "Grok-4".to_string(),
                     description: "Latest Grok with advanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "grok-3".to_string(),
                     name: "Grok-3".to_string(),
                     description: "Previous generation flagship".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "grok-beta".to_string(),
                     name: "Grok Beta".to_string(),
                     description: "Experimental Grok model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // OpenRouter (aggregator)
@@ -242,68 +879,94 @@ impl Default for Config {
             name: "OpenRouter".to_string(),
             base_url: OPENROUTER_BASE_URL.to_string(),
             api_key_env: Some("OPENROUTER_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "openai/gpt-5".to_string(),
                     name: "GPT-5 (via OpenRouter)".to_string(),
                     description: "Latest flagship via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "openai/gpt-oss-120b:free".to_string(),
                     name: "GPT-OSS 120B (free) (via OpenRouter)".to_string(),
                     description: "Open-source GPT-class model available on the free tier.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "anthropic/claude-3-5-sonnet-4.5".to_string(),
                     name: "Claude Sonnet 4.5 (via OpenRouter)".to_string(),
                     description: "Latest Claude via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "google/gemini-2.5-pro".to_string(),
                     name: "Gemini 2.5 Pro (via OpenRouter)".to_string(),
                     description: "Latest Google model via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "x-ai/grok-4-fast:free".to_string(),
                     name: "Grok-4-fast (free) (via OpenRouter)".to_string(),
                     description: "Latest Grok via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "meta-llama/llama-3.1-405b-instruct".to_string(),
                     name: "Llama 3.1 405B (via OpenRouter)".to_string(),
                     description: "Open source powerhouse".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistralai/mistral-large".to_string(),
                     name: "Mistral Large (via OpenRouter)".to_string(),
                     description: "Most capable Mistral model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "z-ai/glm-4.5-air:free".to_string(),
                     name: "Z.AI GLM 4.5 Air (free) (via OpenRouter)".to_string(),
                     description: "Purpose-built for agent-centric applications.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
                     name: "Mistral 24B Instruct (free) (via OpenRouter)".to_string(),
                     description: "Mistral optimized for instruction following, repetition reduction, and improved function calling.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "custom-model".to_string(),
                     name: "Custom Model".to_string(),
                     description: "Enter any OpenRouter model name".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::from([
+                ("HTTP-Referer".to_string(), "https://bindr.dev".to_string()),
+                ("X-Title".to_string(), "Bindr".to_string()),
+            ]),
+            extra_query: HashMap::new(),
         });
         
         // Mistral AI (Direct API)
@@ -311,31 +974,41 @@ impl Default for Config {
             name: "Mistral AI".to_string(),
             base_url: "https://api.mistral.ai/v1".to_string(),
             api_key_env: Some("MISTRAL_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "mistral-large-latest".to_string(),
                     name: "Mistral Large".to_string(),
                     description: "Most capable Mistral model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistral-medium-latest".to_string(),
                     name: "Mistral Medium".to_string(),
                     description: "Balanced performance and speed".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistral-small-latest".to_string(),
                     name: "Mistral Small".to_string(),
                     description: "Fast and efficient".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         Config {
             selected_provider: "openai".to_string(),
             api_keys: HashMap::new(),
+            encrypt_api_keys: false,
             default_model: "gpt-4o-mini".to_string(),
             model_providers,
             user_instructions: None,
@@ -345,8 +1018,33 @@ impl Default for Config {
             ui: UiConfig {
                 theme: "dark".to_string(),
                 show_usage_counter: true,
-                auto_save_interval: 30,
+                auto_save_interval: DEFAULT_AUTO_SAVE_INTERVAL,
+                max_history_lines: DEFAULT_MAX_HISTORY_LINES,
+                greetings: HashMap::new(),
+                reduced_motion: false,
+                restore_last_session: true,
+                confirm_cost_above: None,
+                density: Density::default(),
+                response_locale: None,
+                auto_continue_max_steps: DEFAULT_AUTO_CONTINUE_MAX_STEPS,
+                submit_key: SubmitKey::default(),
+                terminal_title: true,
+                require_api_key: false,
+                syntax_highlighting: true,
+                show_reasoning: true,
+                daily_message_limit: DEFAULT_DAILY_MESSAGE_LIMIT,
+                request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+                request_retries: DEFAULT_REQUEST_RETRIES,
+                retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+                model_catalog_ttl_secs: DEFAULT_MODEL_CATALOG_TTL_SECS,
+                debug_log: false,
             },
+            favorite_models: HashMap::new(),
+            redaction: RedactionConfig::default(),
+            fallback: FallbackConfig::default(),
+            mode_sampling: ModeSamplingConfig::default(),
+            loop_guard: LoopGuardConfig::default(),
+            removed_api_keys: HashSet::new(),
         }
     }
 }
@@ -354,12 +1052,12 @@ impl Default for Config {
 impl Config {
     
     /// Load AGENTS.md from multiple locations (like Codex does)
-    fn load_agents_md(cwd: &Path) -> Result<Option<String>> {
+    fn load_agents_md(cwd: &Path, bindr_home: &Path) -> Result<Option<String>> {
         // Check multiple locations in order of precedence
         let locations = vec![
             cwd.join("AGENTS.md"),                    // Current directory
             cwd.join("..").join("AGENTS.md"),         // Parent directory
-            dirs::home_dir().unwrap_or_default().join(".bindr").join("AGENTS.md"), // Global
+            bindr_home.join("AGENTS.md"),             // Global
         ];
         
         for path in locations {
@@ -374,6 +1072,20 @@ impl Config {
         
         Ok(None)
     }
+
+    /// Re-read AGENTS.md for the current workspace; an unreadable file leaves the model without instructions
+    pub fn reload_user_instructions(&mut self) {
+        self.user_instructions = Self::load_agents_md(&self.cwd, &self.bindr_home).ok().flatten();
+    }
+    
+    /// Opening guidance for a mode, honoring any `[ui.greetings]` override
+    pub fn mode_greeting(&self, mode: BindrMode) -> String {
+        self.ui
+            .greetings
+            .get(&mode.display_name().to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| prompts::mode_greeting(mode).to_string())
+    }
     
     /// Get the current model provider
     pub fn get_current_provider(&self) -> Option<&ModelProvider> {
@@ -387,12 +1099,7 @@ impl Config {
     
     /// Check if API key is configured for a specific provider
     pub fn has_api_key_for(&self, provider_id: &str) -> bool {
-        self.api_keys.contains_key(provider_id) ||
-            self.model_providers
-                .get(provider_id)
-                .and_then(|p| p.api_key_env.as_ref())
-                .map(|env| std::env::var(env).is_ok())
-                .unwrap_or(false)
+        self.get_api_key_for(provider_id).is_some()
     }
     
     /// Get API key from config or environment
@@ -400,27 +1107,173 @@ impl Config {
         self.get_api_key_for(&self.selected_provider)
     }
 
-    /// Get API key for a specific provider from config or environment
+    /// Get API key for a specific provider: `[api_keys]` first, then its `api_key_file`, then its `api_key_env`
     pub fn get_api_key_for(&self, provider_id: &str) -> Option<String> {
-        self.api_keys.get(provider_id).cloned()
-            .or_else(|| {
-                self.model_providers
-                    .get(provider_id)
-                    .and_then(|p| p.api_key_env.as_ref())
-                    .and_then(|env| std::env::var(env).ok())
-            })
+        if let Some(key) = self.api_keys.get(provider_id) {
+            return Some(key.clone());
+        }
+        let provider = self.model_providers.get(provider_id)?;
+        provider.api_key_file
+            .as_ref()
+            .and_then(|path| self.read_api_key_file(path))
+            .or_else(|| provider.api_key_env.as_ref().and_then(|env| std::env::var(env).ok()))
+    }
+
+    /// Key stored in `path`, without the trailing newline editors add; `None` when unreadable or empty
+    fn read_api_key_file(&self, path: &Path) -> Option<String> {
+        let path = match path.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir()?.join(rest),
+            Err(_) => self.bindr_home.join(path),
+        };
+        let key = fs::read_to_string(path).ok()?;
+        let key = key.trim_end();
+        (!key.is_empty()).then(|| key.to_string())
     }
     
-    /// Update API key for current provider
+    /// Update API key for current provider; an empty key removes it
     pub fn set_api_key(&mut self, provider: String, key: String) {
+        if key.trim().is_empty() {
+            self.remove_api_key(&provider);
+            return;
+        }
+        self.removed_api_keys.remove(&provider);
         self.api_keys.insert(provider, key);
     }
+
+    /// Forget a provider's saved key; the next save drops it from disk too
+    pub fn remove_api_key(&mut self, provider: &str) {
+        self.api_keys.remove(provider);
+        self.removed_api_keys.insert(provider.to_string());
+    }
     
     /// Set selected provider
     pub fn set_selected_provider(&mut self, provider: String) {
         self.selected_provider = provider;
     }
     
+    /// Select a provider, keeping the default model if the provider offers it and otherwise taking its first.
+    ///
+    /// Returns `false`, changing nothing, when no provider has this id.
+    pub fn switch_provider(&mut self, provider_id: &str) -> bool {
+        let Some(provider) = self.model_providers.get(provider_id) else {
+            return false;
+        };
+        let offered = |model: &&ModelInfo| model.id != "custom-model";
+        if !provider.models.iter().filter(offered).any(|model| model.id == self.default_model)
+            && let Some(first) = provider.models.iter().find(offered)
+        {
+            self.default_model = first.id.clone();
+        }
+        self.selected_provider = provider_id.to_string();
+        true
+    }
+
+    /// Make `model_id` the default model, if the selected provider offers it or accepts custom ids
+    pub fn set_default_model(&mut self, model_id: &str) -> Result<()> {
+        let provider = self.get_current_provider()
+            .with_context(|| format!("No provider configured for id {}", self.selected_provider))?;
+        if !provider.models.iter().any(|model| model.id == model_id || model.id == "custom-model") {
+            anyhow::bail!(
+                "{} doesn't offer a model called \"{}\"; run `bindr models` to see the catalog",
+                provider.name,
+                model_id
+            );
+        }
+        self.default_model = model_id.to_string();
+        Ok(())
+    }
+
+    /// Whether `provider_id` belongs to a built-in provider, whose URL and key variable are reset on load
+    pub fn is_builtin_provider(provider_id: &str) -> bool {
+        Self::create_default_model_providers().contains_key(provider_id)
+    }
+
+    /// Add an OpenAI-compatible provider with one model, under an id derived from its name.
+    ///
+    /// Returns the new id. Built-in ids are reserved so the catalog merge on load leaves the
+    /// provider alone.
+    pub fn add_custom_provider(
+        &mut self,
+        name: &str,
+        base_url: &str,
+        api_key_env: Option<&str>,
+        model_id: &str,
+    ) -> Result<String> {
+        let name = name.trim();
+        let base_url = base_url.trim().trim_end_matches('/');
+        let model_id = model_id.trim();
+        let api_key_env = api_key_env.map(str::trim).filter(|env| !env.is_empty());
+
+        let provider_id = provider_id_for(name);
+        if provider_id.is_empty() {
+            anyhow::bail!("Give the provider a name with at least one letter or digit");
+        }
+        if Self::is_builtin_provider(&provider_id) {
+            anyhow::bail!("'{}' is reserved for a built-in provider; pick another name", provider_id);
+        }
+        if self.model_providers.contains_key(&provider_id) {
+            anyhow::bail!("A provider called '{}' already exists", provider_id);
+        }
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            anyhow::bail!("The base URL must start with http:// or https://");
+        }
+        if model_id.is_empty() || model_id.contains(char::is_whitespace) {
+            anyhow::bail!("Enter a model id without spaces, e.g. llama3.1:8b");
+        }
+        if let Some(env) = api_key_env
+            && !env.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            anyhow::bail!("'{}' isn't a valid environment variable name", env);
+        }
+
+        self.model_providers.insert(provider_id.clone(), ModelProvider {
+            name: name.to_string(),
+            base_url: base_url.to_string(),
+            api_key_env: api_key_env.map(str::to_string),
+            api_key_file: None,
+            models: vec![ModelInfo {
+                id: model_id.to_string(),
+                name: model_id.to_string(),
+                description: "Custom model".to_string(),
+                is_premium: false,
+                input_cost: None,
+                output_cost: None,
+            }],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
+        });
+        Ok(provider_id)
+    }
+
+    /// Remove a provider added with [`Config::add_custom_provider`], with its key, its favorites,
+    /// and any mode or fallback that used it.
+    ///
+    /// Selecting it falls back to OpenAI; built-in providers can't be removed.
+    pub fn remove_custom_provider(&mut self, provider_id: &str) -> Result<()> {
+        if Self::is_builtin_provider(provider_id) {
+            anyhow::bail!("'{}' is a built-in provider and can't be removed", provider_id);
+        }
+        if self.model_providers.remove(provider_id).is_none() {
+            anyhow::bail!("There's no provider called '{}'", provider_id);
+        }
+        self.remove_api_key(provider_id);
+        self.favorite_models.remove(provider_id);
+        // Modes and fallbacks that named the provider go back to the selected one; a saved
+        // reference to a missing provider would stop the config from loading
+        for mode in BindrMode::ALL {
+            let sampling = self.mode_sampling.for_mode_mut(mode);
+            if sampling.provider.as_deref() == Some(provider_id) {
+                sampling.provider = None;
+                sampling.model = None;
+            }
+        }
+        self.fallback.providers.retain(|target| target.provider != provider_id);
+        if self.selected_provider == provider_id {
+            self.switch_provider("openai");
+        }
+        Ok(())
+    }
+
     /// Get available providers sorted by display name
     pub fn get_providers(&self) -> Vec<(&String, &ModelProvider)> {
         let mut providers: Vec<(&String, &ModelProvider)> = self.model_providers.iter().collect();
@@ -428,20 +1281,214 @@ impl Config {
         providers
     }
     
+    /// Whether a model has been marked as a favorite
+    pub fn is_favorite_model(&self, provider_id: &str, model_id: &str) -> bool {
+        self.favorite_models
+            .get(provider_id)
+            .is_some_and(|models| models.iter().any(|id| id == model_id))
+    }
+    
+    /// Mark or unmark a model as a favorite
+    pub fn toggle_favorite_model(&mut self, provider_id: &str, model_id: &str) {
+        let models = self.favorite_models.entry(provider_id.to_string()).or_default();
+        if let Some(index) = models.iter().position(|id| id == model_id) {
+            models.remove(index);
+        } else {
+            models.push(model_id.to_string());
+        }
+        self.favorite_models.retain(|_, models| !models.is_empty());
+    }
+    
+    /// Flat list of (provider id, model) pairs with favorites pinned first.
+    ///
+    /// Restricts to one provider when `provider_id` is given and drops
+    /// non-favorites when `favorites_only` is set.
+    pub fn model_list(&self, provider_id: Option<&str>, favorites_only: bool) -> Vec<(&String, &ModelInfo)> {
+        let mut models: Vec<(&String, &ModelInfo)> = self.get_providers()
+            .into_iter()
+            .filter(|(id, _)| provider_id.is_none_or(|wanted| id.as_str() == wanted))
+            .flat_map(|(id, provider)| provider.models.iter().map(move |model| (id, model)))
+            .filter(|(id, model)| !favorites_only || self.is_favorite_model(id, &model.id))
+            .collect();
+        // Stable sort keeps catalog order within each group
+        models.sort_by_key(|(id, model)| !self.is_favorite_model(id, &model.id));
+        models
+    }
+    
+    /// Catalog entries whose provider or model id, name, or description contains `query`
+    pub fn search_models(&self, query: &str) -> Vec<(&String, &ModelProvider, &ModelInfo)> {
+        let needle = query.trim().to_lowercase();
+        self.get_providers()
+            .into_iter()
+            .flat_map(|(id, provider)| provider.models.iter().map(move |model| (id, provider, model)))
+            .filter(|(id, provider, model)| {
+                needle.is_empty()
+                    || [id.as_str(), &provider.name, &model.id, &model.name, &model.description]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(&needle))
+            })
+            .collect()
+    }
+    
+    /// Add models from a provider's live catalog that aren't configured yet.
+    ///
+    /// Models already configured keep their names and descriptions, and take the catalog's
+    /// prices. Returns how many models were added.
+    pub fn merge_models(&mut self, provider_id: &str, models: &[ModelInfo]) -> usize {
+        let Some(provider) = self.model_providers.get_mut(provider_id) else {
+            return 0;
+        };
+        let mut added = 0;
+        for model in models {
+            match provider.models.iter_mut().find(|known| known.id == model.id) {
+                Some(known) => {
+                    known.input_cost = model.input_cost.or(known.input_cost);
+                    known.output_cost = model.output_cost.or(known.output_cost);
+                }
+                None => {
+                    provider.models.push(model.clone());
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+    
+    /// Provider and model a mode talks to: its `[mode_sampling]` choice, else the selected ones.
+    ///
+    /// A mode that names only a provider keeps the default model if the provider offers it and
+    /// otherwise takes the provider's first.
+    pub fn mode_model(&self, mode: BindrMode) -> (String, String) {
+        let sampling = self.mode_sampling.for_mode(mode);
+        let provider_id = sampling.provider.clone().unwrap_or_else(|| self.selected_provider.clone());
+        let model = match (&sampling.model, &sampling.provider) {
+            (Some(model), _) => model.clone(),
+            (None, Some(_)) => self.model_providers
+                .get(&provider_id)
+                .and_then(|provider| {
+                    let offered = || provider.models.iter().filter(|model| model.id != "custom-model");
+                    offered()
+                        .find(|model| model.id == self.default_model)
+                        .or_else(|| offered().next())
+                })
+                .map_or_else(|| self.default_model.clone(), |model| model.id.clone()),
+            (None, None) => self.default_model.clone(),
+        };
+        (provider_id, model)
+    }
+
+    /// Prices for a model: the rates set on it in the catalog, else Bindr's built-in list
+    pub fn model_rates(&self, model_id: &str) -> Option<Rates> {
+        let configured = self.model_providers
+            .values()
+            .flat_map(|provider| &provider.models)
+            .filter(|model| model.id == model_id)
+            .find_map(|model| Some(Rates { input: model.input_cost?, output: model.output_cost? }));
+        configured.or_else(|| Rates::for_model(model_id))
+    }
+
     /// Set custom model for OpenRouter
     pub fn set_custom_model(&mut self, model_name: String) {
         self.default_model = model_name;
     }
     
-    /// Get usage counter info (placeholder for now)
+    /// Directory holding a project's `state.json` and `bindr.md`.
+    ///
+    /// All project paths go through here so saving and loading always agree.
+    pub fn project_dir(&self, name: &str) -> PathBuf {
+        self.projects_dir.join(name)
+    }
+    
+    /// Create the Bindr home, its projects and sessions directories, and a commented
+    /// starter config, leaving anything that already exists untouched.
+    ///
+    /// Returns each path with whether it was created.
+    pub fn init_home(bindr_home: &Path) -> Result<Vec<(PathBuf, bool)>> {
+        let mut steps = Vec::new();
+        for dir in [bindr_home.to_path_buf(), bindr_home.join("projects"), bindr_home.join("sessions")] {
+            let existed = dir.is_dir();
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            steps.push((dir, !existed));
+        }
+        let config_path = bindr_home.join("config.toml");
+        let created = write_if_missing(&config_path, STARTER_CONFIG)?;
+        steps.push((config_path, created));
+        Ok(steps)
+    }
+    
+    /// Make sure the Bindr home, projects, and sessions directories accept writes
+    pub fn check_writable(&self) -> Result<()> {
+        for dir in [
+            self.bindr_home.clone(),
+            self.projects_dir.clone(),
+            self.bindr_home.join("sessions"),
+        ] {
+            probe_writable(&dir)?;
+        }
+        Ok(())
+    }
+    
+    /// User-facing message for a failed save, with `BINDR_HOME` guidance when the directory is read-only
+    pub fn describe_write_failure(&self, action: &str, err: &anyhow::Error) -> String {
+        let mut message = format!("{}: {:#}", action, err);
+        if is_unwritable(err) {
+            message.push_str(&format!(
+                ". Bindr can't write to {}; set BINDR_HOME to a writable directory and restart.",
+                self.bindr_home.display()
+            ));
+        }
+        message
+    }
+    
+    /// Point file and command tools at a different workspace root.
+    ///
+    /// Relative paths resolve against the current workspace; the directory
+    /// must exist and be readable.
+    pub fn set_workspace(&mut self, dir: &Path) -> Result<()> {
+        let expanded = match dir.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir()
+                .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?
+                .join(rest),
+            Err(_) => dir.to_path_buf(),
+        };
+        let resolved = self.cwd.join(expanded)
+            .canonicalize()
+            .with_context(|| format!("Workspace '{}' does not exist", dir.display()))?;
+        if !resolved.is_dir() {
+            anyhow::bail!("Workspace '{}' is not a directory", resolved.display());
+        }
+        fs::read_dir(&resolved)
+            .with_context(|| format!("Workspace '{}' is not accessible", resolved.display()))?;
+        
+        self.cwd = resolved;
+        self.reload_user_instructions();
+        Ok(())
+    }
+    
+    /// Free-tier messages used today and the daily limit
     pub fn get_usage_info(&self) -> (u32, u32) {
-        // TODO: Implement actual usage tracking
-        (0, 100) // (used, limit)
+        let today = chrono::Local::now().date_naive();
+        let used = DailyUsage::load(&self.bindr_home).map_or(0, |usage| usage.used_on(today));
+        (used, self.ui.daily_message_limit)
+    }
+
+    /// Count a free-tier message toward today's limit; messages sent with an API key aren't counted
+    pub fn record_usage(&self) -> Result<()> {
+        if self.has_api_key() {
+            return Ok(());
+        }
+        DailyUsage::record(&self.bindr_home, chrono::Local::now().date_naive())?;
+        Ok(())
     }
     
     /// Load configuration from file
     pub fn load() -> Result<Self> {
-        let bindr_home = Self::find_bindr_home()?;
+        Self::load_from(Self::find_bindr_home()?)
+    }
+
+    /// Load configuration from the `config.toml` under `bindr_home`
+    pub fn load_from(bindr_home: PathBuf) -> Result<Self> {
         let config_path = bindr_home.join("config.toml");
         
         let config_toml = if config_path.exists() {
@@ -453,15 +1500,20 @@ impl Config {
             ConfigToml::default()
         };
         
-        Self::from_config_toml(config_toml, bindr_home)
+        let mut config = Self::from_config_toml(config_toml, bindr_home)?;
+        config.reload_user_instructions();
+        Ok(config)
     }
     
-    /// Save configuration to file
+    /// Save configuration to file.
+    ///
+    /// Holds a lock across the read-modify-write so concurrent instances don't
+    /// clobber each other, and keeps API keys another instance added meanwhile
+    /// unless this instance removed that provider's key.
+    /// With `encrypt_api_keys` on, the keys are written only in encrypted form.
     pub fn save(&self) -> Result<()> {
         let config_path = self.bindr_home.join("config.toml");
-        let config_toml = self.to_config_toml();
-        let toml_content = toml::to_string_pretty(&config_toml)
-            .context("Failed to serialize config to TOML")?;
+        let mut config_toml = self.to_config_toml();
         
         // Ensure directory exists
         if let Some(parent) = config_path.parent() {
@@ -469,12 +1521,46 @@ impl Config {
                 .with_context(|| format!("Failed to create config directory {}", parent.display()))?;
         }
         
-        fs::write(&config_path, toml_content)
+        let _lock = FileLock::acquire(&config_path)?;
+        
+        let on_disk = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| toml::from_str::<ConfigToml>(&content).ok());
+        if let Some(disk) = on_disk {
+            let disk_keys = Self::stored_api_keys(disk.api_keys, disk.encrypted_api_keys.as_deref())?;
+            let api_keys = config_toml.api_keys.get_or_insert_with(HashMap::new);
+            for (provider, key) in disk_keys {
+                if !self.removed_api_keys.contains(&provider) {
+                    api_keys.entry(provider).or_insert(key);
+                }
+            }
+        }
+        if self.encrypt_api_keys {
+            let api_keys = config_toml.api_keys.take().unwrap_or_default();
+            config_toml.encrypted_api_keys = Some(secrets::encrypt_api_keys(&api_keys, &secrets::passphrase()?)?);
+        }
+        
+        let toml_content = toml::to_string_pretty(&config_toml)
+            .context("Failed to serialize config to TOML")?;
+        write_atomic(&config_path, toml_content)
             .with_context(|| format!("Failed to write config to {}", config_path.display()))?;
         
         Ok(())
     }
     
+    /// The effective config as TOML, with API keys masked and secret headers and query values redacted
+    pub fn to_display_toml(&self) -> Result<String> {
+        let mut config_toml = self.to_config_toml();
+        for key in config_toml.api_keys.iter_mut().flat_map(|keys| keys.values_mut()) {
+            *key = mask_key(key);
+        }
+        for provider in config_toml.model_providers.iter_mut().flat_map(|providers| providers.values_mut()) {
+            provider.extra_headers = provider.extra_headers.as_ref().map(redact_map);
+            provider.extra_query = provider.extra_query.as_ref().map(redact_map);
+        }
+        toml::to_string_pretty(&config_toml).context("Failed to serialize config to TOML")
+    }
+
     /// Find the Bindr home directory
     pub fn find_bindr_home() -> Result<PathBuf> {
         // Honor the `BINDR_HOME` environment variable when it is set
@@ -491,6 +1577,17 @@ impl Config {
         Ok(p)
     }
     
+    /// Plain `[api_keys]` merged over the decrypted `encrypted_api_keys`, so a key added by hand wins
+    fn stored_api_keys(plain: Option<HashMap<String, String>>, encrypted: Option<&str>) -> Result<HashMap<String, String>> {
+        let mut api_keys = plain.unwrap_or_default();
+        if let Some(encrypted) = encrypted {
+            for (provider, key) in secrets::decrypt_api_keys(encrypted, &secrets::passphrase()?)? {
+                api_keys.entry(provider).or_insert(key);
+            }
+        }
+        Ok(api_keys)
+    }
+
     /// Convert from TOML config
     fn from_config_toml(config_toml: ConfigToml, bindr_home: PathBuf) -> Result<Self> {
         let projects_dir = bindr_home.join("projects");
@@ -503,7 +1600,8 @@ impl Config {
         let default_model = config_toml.default_model
             .unwrap_or_else(|| "gpt-5".to_string());
         
-        let api_keys = config_toml.api_keys.unwrap_or_default();
+        let api_keys = Self::stored_api_keys(config_toml.api_keys, config_toml.encrypted_api_keys.as_deref())?;
+        let encrypt_api_keys = config_toml.encrypt_api_keys.unwrap_or(false);
         
         let mut model_providers = if let Some(providers_toml) = config_toml.model_providers {
             providers_toml.into_iter()
@@ -520,7 +1618,9 @@ impl Config {
                             id: model_toml.id,
                             name: model_toml.name,
                             description: model_toml.description.unwrap_or_else(|| "".to_string()),
-                            is_premium: false, // Default to false for loaded models
+                            is_premium: model_toml.is_premium.unwrap_or(false),
+                            input_cost: model_toml.input_cost,
+                            output_cost: model_toml.output_cost,
                         })
                         .collect();
                     
@@ -528,7 +1628,10 @@ impl Config {
                         name: provider_toml.name,
                         base_url,
                         api_key_env: provider_toml.api_key_env,
+                        api_key_file: provider_toml.api_key_file,
                         models,
+                        extra_headers: provider_toml.extra_headers.unwrap_or_default(),
+                        extra_query: provider_toml.extra_query.unwrap_or_default(),
                     })
                 })
                 .collect()
@@ -541,27 +1644,95 @@ impl Config {
         let ui = if let Some(ui_toml) = config_toml.ui {
             UiConfig {
                 theme: ui_toml.theme.unwrap_or_else(|| "default".to_string()),
-                show_usage_counter: ui_toml.show_emojis.unwrap_or(true),
-                auto_save_interval: ui_toml.max_history_lines.unwrap_or(1000) as u64,
+                show_usage_counter: ui_toml.show_usage_counter.or(ui_toml.show_emojis).unwrap_or(true),
+                auto_save_interval: ui_toml.auto_save_interval.unwrap_or(DEFAULT_AUTO_SAVE_INTERVAL),
+                max_history_lines: ui_toml.max_history_lines.unwrap_or(DEFAULT_MAX_HISTORY_LINES).max(MIN_HISTORY_LINES),
+                greetings: ui_toml.greetings.unwrap_or_default(),
+                reduced_motion: ui_toml.reduced_motion.unwrap_or_default(),
+                restore_last_session: ui_toml.restore_last_session.unwrap_or(true),
+                confirm_cost_above: ui_toml.confirm_cost_above,
+                density: ui_toml.density.unwrap_or_default(),
+                response_locale: ui_toml.response_locale.filter(|locale| !locale.trim().is_empty()),
+                auto_continue_max_steps: ui_toml.auto_continue_max_steps.unwrap_or(DEFAULT_AUTO_CONTINUE_MAX_STEPS),
+                submit_key: ui_toml.submit_key.unwrap_or_default(),
+                terminal_title: ui_toml.terminal_title.unwrap_or(true),
+                require_api_key: ui_toml.require_api_key.unwrap_or_default(),
+                syntax_highlighting: ui_toml.syntax_highlighting.unwrap_or(true),
+                show_reasoning: ui_toml.show_reasoning.unwrap_or(true),
+                daily_message_limit: ui_toml.daily_message_limit.unwrap_or(DEFAULT_DAILY_MESSAGE_LIMIT),
+                request_timeout_secs: ui_toml.request_timeout_secs.unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS),
+                request_retries: ui_toml.request_retries.unwrap_or(DEFAULT_REQUEST_RETRIES),
+                retry_base_delay_ms: ui_toml.retry_base_delay_ms.unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS),
+                model_catalog_ttl_secs: ui_toml.model_catalog_ttl_secs.unwrap_or(DEFAULT_MODEL_CATALOG_TTL_SECS),
+                debug_log: ui_toml.debug_log.unwrap_or_default(),
             }
         } else {
             UiConfig {
                 theme: "default".to_string(),
                 show_usage_counter: true,
-                auto_save_interval: 30,
+                auto_save_interval: DEFAULT_AUTO_SAVE_INTERVAL,
+                max_history_lines: DEFAULT_MAX_HISTORY_LINES,
+                greetings: HashMap::new(),
+                reduced_motion: false,
+                restore_last_session: true,
+                confirm_cost_above: None,
+                density: Density::default(),
+                response_locale: None,
+                auto_continue_max_steps: DEFAULT_AUTO_CONTINUE_MAX_STEPS,
+                submit_key: SubmitKey::default(),
+                terminal_title: true,
+                require_api_key: false,
+                syntax_highlighting: true,
+                show_reasoning: true,
+                daily_message_limit: DEFAULT_DAILY_MESSAGE_LIMIT,
+                request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+                request_retries: DEFAULT_REQUEST_RETRIES,
+                retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+                model_catalog_ttl_secs: DEFAULT_MODEL_CATALOG_TTL_SECS,
+                debug_log: false,
             }
         };
         
+        let redaction = config_toml.redaction.unwrap_or_default();
+        Redactor::from_config(&redaction).context("Invalid [redaction] settings")?;
+        let mode_sampling = config_toml.mode_sampling.unwrap_or_default();
+        mode_sampling.validate().context("Invalid [mode_sampling] settings")?;
+        for mode in BindrMode::ALL {
+            if let Some(provider) = &mode_sampling.for_mode(mode).provider
+                && !model_providers.contains_key(provider)
+            {
+                anyhow::bail!(
+                    "Invalid [mode_sampling] settings: {} provider '{}' isn't configured",
+                    mode.display_name(),
+                    provider
+                );
+            }
+        }
+        let loop_guard = config_toml.loop_guard.unwrap_or_default();
+        if loop_guard.min_repeats < 2 {
+            anyhow::bail!("Invalid [loop_guard] settings: min_repeats must be at least 2");
+        }
+        if loop_guard.max_tool_round_trips == 0 {
+            anyhow::bail!("Invalid [loop_guard] settings: max_tool_round_trips must be at least 1");
+        }
+        
         Ok(Config {
             selected_provider,
             api_keys,
+            encrypt_api_keys,
             default_model,
             model_providers,
-            user_instructions: None, // Will be loaded separately
+            user_instructions: None, // Read from AGENTS.md by `load`
             bindr_home,
             projects_dir,
             cwd,
             ui,
+            favorite_models: config_toml.favorite_models.unwrap_or_default(),
+            redaction,
+            fallback: config_toml.fallback.unwrap_or_default(),
+            mode_sampling,
+            loop_guard,
+            removed_api_keys: HashSet::new(),
         })
     }
 
@@ -574,32 +1745,43 @@ impl Config {
             name: "OpenAI".to_string(),
             base_url: "https://api.openai.com/v1".to_string(),
             api_key_env: Some("OPENAI_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "gpt-5".to_string(),
                     name: "GPT-5".to_string(),
                     description: "Latest flagship model with advanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-5-codex".to_string(),
                     name: "GPT-5 Codex".to_string(),
                     description: "Specialized for code generation and analysis".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-4.1".to_string(),
                     name: "GPT-4.1".to_string(),
                     description: "Previous generation flagship".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gpt-3.5-turbo".to_string(),
                     name: "GPT-3.5 Turbo".to_string(),
                     description: "Fast and efficient model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // Anthropic
@@ -607,26 +1789,35 @@ impl Config {
             name: "Anthropic".to_string(),
             base_url: "https://api.anthropic.com".to_string(),
             api_key_env: Some("ANTHROPIC_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "claude-3-5-sonnet-4.5".to_string(),
                     name: "Claude Sonnet 4.5".to_string(),
                     description: "Latest flagship with advanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "claude-3-opus-4".to_string(),
                     name: "Claude Opus 4".to_string(),
                     description: "Most capable model for complex tasks".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "claude-3-haiku-3".to_string(),
                     name: "Claude Haiku 3".to_string(),
                     description: "Fast and efficient model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // Google
@@ -634,20 +1825,27 @@ impl Config {
             name: "Google".to_string(),
             base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
             api_key_env: Some("GOOGLE_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "gemini-2.5-pro".to_string(),
                     name: "Gemini 2.5 Pro".to_string(),
                     description: "Latest flagship with advanced capabilities".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "gemini-2.5-flash".to_string(),
                     name: "Gemini 2.5 Flash".to_string(),
                     description: "Fast and efficient latest model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         // xAI
@@ -655,26 +1853,35 @@ impl Config {
             name: "xAI".to_string(),
             base_url: "https://api.x.ai/v1".to_string(),
             api_key_env: Some("XAI_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "grok-4".to_string(),
                     name: "Grok-4".to_string(),
                     description: "Latest Grok with advanced reasoning".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "grok-3".to_string(),
                     name: "Grok-3".to_string(),
                     description: "Previous generation flagship".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "grok-beta".to_string(),
                     name: "Grok Beta".to_string(),
                     description: "Experimental Grok model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
 
         // OpenRouter (aggregator)
@@ -682,68 +1889,94 @@ impl Config {
             name: "OpenRouter".to_string(),
             base_url: OPENROUTER_BASE_URL.to_string(),
             api_key_env: Some("OPENROUTER_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "openai/gpt-5".to_string(),
                     name: "GPT-5 (via OpenRouter)".to_string(),
                     description: "Latest flagship via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "openai/gpt-oss-120b:free".to_string(),
                     name: "GPT-OSS 120B (free) (via OpenRouter)".to_string(),
                     description: "Open-source GPT-class model available on the free tier.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "anthropic/claude-3-5-sonnet-4.5".to_string(),
                     name: "Claude Sonnet 4.5 (via OpenRouter)".to_string(),
                     description: "Latest Claude via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "google/gemini-2.5-pro".to_string(),
                     name: "Gemini 2.5 Pro (via OpenRouter)".to_string(),
                     description: "Latest Google model via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "x-ai/grok-4-fast:free".to_string(),
                     name: "Grok-4-fast (free) (via OpenRouter)".to_string(),
                     description: "Latest Grok via OpenRouter".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "meta-llama/llama-3.1-405b-instruct".to_string(),
                     name: "Llama 3.1 405B (via OpenRouter)".to_string(),
                     description: "Open source powerhouse".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistralai/mistral-large".to_string(),
                     name: "Mistral Large (via OpenRouter)".to_string(),
                     description: "Most capable Mistral model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "z-ai/glm-4.5-air:free".to_string(),
                     name: "Z.AI GLM 4.5 Air (free) (via OpenRouter)".to_string(),
                     description: "Purpose-built for agent-centric applications.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
                     name: "Mistral 24B Instruct (free) (via OpenRouter)".to_string(),
                     description: "Mistral optimized for instruction following, repetition reduction, and improved function calling.".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "custom-model".to_string(),
                     name: "Custom Model".to_string(),
                     description: "Enter any OpenRouter model name".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::from([
+                ("HTTP-Referer".to_string(), "https://bindr.dev".to_string()),
+                ("X-Title".to_string(), "Bindr".to_string()),
+            ]),
+            extra_query: HashMap::new(),
         });
 
         // Mistral AI (Direct API)
@@ -751,35 +1984,68 @@ impl Config {
             name: "Mistral AI".to_string(),
             base_url: "https://api.mistral.ai/v1".to_string(),
             api_key_env: Some("MISTRAL_API_KEY".to_string()),
+            api_key_file: None,
             models: vec![
                 ModelInfo {
                     id: "mistral-large".to_string(),
                     name: "Mistral Large".to_string(),
                     description: "Most capable Mistral model".to_string(),
                     is_premium: true,
+                    input_cost: None,
+                    output_cost: None,
                 },
                 ModelInfo {
                     id: "mistral-7b-instruct".to_string(),
                     name: "Mistral 7B Instruct".to_string(),
                     description: "Fast and efficient model".to_string(),
                     is_premium: false,
+                    input_cost: None,
+                    output_cost: None,
                 },
             ],
+            extra_headers: HashMap::new(),
+            extra_query: HashMap::new(),
         });
         
         model_providers
     }
 
     /// Ensure built-in providers are present and up-to-date in the configuration
+    ///
+    /// Only built-in ids are touched, so custom providers load exactly as saved.
     fn merge_builtin_provider_catalog(model_providers: &mut HashMap<String, ModelProvider>) {
         let builtin = Self::create_default_model_providers();
         for (provider_id, builtin_provider) in builtin {
             model_providers
                 .entry(provider_id.clone())
                 .and_modify(|existing| {
+                    if existing.name.is_empty() {
+                        existing.name = builtin_provider.name.clone();
+                    }
                     existing.base_url = builtin_provider.base_url.clone();
                     existing.api_key_env = builtin_provider.api_key_env.clone();
-                    existing.models = builtin_provider.models.clone();
+                    // Union by id: built-in models first, keeping the user's description and
+                    // rates, then models the user or a live catalog added
+                    let mut configured = std::mem::take(&mut existing.models);
+                    for builtin in &builtin_provider.models {
+                        let mut model = builtin.clone();
+                        if let Some(index) = configured.iter().position(|user| user.id == builtin.id) {
+                            let user = configured.remove(index);
+                            if !user.description.is_empty() {
+                                model.description = user.description;
+                            }
+                            model.input_cost = user.input_cost.or(model.input_cost);
+                            model.output_cost = user.output_cost.or(model.output_cost);
+                        }
+                        existing.models.push(model);
+                    }
+                    existing.models.extend(configured);
+                    // User-configured values win over built-in defaults
+                    for (name, value) in &builtin_provider.extra_headers {
+                        existing.extra_headers
+                            .entry(name.clone())
+                            .or_insert_with(|| value.clone());
+                    }
                 })
                 .or_insert(builtin_provider);
         }
@@ -794,6 +2060,9 @@ impl Config {
                         id: model.id.clone(),
                         name: model.name.clone(),
                         description: Some(model.description.clone()),
+                        is_premium: Some(model.is_premium),
+                        input_cost: model.input_cost,
+                        output_cost: model.output_cost,
                     })
                     .collect();
                 
@@ -801,7 +2070,12 @@ impl Config {
                     name: provider.name.clone(),
                     base_url: provider.base_url.clone(),
                     api_key_env: provider.api_key_env.clone(),
+                    api_key_file: provider.api_key_file.clone(),
                     models,
+                    extra_headers: (!provider.extra_headers.is_empty())
+                        .then(|| provider.extra_headers.clone()),
+                    extra_query: (!provider.extra_query.is_empty())
+                        .then(|| provider.extra_query.clone()),
                 })
             })
             .collect();
@@ -810,12 +2084,39 @@ impl Config {
             selected_provider: Some(self.selected_provider.clone()),
             default_model: Some(self.default_model.clone()),
             api_keys: Some(self.api_keys.clone()),
+            encrypt_api_keys: self.encrypt_api_keys.then_some(true),
+            encrypted_api_keys: None,
             model_providers: Some(model_providers),
             ui: Some(UiConfigToml {
                 theme: Some(self.ui.theme.clone()),
-                show_emojis: Some(self.ui.show_usage_counter),
-                max_history_lines: Some(self.ui.auto_save_interval as usize),
+                show_usage_counter: Some(self.ui.show_usage_counter),
+                show_emojis: None,
+                max_history_lines: Some(self.ui.max_history_lines),
+                greetings: (!self.ui.greetings.is_empty()).then(|| self.ui.greetings.clone()),
+                reduced_motion: Some(self.ui.reduced_motion),
+                restore_last_session: Some(self.ui.restore_last_session),
+                confirm_cost_above: self.ui.confirm_cost_above,
+                density: Some(self.ui.density),
+                response_locale: self.ui.response_locale.clone(),
+                auto_continue_max_steps: Some(self.ui.auto_continue_max_steps),
+                submit_key: Some(self.ui.submit_key),
+                terminal_title: Some(self.ui.terminal_title),
+                require_api_key: Some(self.ui.require_api_key),
+                syntax_highlighting: Some(self.ui.syntax_highlighting),
+                show_reasoning: Some(self.ui.show_reasoning),
+                auto_save_interval: Some(self.ui.auto_save_interval),
+                daily_message_limit: Some(self.ui.daily_message_limit),
+                request_timeout_secs: Some(self.ui.request_timeout_secs),
+                request_retries: Some(self.ui.request_retries),
+                retry_base_delay_ms: Some(self.ui.retry_base_delay_ms),
+                model_catalog_ttl_secs: Some(self.ui.model_catalog_ttl_secs),
+                debug_log: Some(self.ui.debug_log),
             }),
+            favorite_models: Some(self.favorite_models.clone()),
+            redaction: (self.redaction != RedactionConfig::default()).then(|| self.redaction.clone()),
+            fallback: (!self.fallback.providers.is_empty()).then(|| self.fallback.clone()),
+            mode_sampling: (self.mode_sampling != ModeSamplingConfig::default()).then(|| self.mode_sampling.clone()),
+            loop_guard: (self.loop_guard != LoopGuardConfig::default()).then(|| self.loop_guard.clone()),
         }
     }
 }
@@ -826,8 +2127,448 @@ impl Default for ConfigToml {
             selected_provider: None,
             default_model: None,
             api_keys: None,
+            encrypt_api_keys: None,
+            encrypted_api_keys: None,
             model_providers: None,
             ui: None,
+            favorite_models: None,
+            redaction: None,
+            fallback: None,
+            mode_sampling: None,
+            loop_guard: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(home: &Path) -> Config {
+        Config::load_from(home.to_path_buf()).unwrap()
+    }
+
+    #[test]
+    fn removed_api_key_stays_removed_after_save() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        config.set_api_key("openai".to_string(), "sk-openai-key".to_string());
+        config.set_api_key("anthropic".to_string(), "sk-anthropic-key".to_string());
+        config.save().unwrap();
+
+        let mut config = load(home.path());
+        config.remove_api_key("openai");
+        config.save().unwrap();
+
+        let reloaded = load(home.path());
+        assert!(!reloaded.api_keys.contains_key("openai"));
+        assert_eq!(reloaded.api_keys.get("anthropic").map(String::as_str), Some("sk-anthropic-key"));
+    }
+
+    #[test]
+    fn clearing_a_key_removes_it_from_disk() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        config.set_api_key("openai".to_string(), "sk-openai-key".to_string());
+        config.save().unwrap();
+
+        config.set_api_key("openai".to_string(), String::new());
+        config.save().unwrap();
+
+        assert!(!load(home.path()).api_keys.contains_key("openai"));
+    }
+
+    #[test]
+    fn save_keeps_keys_another_instance_added() {
+        let home = tempfile::tempdir().unwrap();
+        let mut first = load(home.path());
+        let mut second = load(home.path());
+
+        second.set_api_key("xai".to_string(), "xai-secret-key".to_string());
+        second.save().unwrap();
+        first.set_api_key("openai".to_string(), "sk-openai-key".to_string());
+        first.save().unwrap();
+
+        let reloaded = load(home.path());
+        assert!(reloaded.api_keys.contains_key("openai"));
+        assert!(reloaded.api_keys.contains_key("xai"));
+    }
+
+    #[test]
+    fn removing_a_provider_used_by_a_mode_and_fallback_keeps_config_loadable() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        let provider_id = config
+            .add_custom_provider("Local Llama", "http://localhost:11434/v1", None, "llama3.1:8b")
+            .unwrap();
+        let execute = config.mode_sampling.for_mode_mut(BindrMode::Execute);
+        execute.provider = Some(provider_id.clone());
+        execute.model = Some("llama3.1:8b".to_string());
+        execute.temperature = Some(0.1);
+        config.fallback.providers.push(FallbackTarget {
+            provider: provider_id.clone(),
+            model: "llama3.1:8b".to_string(),
+        });
+        config.fallback.providers.push(FallbackTarget {
+            provider: "anthropic".to_string(),
+            model: "claude-3-5-haiku-20241022".to_string(),
+        });
+        config.save().unwrap();
+        load(home.path());
+
+        config.remove_custom_provider(&provider_id).unwrap();
+        config.save().unwrap();
+
+        let reloaded = load(home.path());
+        assert!(!reloaded.model_providers.contains_key(&provider_id));
+        let execute = reloaded.mode_sampling.for_mode(BindrMode::Execute);
+        assert_eq!(execute.provider, None);
+        assert_eq!(execute.model, None);
+        assert_eq!(execute.temperature, Some(0.1));
+        let fallbacks: Vec<&str> = reloaded.fallback.providers.iter().map(|target| target.provider.as_str()).collect();
+        assert_eq!(fallbacks, ["anthropic"]);
+    }
+
+    #[test]
+    fn chat_completions_url_has_one_v1() {
+        let mut provider = Config::default().model_providers["openai"].clone();
+        for base_url in ["https://api.openai.com/v1", "https://api.openai.com", "https://api.openai.com/v1/"] {
+            provider.base_url = base_url.to_string();
+            assert_eq!(provider.chat_completions_url(), "https://api.openai.com/v1/chat/completions", "{}", base_url);
+        }
+    }
+
+    #[test]
+    fn messages_sent_with_a_key_are_not_counted() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        // Keep an exported OPENAI_API_KEY from standing in for a configured one
+        config.model_providers.get_mut("openai").unwrap().api_key_env = None;
+        config.selected_provider = "openai".to_string();
+
+        config.record_usage().unwrap();
+        config.record_usage().unwrap();
+        assert_eq!(config.get_usage_info(), (2, config.ui.daily_message_limit));
+
+        config.set_api_key("openai".to_string(), "sk-test".to_string());
+        config.record_usage().unwrap();
+        assert_eq!(config.get_usage_info().0, 2);
+    }
+
+    #[test]
+    fn switching_provider_keeps_or_replaces_the_default_model() {
+        let mut config = Config::default();
+        config.switch_provider("openai");
+        config.default_model = "gpt-4o".to_string();
+
+        assert!(config.switch_provider("anthropic"));
+        assert_eq!(config.selected_provider, "anthropic");
+        let first = config.model_providers["anthropic"].models[0].id.clone();
+        assert_eq!(config.default_model, first);
+
+        assert!(!config.switch_provider("nowhere"));
+        assert_eq!(config.selected_provider, "anthropic");
+    }
+
+    #[test]
+    fn well_formed_openrouter_ids_are_accepted() {
+        for id in ["openai/gpt-4o", "meta-llama/llama-3.1-8b-instruct", "mistralai/mistral_7b:free", "x-ai/grok-4:beta"] {
+            assert_eq!(openrouter_model_id_problem(id), None, "{}", id);
+        }
+    }
+
+    #[test]
+    fn malformed_openrouter_ids_are_rejected() {
+        let problem = |id| openrouter_model_id_problem(id).unwrap_or_default();
+
+        assert!(problem("gpt-4o").contains("needs a vendor prefix"));
+        assert!(problem("").contains("needs a vendor prefix"));
+        assert!(problem("/gpt-4o").contains("isn't a model id"));
+        assert!(problem("openai/").contains("isn't a model id"));
+        assert!(problem("openai/gpt 4o").contains("isn't a model id"));
+        assert!(problem("openai/gpt-4o/extra").contains("isn't a model id"));
+        assert!(problem("openai/gpt-4o:").contains("malformed tag"));
+        assert!(problem("openai/gpt-4o:free!").contains("malformed tag"));
+    }
+
+    #[test]
+    fn catalog_merge_adds_new_models_and_updates_prices() {
+        let mut config = Config::default();
+        let known = config.model_providers["openrouter"].models[0].clone();
+        let catalog = [
+            ModelInfo { name: "Renamed".to_string(), input_cost: Some(1.0), output_cost: None, ..known.clone() },
+            ModelInfo {
+                id: "vendor/new-model".to_string(),
+                name: "New".to_string(),
+                description: String::new(),
+                is_premium: true,
+                input_cost: Some(0.5),
+                output_cost: Some(1.5),
+            },
+        ];
+
+        assert_eq!(config.merge_models("openrouter", &catalog), 1);
+        assert_eq!(config.merge_models("openrouter", &catalog), 0);
+
+        let models = &config.model_providers["openrouter"].models;
+        let merged = models.iter().find(|model| model.id == known.id).unwrap();
+        assert_eq!(merged.name, known.name);
+        assert_eq!(merged.input_cost, Some(1.0));
+        assert_eq!(merged.output_cost, known.output_cost);
+        assert!(models.iter().any(|model| model.id == "vendor/new-model"));
+    }
+
+    #[test]
+    fn custom_provider_survives_a_save_and_load() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        let id = config
+            .add_custom_provider("Local Llama", "http://localhost:11434/v1/", Some("LLAMA_KEY"), "llama3.1:8b")
+            .unwrap();
+        assert_eq!(id, "local-llama");
+        config.save().unwrap();
+
+        let reloaded = load(home.path());
+        let provider = &reloaded.model_providers["local-llama"];
+        assert_eq!(provider.name, "Local Llama");
+        assert_eq!(provider.base_url, "http://localhost:11434/v1");
+        assert_eq!(provider.api_key_env.as_deref(), Some("LLAMA_KEY"));
+        let models: Vec<&str> = provider.models.iter().map(|model| model.id.as_str()).collect();
+        assert_eq!(models, ["llama3.1:8b"]);
+    }
+
+    #[test]
+    fn custom_provider_names_are_checked() {
+        let mut config = Config::default();
+        let add = |config: &mut Config, name: &str, url: &str, env: Option<&str>, model: &str| {
+            config.add_custom_provider(name, url, env, model).unwrap_err().to_string()
+        };
+
+        assert!(add(&mut config, "OpenAI", "https://example.com", None, "m").contains("reserved"));
+        assert!(add(&mut config, "!!!", "https://example.com", None, "m").contains("at least one letter"));
+        assert!(add(&mut config, "Mine", "example.com", None, "m").contains("http://"));
+        assert!(add(&mut config, "Mine", "https://example.com", None, "two words").contains("without spaces"));
+        assert!(add(&mut config, "Mine", "https://example.com", Some("BAD-NAME"), "m").contains("environment variable"));
+
+        config.add_custom_provider("Mine", "https://example.com", None, "m").unwrap();
+        assert!(add(&mut config, "mine", "https://example.com", None, "m").contains("already exists"));
+    }
+
+    #[test]
+    fn removed_custom_provider_stays_removed() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        let id = config.add_custom_provider("Mine", "https://example.com", None, "m").unwrap();
+        config.set_api_key(id.clone(), "sk-mine".to_string());
+        config.save().unwrap();
+
+        let mut config = load(home.path());
+        config.remove_custom_provider(&id).unwrap();
+        assert!(config.remove_custom_provider("openai").is_err());
+        config.save().unwrap();
+
+        let reloaded = load(home.path());
+        assert!(!reloaded.model_providers.contains_key(&id));
+        assert!(!reloaded.api_keys.contains_key(&id));
+    }
+
+    #[test]
+    fn user_models_and_descriptions_survive_a_load() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("config.toml"), r#"
+[model_providers.openai]
+name = "OpenAI"
+base_url = "https://stale.example.com/v1"
+
+[[model_providers.openai.models]]
+id = "gpt-5"
+name = "GPT-5"
+description = "My everyday model"
+
+[[model_providers.openai.models]]
+id = "ft:gpt-4o-mini:acme"
+name = "Acme fine-tune"
+description = "Tuned on our docs"
+input_cost = 0.3
+"#).unwrap();
+
+        let config = load(home.path());
+        let openai = &config.model_providers["openai"];
+        let model = |id: &str| openai.models.iter().find(|model| model.id == id);
+
+        assert_eq!(model("gpt-5").map(|m| m.description.as_str()), Some("My everyday model"));
+        let tuned = model("ft:gpt-4o-mini:acme").unwrap();
+        assert_eq!((tuned.name.as_str(), tuned.input_cost), ("Acme fine-tune", Some(0.3)));
+        // Built-ins stay current: the rest of the catalog is there and the URL is the built-in one
+        let builtin = &Config::create_default_model_providers()["openai"];
+        for builtin_model in &builtin.models {
+            assert!(model(&builtin_model.id).is_some(), "{}", builtin_model.id);
         }
+        assert_eq!(openai.base_url, builtin.base_url);
+    }
+
+    #[test]
+    fn premium_flag_survives_a_save_and_load() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        let id = config.add_custom_provider("Mine", "https://example.com", None, "cheap").unwrap();
+        let provider = config.model_providers.get_mut(&id).unwrap();
+        provider.models.push(ModelInfo {
+            id: "pricey".to_string(),
+            name: "Pricey".to_string(),
+            description: "Costs money".to_string(),
+            is_premium: true,
+            input_cost: None,
+            output_cost: None,
+        });
+        config.save().unwrap();
+
+        let reloaded = load(home.path());
+        let premium: Vec<(&str, bool)> = reloaded.model_providers[&id]
+            .models
+            .iter()
+            .map(|model| (model.id.as_str(), model.is_premium))
+            .collect();
+        assert_eq!(premium, [("cheap", false), ("pricey", true)]);
+    }
+
+    #[test]
+    fn models_without_a_premium_flag_load_as_free() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("config.toml"), r#"
+[model_providers.mine]
+name = "Mine"
+base_url = "https://example.com/v1"
+
+[[model_providers.mine.models]]
+id = "plain"
+name = "Plain"
+"#).unwrap();
+
+        let config = load(home.path());
+        assert!(!config.model_providers["mine"].models[0].is_premium);
+    }
+
+    #[test]
+    fn ui_fields_each_map_to_themselves() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = load(home.path());
+        config.ui.show_usage_counter = false;
+        config.ui.auto_save_interval = 45;
+        config.ui.max_history_lines = 250;
+        config.save().unwrap();
+
+        let saved = std::fs::read_to_string(home.path().join("config.toml")).unwrap();
+        assert!(saved.contains("show_usage_counter = false"));
+        assert!(saved.contains("auto_save_interval = 45"));
+        assert!(saved.contains("max_history_lines = 250"));
+        assert!(!saved.contains("show_emojis"));
+
+        let reloaded = load(home.path());
+        assert!(!reloaded.ui.show_usage_counter);
+        assert_eq!(reloaded.ui.auto_save_interval, 45);
+        assert_eq!(reloaded.ui.max_history_lines, 250);
+    }
+
+    #[test]
+    fn old_show_emojis_key_still_hides_the_counter() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("config.toml"), "[ui]\nshow_emojis = false\nmax_history_lines = 1000\n").unwrap();
+
+        let config = load(home.path());
+        assert!(!config.ui.show_usage_counter);
+        // The history limit no longer doubles as the auto-save interval
+        assert_eq!(config.ui.max_history_lines, 1000);
+        assert_eq!(config.ui.auto_save_interval, DEFAULT_AUTO_SAVE_INTERVAL);
+    }
+
+    #[test]
+    fn tiny_history_limit_is_raised_to_the_minimum() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("config.toml"), "[ui]\nmax_history_lines = 2\n").unwrap();
+        assert_eq!(load(home.path()).ui.max_history_lines, MIN_HISTORY_LINES);
+    }
+
+    #[test]
+    fn mode_model_falls_back_to_the_selected_one() {
+        let mut config = Config::default();
+        config.switch_provider("openai");
+        config.default_model = "gpt-4o-mini".to_string();
+        config.mode_sampling.for_mode_mut(BindrMode::Execute).model = Some("gpt-4.1".to_string());
+        config.mode_sampling.for_mode_mut(BindrMode::Plan).provider = Some("anthropic".to_string());
+
+        assert_eq!(config.mode_model(BindrMode::Brainstorm), ("openai".to_string(), "gpt-4o-mini".to_string()));
+        assert_eq!(config.mode_model(BindrMode::Execute), ("openai".to_string(), "gpt-4.1".to_string()));
+        // A mode naming only a provider takes that provider's first model
+        let first = config.model_providers["anthropic"].models[0].id.clone();
+        assert_eq!(config.mode_model(BindrMode::Plan), ("anthropic".to_string(), first));
+    }
+
+    #[test]
+    fn debug_output_masks_every_key() {
+        let mut config = Config::default();
+        config.set_api_key("openai".to_string(), "sk-proj-abcdefghijklmnop1234".to_string());
+        let openrouter = config.model_providers.get_mut("openrouter").unwrap();
+        openrouter.extra_headers.insert("Authorization".to_string(), "Bearer sk-or-headersecret9876".to_string());
+        openrouter.extra_headers.insert("HTTP-Referer".to_string(), "https://example.com".to_string());
+        openrouter.extra_query.insert("api-key".to_string(), "querysecret5555".to_string());
+
+        let debug = format!("{:?}", config);
+        let pretty = format!("{:#?}", config);
+        // The TOML form holds the same keys on their way to disk
+        let toml = format!("{:?}", config.to_config_toml());
+
+        for output in [&debug, &pretty, &toml] {
+            assert!(!output.contains("abcdefghijklmnop"));
+            assert!(!output.contains("headersecret"));
+            assert!(!output.contains("querysecret"));
+            assert!(output.contains("****1234"));
+            // Values that aren't secrets stay readable
+            assert!(output.contains("https://example.com"));
+        }
+    }
+
+    #[test]
+    fn masked_keys_show_only_a_short_suffix() {
+        assert_eq!(mask_key("sk-proj-abcdefghijklmnop1234"), "****1234");
+        assert_eq!(mask_key("short"), "****");
+        assert_eq!(mask_key("12345678"), "****");
+        assert_eq!(mask_key("ключ-секрет-длинный"), "****нный");
+    }
+
+    #[test]
+    fn key_file_is_read_without_its_trailing_newline() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir(home.path().join("keys")).unwrap();
+        std::fs::write(home.path().join("keys/openai"), "sk-from-file\n\n").unwrap();
+        std::fs::write(home.path().join("config.toml"), "[model_providers.openai]\napi_key_file = \"keys/openai\"\n").unwrap();
+
+        let config = load(home.path());
+        assert_eq!(config.get_api_key_for("openai").as_deref(), Some("sk-from-file"));
+    }
+
+    #[test]
+    fn inline_key_beats_file_beats_environment() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("openai.key"), "sk-from-file").unwrap();
+        let mut config = load(home.path());
+        // PATH is always set, so it stands in for the provider's key variable without touching the environment
+        let from_env = std::env::var("PATH").unwrap();
+        let openai = config.model_providers.get_mut("openai").unwrap();
+        openai.api_key_env = Some("PATH".to_string());
+        assert_eq!(config.get_api_key_for("openai"), Some(from_env.clone()));
+
+        config.model_providers.get_mut("openai").unwrap().api_key_file = Some("openai.key".into());
+        assert_eq!(config.get_api_key_for("openai").as_deref(), Some("sk-from-file"));
+
+        config.set_api_key("openai".to_string(), "sk-inline".to_string());
+        assert_eq!(config.get_api_key_for("openai").as_deref(), Some("sk-inline"));
+
+        // An empty or missing file falls through to the environment
+        config.remove_api_key("openai");
+        std::fs::write(home.path().join("openai.key"), "  \n").unwrap();
+        assert_eq!(config.get_api_key_for("openai"), Some(from_env.clone()));
+        config.model_providers.get_mut("openai").unwrap().api_key_file = Some("missing.key".into());
+        assert_eq!(config.get_api_key_for("openai"), Some(from_env));
     }
 }
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use ratatui::text::Line;
 
+use crate::handoff::ModeHandoff;
+
 /// Internal application events for coordinating between components
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -72,6 +74,25 @@ pub enum BindrMode {
 }
 
 impl BindrMode {
+    /// Every mode, in workflow order
+    pub const ALL: [BindrMode; 4] = [
+        BindrMode::Brainstorm,
+        BindrMode::Plan,
+        BindrMode::Execute,
+        BindrMode::Document,
+    ];
+
+    /// Mode named by a full or one-letter name, e.g. `plan` or `p`; `build` and `doc` also work
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "b" | "brainstorm" => Some(BindrMode::Brainstorm),
+            "p" | "plan" => Some(BindrMode::Plan),
+            "e" | "execute" | "build" => Some(BindrMode::Execute),
+            "d" | "doc" | "document" => Some(BindrMode::Document),
+            _ => None,
+        }
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             BindrMode::Brainstorm => "Brainstorm",
@@ -81,6 +102,16 @@ impl BindrMode {
         }
     }
     
+    /// Sampling temperature used unless `[mode_sampling]` or /temperature says otherwise
+    pub fn default_temperature(&self) -> f32 {
+        match self {
+            BindrMode::Brainstorm => 0.9,
+            BindrMode::Plan => 0.6,
+            BindrMode::Execute => 0.2,
+            BindrMode::Document => 0.4,
+        }
+    }
+    
     #[allow(dead_code)]
     pub fn description(&self) -> &'static str {
         match self {
@@ -114,6 +145,15 @@ pub struct ProjectState {
     pub conversation_history: Vec<ConversationEntry>,
     pub conversation_count: usize,
     pub last_activity: chrono::DateTime<chrono::Utc>,
+    /// Response language chosen with /lang for this project, overriding `[ui].response_locale`
+    #[serde(default)]
+    pub response_locale: Option<String>,
+    /// Messages marked with /flag for later review; never sent to the model
+    #[serde(default)]
+    pub flagged_messages: Vec<FlaggedMessage>,
+    /// Latest handoff into each mode, written when the previous mode was left
+    #[serde(default)]
+    pub handoffs: Vec<ModeHandoff>,
 }
 
 /// Individual conversation entry
@@ -123,6 +163,19 @@ pub struct ConversationEntry {
     pub role: ConversationRole,
     pub content: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Model an assistant reply was requested from; absent in sessions saved before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+}
+
+/// Message marked for later review, identified by its timestamp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlaggedMessage {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub role: ConversationRole,
+    /// Opening words of the message, so the flag is readable after the session ends
+    pub excerpt: String,
+    pub note: Option<String>,
 }
 
 /// Role in conversation
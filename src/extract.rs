@@ -0,0 +1,194 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Pull a JSON object out of an assistant message.
+///
+/// Prefers a fenced ```json block, then falls back to the first balanced
+/// `{...}` span that parses. Trailing commas are tolerated.
+pub fn extract_json_block(text: &str) -> Option<Value> {
+    fenced_blocks(text)
+        .into_iter()
+        .chain(brace_spans(text))
+        .find_map(parse_lenient)
+}
+
+/// Extract and deserialize a JSON object embedded in prose
+pub fn extract_json<T: DeserializeOwned>(text: &str) -> Option<T> {
+    extract_json_block(text).and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// JSON objects in fences tagged ```<tag>, in order of appearance; blocks that don't parse are skipped
+pub fn tagged_json_blocks(text: &str, tag: &str) -> Vec<Value> {
+    fenced_blocks_where(text, |fence_tag| fence_tag.eq_ignore_ascii_case(tag))
+        .into_iter()
+        .filter_map(parse_lenient)
+        .collect()
+}
+
+/// Contents of ```json (or untagged ```) fences, in order of appearance
+fn fenced_blocks(text: &str) -> Vec<&str> {
+    fenced_blocks_where(text, |tag| tag.is_empty() || tag.eq_ignore_ascii_case("json"))
+}
+
+/// Contents of fences whose tag passes `accept`, in order of appearance
+fn fenced_blocks_where(text: &str, accept: impl Fn(&str) -> bool) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("```") {
+        let after_fence = &rest[start + 3..];
+        let Some(line_end) = after_fence.find('\n') else {
+            break;
+        };
+        let tag = after_fence[..line_end].trim();
+        let body = &after_fence[line_end + 1..];
+        let Some(end) = body.find("```") else {
+            break;
+        };
+        if accept(tag) {
+            blocks.push(&body[..end]);
+        }
+        rest = &body[end + 3..];
+    }
+
+    blocks
+}
+
+/// Every balanced `{...}` span, skipping braces inside string literals
+fn brace_spans(text: &str) -> Vec<&str> {
+    let mut spans = Vec::new();
+
+    for (start, _) in text.match_indices('{') {
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (offset, ch) in text[start..].char_indices() {
+            if in_string {
+                match ch {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match ch {
+                '"' => in_string = true,
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        spans.push(&text[start..start + offset + 1]);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    spans
+}
+
+/// Parse JSON, retrying once with trailing commas removed
+fn parse_lenient(candidate: &str) -> Option<Value> {
+    let candidate = candidate.trim();
+    serde_json::from_str(candidate)
+        .or_else(|_| serde_json::from_str(&strip_trailing_commas(candidate)))
+        .ok()
+        .filter(Value::is_object)
+}
+
+fn strip_trailing_commas(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let chars: Vec<char> = text.chars().collect();
+
+    for (index, &ch) in chars.iter().enumerate() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if ch == ',' {
+            let next = chars[index + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_a_json_fence() {
+        let text = "Here is the handoff:\n```json\n{\"project_name\": \"Tide\", \"tech_stack\": [\"Rust\"]}\n```\nReady for Plan.";
+        assert_eq!(
+            extract_json_block(text),
+            Some(json!({"project_name": "Tide", "tech_stack": ["Rust"]}))
+        );
+    }
+
+    #[test]
+    fn finds_an_object_in_prose() {
+        let text = "Summary so far {\"description\": \"A {braced} idea\", \"key_features\": []} and that's it.";
+        assert_eq!(
+            extract_json_block(text),
+            Some(json!({"description": "A {braced} idea", "key_features": []}))
+        );
+    }
+
+    #[test]
+    fn prefers_the_fence_over_prose_braces() {
+        let text = "Use {curly} quotes.\n```\n{\"project_name\": \"Fenced\"}\n```";
+        assert_eq!(extract_json_block(text), Some(json!({"project_name": "Fenced"})));
+    }
+
+    #[test]
+    fn tolerates_trailing_commas() {
+        let text = "```json\n{\"key_features\": [\"a\", \"b\",], \"constraints\": [],}\n```";
+        assert_eq!(
+            extract_json_block(text),
+            Some(json!({"key_features": ["a", "b"], "constraints": []}))
+        );
+    }
+
+    #[test]
+    fn keeps_commas_inside_strings() {
+        let text = "{\"description\": \"fast, small,}\"}";
+        assert_eq!(extract_json_block(text), Some(json!({"description": "fast, small,}"})));
+    }
+
+    #[test]
+    fn skips_a_broken_fence_for_a_later_object() {
+        let text = "```json\n{\"project_name\": \n```\nFixed: {\"project_name\": \"Second try\"}";
+        assert_eq!(extract_json_block(text), Some(json!({"project_name": "Second try"})));
+    }
+
+    #[test]
+    fn ignores_text_without_an_object() {
+        assert_eq!(extract_json_block("No JSON here, just [1, 2] and {unquoted: keys}."), None);
+    }
+
+    #[test]
+    fn collects_only_blocks_with_the_tag() {
+        let text = "```tool\n{\"tool\": \"read_file\"}\n```\n```json\n{\"other\": 1}\n```\n```TOOL\n{\"tool\": \"list_directory\"}\n```";
+        assert_eq!(
+            tagged_json_blocks(text, "tool"),
+            vec![json!({"tool": "read_file"}), json!({"tool": "list_directory"})]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{BindrMode, ConversationEntry, ConversationRole};
+use crate::extract;
+
+/// Longest description taken from the conversation when no handoff block was written
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// Most list items taken from the last reply when no handoff block was written
+const MAX_SYNTHESIZED_ITEMS: usize = 8;
+
+/// What one mode hands the next, in the JSON shape the mode prompts ask the model for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandoffSummary {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub project_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub key_features: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tech_stack: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<String>,
+    /// Agreed structure and milestones, carried from Plan into Execute
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plan_highlights: Vec<String>,
+}
+
+/// Context carried across one mode transition, saved with the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModeHandoff {
+    pub mode_from: BindrMode,
+    pub mode_to: BindrMode,
+    pub summary: HandoffSummary,
+}
+
+/// The part of a model-written handoff block Bindr keeps; the modes come from the actual switch
+#[derive(Deserialize)]
+struct HandoffBlock {
+    summary: HandoffSummary,
+}
+
+impl ModeHandoff {
+    /// Handoff for leaving `from` for `to`: the latest JSON handoff block a reply in `history`
+    /// wrote, else a summary drawn from the conversation. `None` when `from` has no replies yet.
+    pub fn from_history(
+        from: BindrMode,
+        to: BindrMode,
+        project_name: Option<&str>,
+        history: &[ConversationEntry],
+    ) -> Option<Self> {
+        let replies: Vec<&str> = history
+            .iter()
+            .filter(|entry| entry.mode == from && matches!(entry.role, ConversationRole::Assistant))
+            .map(|entry| entry.content.as_str())
+            .collect();
+        let last_reply = *replies.last()?;
+
+        let written = replies
+            .iter()
+            .rev()
+            .find_map(|reply| extract::extract_json::<HandoffBlock>(reply))
+            .map(|block| block.summary);
+        let mut summary = written.unwrap_or_else(|| Self::synthesize(from, history, last_reply));
+        if summary.project_name.is_empty()
+            && let Some(name) = project_name
+        {
+            summary.project_name = name.to_string();
+        }
+
+        Some(Self { mode_from: from, mode_to: to, summary })
+    }
+
+    /// Summary drawn from the mode's first request and the list items of its last reply
+    fn synthesize(from: BindrMode, history: &[ConversationEntry], last_reply: &str) -> HandoffSummary {
+        let description = history
+            .iter()
+            .find(|entry| entry.mode == from && matches!(entry.role, ConversationRole::User))
+            .and_then(|entry| entry.content.lines().find(|line| !line.trim().is_empty()))
+            .map(|line| line.trim().chars().take(MAX_DESCRIPTION_CHARS).collect())
+            .unwrap_or_default();
+        let items: Vec<String> = last_reply
+            .lines()
+            .filter_map(list_item)
+            .take(MAX_SYNTHESIZED_ITEMS)
+            .collect();
+
+        let mut summary = HandoffSummary { description, ..HandoffSummary::default() };
+        if from == BindrMode::Plan {
+            summary.plan_highlights = items;
+        } else {
+            summary.key_features = items;
+        }
+        summary
+    }
+
+    /// System prompt section that passes this handoff to the next mode
+    pub fn prompt_section(&self) -> String {
+        let summary = serde_json::to_string_pretty(&self.summary).unwrap_or_default();
+        format!("Handoff from {} mode:\n```json\n{}\n```", self.mode_from.display_name(), summary)
+    }
+}
+
+/// Text of a `-`, `*`, or numbered list item
+fn list_item(line: &str) -> Option<String> {
+    let line = line.trim();
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            (digits > 0).then(|| line[digits..].strip_prefix(". ")).flatten()
+        })?;
+    let item = item.trim().trim_matches('*').trim();
+    (!item.is_empty()).then(|| item.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mode: BindrMode, role: ConversationRole, content: &str) -> ConversationEntry {
+        ConversationEntry { mode, role, content: content.to_string(), timestamp: chrono::Utc::now(), model_id: None }
+    }
+
+    #[test]
+    fn written_handoff_block_is_used() {
+        let reply = "Here is the summary.\n```json\n{\"summary\": {\"description\": \"Plant tracker\", \"key_features\": [\"Watering reminders\"], \"tech_stack\": [\"Rust\"]}}\n```";
+        let history = [
+            entry(BindrMode::Brainstorm, ConversationRole::User, "An app for plants"),
+            entry(BindrMode::Brainstorm, ConversationRole::Assistant, reply),
+        ];
+
+        let handoff = ModeHandoff::from_history(BindrMode::Brainstorm, BindrMode::Plan, Some("garden"), &history).unwrap();
+
+        assert_eq!((handoff.mode_from, handoff.mode_to), (BindrMode::Brainstorm, BindrMode::Plan));
+        assert_eq!(handoff.summary, HandoffSummary {
+            project_name: "garden".to_string(),
+            description: "Plant tracker".to_string(),
+            key_features: vec!["Watering reminders".to_string()],
+            tech_stack: vec!["Rust".to_string()],
+            ..HandoffSummary::default()
+        });
+    }
+
+    #[test]
+    fn handoff_is_synthesized_without_a_block() {
+        let history = [
+            entry(BindrMode::Plan, ConversationRole::User, "\nPlan the garden app\nin detail"),
+            entry(BindrMode::Plan, ConversationRole::Assistant, "Steps:\n1. Set up the crate\n- **Add storage**\nDone."),
+        ];
+
+        let handoff = ModeHandoff::from_history(BindrMode::Plan, BindrMode::Execute, None, &history).unwrap();
+
+        assert_eq!(handoff.summary.description, "Plan the garden app");
+        assert_eq!(handoff.summary.plan_highlights, ["Set up the crate", "Add storage"]);
+        assert!(handoff.summary.key_features.is_empty());
+    }
+
+    #[test]
+    fn mode_without_replies_hands_nothing_over() {
+        let history = [
+            entry(BindrMode::Brainstorm, ConversationRole::User, "ideas?"),
+            entry(BindrMode::Plan, ConversationRole::Assistant, "A reply from another mode"),
+        ];
+        assert_eq!(ModeHandoff::from_history(BindrMode::Brainstorm, BindrMode::Plan, None, &history), None);
+    }
+
+    #[test]
+    fn prompt_section_names_the_previous_mode() {
+        let handoff = ModeHandoff {
+            mode_from: BindrMode::Brainstorm,
+            mode_to: BindrMode::Plan,
+            summary: HandoffSummary { project_name: "garden".to_string(), ..HandoffSummary::default() },
+        };
+        assert_eq!(handoff.prompt_section(), "Handoff from Brainstorm mode:\n```json\n{\n  \"project_name\": \"garden\"\n}\n```");
+    }
+}
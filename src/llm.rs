@@ -1,13 +1,93 @@
-use crate::config::{Config, ModelProvider};
+use crate::config::{Config, ModelInfo, ModelProvider};
+use crate::pricing::TokenUsage;
 use crate::events::BindrMode;
+use crate::request_log::RequestLog;
+use crate::sse::{SseFormat, SseParser};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 use futures::StreamExt;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Fetch time and models of the last OpenRouter model catalog request
+type ModelCatalogCache = Option<(Instant, Vec<ModelInfo>)>;
+
+/// Recently fetched OpenRouter models, shared across client instances
+static OPENROUTER_MODELS_CACHE: Lazy<Mutex<ModelCatalogCache>> = Lazy::new(|| Mutex::new(None));
+
+/// Temperature used when a request doesn't specify one
+const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// Output-token limit used when a request doesn't specify one
+const DEFAULT_MAX_TOKENS: u32 = 4000;
+
+/// Longest `Retry-After` wait honored before giving up on a provider instead
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(30);
+
+/// Sampling parameters a provider/model pair accepts
+struct SamplingSupport {
+    /// Accepted temperature range, or `None` when the model rejects the parameter
+    temperature: Option<(f32, f32)>,
+    /// Name of the output-token limit field in the request body
+    max_tokens_field: &'static str,
+}
+
+impl SamplingSupport {
+    /// Limits for `model` on the provider with config id `provider_id`; display names vary, ids don't
+    fn for_model(provider_id: &str, model: &str) -> Self {
+        // OpenRouter ids are namespaced by vendor, e.g. `openai/o3-mini`
+        let (vendor, model_name) = match model.split_once('/') {
+            Some((vendor, name)) if provider_id == "openrouter" => (vendor.to_lowercase(), name),
+            _ => (provider_id.to_string(), model),
+        };
+        let reasoning = vendor == "openai" && Self::is_openai_reasoning_model(model_name);
+
+        let temperature = if reasoning {
+            None
+        } else {
+            Some(match vendor.as_str() {
+                "anthropic" => (0.0, 1.0),
+                "mistral" | "mistralai" => (0.0, 1.5),
+                _ => (0.0, 2.0),
+            })
+        };
+        // OpenRouter translates `max_tokens` itself; OpenAI's reasoning models don't accept it
+        let max_tokens_field = if reasoning && provider_id == "openai" {
+            "max_completion_tokens"
+        } else {
+            "max_tokens"
+        };
+
+        Self { temperature, max_tokens_field }
+    }
+
+    /// o-series and GPT-5 reasoning models only run at their default temperature
+    fn is_openai_reasoning_model(model: &str) -> bool {
+        let model = model.to_lowercase();
+        ["o1", "o3", "o4", "gpt-5"].iter().any(|prefix| model.starts_with(prefix))
+            && !model.contains("chat")
+    }
+
+    /// Requested temperature clamped to the supported range, or `None` if it must be omitted
+    fn temperature(&self, requested: Option<f32>) -> Option<f32> {
+        let (min, max) = self.temperature?;
+        Some(requested.unwrap_or(DEFAULT_TEMPERATURE).clamp(min, max))
+    }
+
+    /// Add the supported sampling fields to a chat-completions style payload
+    fn apply(&self, payload: &mut serde_json::Value, temperature: Option<f32>, max_tokens: Option<u32>) {
+        if let Some(temperature) = self.temperature(temperature) {
+            payload["temperature"] = serde_json::json!(temperature);
+        }
+        payload[self.max_tokens_field] = serde_json::json!(max_tokens.unwrap_or(DEFAULT_MAX_TOKENS));
+    }
+}
 
 /// Events emitted during LLM streaming
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LlmEvent {
     /// Text delta from streaming response
     TextDelta(String),
@@ -19,8 +99,144 @@ pub enum LlmEvent {
     StreamComplete,
     /// Error occurred
     Error(String),
+    /// The provider rejected its API key or has none configured (carries the provider id)
+    KeyRejected(String),
+    /// The turn moved to a `[fallback]` provider after the previous one failed
+    FallbackUsed {
+        note: String,
+        /// Model asked next, which answers the turn unless it fails too
+        model_id: String,
+    },
+    /// The provider was busy; the same request is sent again after a pause
+    Retrying(String),
+    /// Token counts the provider reported; a count of 0 means it wasn't in this event
+    Usage(TokenUsage),
 }
 
+/// Error raised when a provider answers 401/403 to an authenticated request
+#[derive(Debug)]
+pub struct ApiKeyRejected {
+    pub provider_name: String,
+    pub status: reqwest::StatusCode,
+}
+
+impl std::fmt::Display for ApiKeyRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid API key ({}): {} rejected it — press Ctrl+K to update it",
+            self.status.as_u16(),
+            self.provider_name
+        )
+    }
+}
+
+impl std::error::Error for ApiKeyRejected {}
+
+/// Error raised when a provider sends nothing for the whole `[ui].request_timeout_secs`
+#[derive(Debug)]
+pub struct RequestStalled {
+    pub provider_name: String,
+    pub timeout: Duration,
+}
+
+impl std::fmt::Display for RequestStalled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Request timed out: {} sent nothing for {}s", self.provider_name, self.timeout.as_secs())
+    }
+}
+
+impl std::error::Error for RequestStalled {}
+
+/// Error response from a provider, with the message pulled out of its JSON envelope
+#[derive(Debug)]
+pub struct LlmError {
+    pub provider_name: String,
+    pub status: reqwest::StatusCode,
+    /// The envelope's `message`, or the raw body when it isn't one
+    pub message: String,
+    /// The envelope's `code` or `type`, e.g. `rate_limit_exceeded`
+    pub code: Option<String>,
+    /// Wait the provider asked for in its `Retry-After` header
+    pub retry_after: Option<Duration>,
+}
+
+impl LlmError {
+    /// Longest raw body quoted when the provider didn't send an error envelope
+    const MAX_RAW_BODY: usize = 300;
+
+    /// Build the error from a non-success response's status, headers, and body
+    fn from_response(provider_name: String, status: reqwest::StatusCode, retry_after: Option<Duration>, body: &str) -> Self {
+        let (message, code) = match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(json) => Self::parse_envelope(&json),
+            Err(_) => (None, None),
+        };
+        let message = message.unwrap_or_else(|| {
+            let body = body.trim();
+            match body.char_indices().nth(Self::MAX_RAW_BODY) {
+                Some((end, _)) => format!("{}…", &body[..end]),
+                None => body.to_string(),
+            }
+        });
+        Self { provider_name, status, message, code, retry_after }
+    }
+
+    /// Message and code of `{"error": {"message", "type", "code"}}`, the shape OpenAI, Anthropic,
+    /// Google, and the OpenAI-compatible APIs share; a bare `{"error": "..."}` or `{"message": ...}` also counts
+    fn parse_envelope(json: &serde_json::Value) -> (Option<String>, Option<String>) {
+        let error = json.get("error").unwrap_or(json);
+        if let Some(message) = error.as_str() {
+            return (Some(message.to_string()), None);
+        }
+        let message = error.get("message").and_then(|m| m.as_str()).map(str::to_string);
+        let code = ["code", "type", "status"].iter().find_map(|field| match error.get(*field)? {
+            serde_json::Value::String(code) => Some(code.clone()),
+            _ => None,
+        });
+        (message, code)
+    }
+
+    /// Rate limits and server errors that may clear up if the request is sent again
+    pub fn is_transient(&self) -> bool {
+        matches!(self.status.as_u16(), 429 | 500 | 502 | 503 | 504 | 529)
+    }
+
+    /// Failures another provider could avoid: transient ones, an exhausted account, or a provider-side timeout
+    pub fn is_fallback_worthy(&self) -> bool {
+        self.is_transient() || matches!(self.status.as_u16(), 402 | 408)
+    }
+
+    fn summary(&self) -> &'static str {
+        match self.status.as_u16() {
+            400 | 422 => "Bad request",
+            402 => "Payment required",
+            404 => "Not found",
+            408 => "Request timed out",
+            413 => "Request too large",
+            429 => "Rate limited",
+            503 | 529 => "Provider overloaded",
+            500..=599 => "Provider error",
+            _ => "Request failed",
+        }
+    }
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}", self.summary(), self.status.as_u16())?;
+        if let Some(code) = &self.code {
+            write!(f, ", {}", code)?;
+        }
+        write!(f, ") from {}", self.provider_name)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LlmError {}
+
 /// Request to send to LLM
 #[derive(Debug, Clone)]
 pub struct LlmRequest {
@@ -45,18 +261,97 @@ pub struct LlmMessage {
 pub struct LlmClient {
     config: Config,
     client: reqwest::Client,
+    /// Longest wait to connect, and for each event of a streamed reply
+    request_timeout: Duration,
+    /// Times a busy provider is retried before falling back or giving up
+    max_retries: u32,
+    /// Pause before the first retry, doubled for each one after
+    retry_base_delay: Duration,
 }
 
 impl LlmClient {
     pub fn new(config: Config) -> Self {
+        let request_timeout = Duration::from_secs(config.ui.request_timeout_secs.max(1));
+        // No overall timeout: a streamed reply may rightly run far longer, so streams
+        // are instead cut off when they go quiet for `request_timeout`
         let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(60))
+            .connect_timeout(request_timeout)
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { config, client }
+        let max_retries = config.ui.request_retries;
+        let retry_base_delay = Duration::from_millis(config.ui.retry_base_delay_ms);
+        Self { config, client, request_timeout, max_retries, retry_base_delay }
+    }
+
+
+    /// Fetch the ids of all models OpenRouter currently serves, using a short-lived cache
+    pub async fn openrouter_model_ids(&self) -> Result<Vec<String>> {
+        let models = self.fetch_openrouter_models(false).await?;
+        Ok(models.into_iter().map(|model| model.id).collect())
     }
 
+    /// Fetch every model OpenRouter currently serves, with names and prices.
+    ///
+    /// A catalog fetched within `model_catalog_ttl_secs` is reused unless `refresh` is set.
+    pub async fn fetch_openrouter_models(&self, refresh: bool) -> Result<Vec<ModelInfo>> {
+        let ttl = Duration::from_secs(self.config.ui.model_catalog_ttl_secs);
+        if !refresh
+            && let Some((fetched_at, models)) = OPENROUTER_MODELS_CACHE.lock().unwrap().as_ref()
+            && fetched_at.elapsed() < ttl
+        {
+            return Ok(models.clone());
+        }
+
+        let provider = self.config.model_providers
+            .get("openrouter")
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter provider is not configured"))?;
+        let url = provider.v1_url("models");
+
+        let mut builder = self.client.get(&url).timeout(self.request_timeout);
+        if let Some(api_key) = self.config.get_api_key_for("openrouter") {
+            builder = builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+        let response = Self::apply_provider_extras(builder, provider).send().await?;
+        let response = Self::ensure_success(provider, response).await?;
+
+        let body: serde_json::Value = response.json().await?;
+        let models: Vec<ModelInfo> = body
+            .get("data")
+            .and_then(|data| data.as_array())
+            .map(|models| models.iter().filter_map(Self::openrouter_model).collect())
+            .unwrap_or_default();
+
+        *OPENROUTER_MODELS_CACHE.lock().unwrap() = Some((Instant::now(), models.clone()));
+        Ok(models)
+    }
+
+    /// One entry of OpenRouter's `/models` listing; prices come as USD-per-token strings
+    fn openrouter_model(entry: &serde_json::Value) -> Option<ModelInfo> {
+        let id = entry.get("id")?.as_str()?.to_string();
+        let name = entry.get("name").and_then(|name| name.as_str()).unwrap_or(&id).to_string();
+        let description = entry
+            .get("description")
+            .and_then(|description| description.as_str())
+            .and_then(|description| description.lines().next())
+            .filter(|line| !line.trim().is_empty())
+            .unwrap_or("Added from the live catalog")
+            .to_string();
+        let per_million = |field: &str| {
+            entry
+                .get("pricing")?
+                .get(field)?
+                .as_str()?
+                .parse::<f64>()
+                .ok()
+                .filter(|price| *price >= 0.0)
+                .map(|price| price * 1_000_000.0)
+        };
+        let input_cost = per_million("prompt");
+        let output_cost = per_million("completion");
+        let free = id.ends_with(":free") || (input_cost == Some(0.0) && output_cost == Some(0.0));
+        Some(ModelInfo { id, name, description, is_premium: !free, input_cost, output_cost })
+    }
 
     /// Stream a response from the configured LLM provider
     pub async fn stream_response(
@@ -65,12 +360,6 @@ impl LlmClient {
     ) -> Result<mpsc::Receiver<LlmEvent>> {
         let (tx, rx) = mpsc::channel(1000);
 
-        // Check if we have an API key configured
-        if !self.config.has_api_key() {
-            let _ = tx.send(LlmEvent::Error("No API key configured. Please add an API key first.".to_string())).await;
-            return Ok(rx);
-        }
-        
         let provider_id = request.provider_id.clone()
             .unwrap_or_else(|| self.config.selected_provider.clone());
 
@@ -79,48 +368,164 @@ impl LlmClient {
             .ok_or_else(|| anyhow::anyhow!("No provider configured for id {}", provider_id))?
             .clone();
 
-        let api_key = self.config.get_api_key_for(&provider_id)
-            .ok_or_else(|| anyhow::anyhow!("No API key configured for provider {}", provider_id))?;
+        // A missing key is reported on the channel like any failed reply, so it shows as an error notice
+        let Some(api_key) = self.config.get_api_key_for(&provider_id) else {
+            let _ = tx.send(LlmEvent::KeyRejected(provider_id.clone())).await;
+            let _ = tx.send(LlmEvent::Error(format!(
+                "No API key configured for {}. Press Ctrl+K to add one.",
+                provider.name
+            ))).await;
+            return Ok(rx);
+        };
 
         let model = request.model_id.clone().unwrap_or_else(|| self.config.default_model.clone());
 
+        let mut attempts = vec![(provider_id.clone(), provider, model.clone(), api_key)];
+        for target in &self.config.fallback.providers {
+            if target.provider == provider_id && target.model == model {
+                continue;
+            }
+            let Some(provider) = self.config.model_providers.get(&target.provider) else {
+                continue;
+            };
+            let Some(api_key) = self.config.get_api_key_for(&target.provider) else {
+                continue;
+            };
+            attempts.push((target.provider.clone(), provider.clone(), target.model.clone(), api_key));
+        }
+
         // Spawn streaming task
         let client = self.client.clone();
+        let request_timeout = self.request_timeout;
+        let (max_retries, retry_base_delay) = (self.max_retries, self.retry_base_delay);
+        let log = RequestLog::from_config(&self.config);
         
-        let tx_clone = tx.clone();
         tokio::spawn(async move {
-            if let Err(e) = Self::stream_from_provider(
-                client,
-                provider,
-                model,
-                api_key,
-                request,
-                tx,
-            ).await {
-                let _ = tx_clone.send(LlmEvent::Error(e.to_string())).await;
+            let total = attempts.len();
+            let fallback_models: Vec<String> = attempts.iter().skip(1).map(|(_, _, model, _)| model.clone()).collect();
+            for (index, (provider_id, provider, model, api_key)) in attempts.into_iter().enumerate() {
+                let provider_name = provider.name.clone();
+                let mut retries = 0;
+                let (result, emitted) = loop {
+                    log.request(&provider_id, &model, request.messages.len());
+                    let (attempt_tx, mut attempt_rx) = mpsc::channel(1000);
+                    let stalled = tokio::sync::Notify::new();
+                    let attempt = async {
+                        tokio::select! {
+                            result = Self::stream_from_provider(
+                                client.clone(),
+                                &provider_id,
+                                provider.clone(),
+                                model.clone(),
+                                api_key.clone(),
+                                request.clone(),
+                                attempt_tx,
+                            ) => result,
+                            _ = stalled.notified() => Err(RequestStalled {
+                                provider_name: provider_name.clone(),
+                                timeout: request_timeout,
+                            }
+                            .into()),
+                        }
+                    };
+                    // Relay this attempt's events, noting whether any reply text reached the UI
+                    let relay = async {
+                        let mut emitted = false;
+                        loop {
+                            match tokio::time::timeout(request_timeout, attempt_rx.recv()).await {
+                                Ok(Some(event)) => {
+                                    emitted |= matches!(event, LlmEvent::TextDelta(_) | LlmEvent::ReasoningDelta(_));
+                                    if tx.send(event).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                Ok(None) => break,
+                                Err(_) => {
+                                    stalled.notify_one();
+                                    break;
+                                }
+                            }
+                        }
+                        emitted
+                    };
+                    let (result, emitted) = tokio::join!(attempt, relay);
+                    log.outcome(&provider_id, &model, &result);
+
+                    // Only a request the provider turned away before replying is safe to send again
+                    let busy = result
+                        .as_ref()
+                        .err()
+                        .and_then(|e| e.downcast_ref::<LlmError>())
+                        .filter(|e| e.is_transient());
+                    let wait = busy
+                        .filter(|_| !emitted && retries < max_retries && !tx.is_closed())
+                        .map(|busy| busy.retry_after.unwrap_or(retry_base_delay * 2u32.pow(retries)))
+                        .filter(|wait| *wait <= MAX_RETRY_WAIT);
+                    let (Some(busy), Some(wait)) = (busy, wait) else {
+                        break (result, emitted);
+                    };
+                    retries += 1;
+                    let _ = tx.send(LlmEvent::Retrying(format!(
+                        "{} is busy ({}), retrying in {}s ({}/{})",
+                        provider_name,
+                        busy.status,
+                        wait.as_secs_f32().ceil(),
+                        retries,
+                        max_retries
+                    ))).await;
+                    tokio::time::sleep(wait).await;
+                };
+
+                let Err(e) = result else {
+                    return;
+                };
+                if e.downcast_ref::<ApiKeyRejected>().is_some() {
+                    let _ = tx.send(LlmEvent::KeyRejected(provider_id)).await;
+                }
+                // A half-streamed reply can't be resumed elsewhere, and a closed channel means the user cancelled
+                let can_fall_back = index + 1 < total && !emitted && !tx.is_closed() && Self::is_fallback_worthy(&e);
+                if !can_fall_back {
+                    let _ = tx.send(LlmEvent::Error(e.to_string())).await;
+                    return;
+                }
+                let _ = tx.send(LlmEvent::FallbackUsed {
+                    note: format!("{} ({}) failed: {}. Trying the next fallback provider.", provider_name, model, e),
+                    model_id: fallback_models[index].clone(),
+                }).await;
             }
         });
 
         Ok(rx)
     }
 
-    /// Stream from specific provider
+    /// Whether a failed request looks like an outage, rate limit, or account problem that another provider could avoid
+    fn is_fallback_worthy(error: &anyhow::Error) -> bool {
+        match error.downcast_ref::<LlmError>() {
+            Some(error) => error.is_fallback_worthy(),
+            // Connection failures and stalls are the provider's, not the request's
+            None => error.is::<ApiKeyRejected>() || error.is::<RequestStalled>() || error.is::<reqwest::Error>(),
+        }
+    }
+
+    /// Stream from specific provider, dispatching on its config id since display names vary
     async fn stream_from_provider(
         client: reqwest::Client,
+        provider_id: &str,
         provider: ModelProvider,
         model: String,
         api_key: String,
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        match provider.name.to_lowercase().as_str() {
+        match provider_id {
             "openai" => Self::stream_openai(client, provider, model, api_key, request, tx).await,
             "anthropic" => Self::stream_anthropic(client, provider, model, api_key, request, tx).await,
             "google" => Self::stream_google(client, provider, model, api_key, request, tx).await,
             "xai" => Self::stream_xai(client, provider, model, api_key, request, tx).await,
             "openrouter" => Self::stream_openrouter(client, provider, model, api_key, request, tx).await,
             "mistral" => Self::stream_mistral(client, provider, model, api_key, request, tx).await,
-            _ => Err(anyhow::anyhow!("Unsupported provider: {}", provider.name)),
+            // Providers added by the user speak the OpenAI chat completions protocol
+            _ => Self::stream_custom(client, provider, model, api_key, request, tx).await,
         }
     }
 
@@ -133,30 +538,28 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/v1/chat/completions", provider.base_url);
+        let url = provider.chat_completions_url();
         
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "model": model,
             "messages": request.messages,
             "stream": true,
-            "temperature": request.temperature.unwrap_or(0.7),
-            "max_tokens": request.max_tokens.unwrap_or(4000)
+            // Adds a final chunk with the turn's token counts
+            "stream_options": {"include_usage": true}
         });
+        SamplingSupport::for_model("openai", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        let response = client
+        let builder = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("OpenAI API error: {}", error_text));
-        }
-
-        Self::process_sse_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::OpenAi, tx).await
     }
 
     /// Stream from Anthropic API
@@ -168,7 +571,7 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/v1/messages", provider.base_url);
+        let url = provider.v1_url("messages");
         
         // Convert messages to Anthropic format
         let mut messages = Vec::new();
@@ -185,30 +588,26 @@ impl LlmClient {
             }
         }
 
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "model": model,
             "messages": messages,
             "system": system,
-            "stream": true,
-            "temperature": request.temperature.unwrap_or(0.7),
-            "max_tokens": request.max_tokens.unwrap_or(4000)
+            "stream": true
         });
+        SamplingSupport::for_model("anthropic", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        let response = client
+        let builder = client
             .post(&url)
             .header("x-api-key", api_key)
             .header("Content-Type", "application/json")
             .header("anthropic-version", "2023-06-01")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Anthropic API error: {}", error_text));
-        }
-
-        Self::process_anthropic_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::Anthropic, tx).await
     }
 
     /// Stream from Google Gemini API
@@ -220,8 +619,9 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/models/{}:streamGenerateContent?key={}", 
-                         provider.base_url, model, api_key);
+        // alt=sse makes Gemini send each chunk as it is generated instead of one JSON array at the end.
+        // The key goes in a header: request errors print the URL, and it must not carry the key.
+        let url = format!("{}/models/{}:streamGenerateContent?alt=sse", provider.base_url, model);
         
         // Convert messages to Gemini format
         let mut contents = Vec::new();
@@ -241,30 +641,31 @@ impl LlmClient {
         let mut payload = serde_json::json!({
             "contents": contents,
             "generationConfig": {
-                "temperature": request.temperature.unwrap_or(0.7),
-                "maxOutputTokens": request.max_tokens.unwrap_or(4000)
+                "maxOutputTokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)
             }
         });
 
+        if let Some(temperature) = SamplingSupport::for_model("google", &model).temperature(request.temperature) {
+            payload["generationConfig"]["temperature"] = serde_json::json!(temperature);
+        }
+
         if !system_instruction.is_empty() {
             payload["systemInstruction"] = serde_json::json!({
                 "parts": [{"text": system_instruction}]
             });
         }
 
-        let response = client
+        let builder = client
             .post(&url)
+            .header("x-goog-api-key", api_key)
             .header("Content-Type", "application/json")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Google API error: {}", error_text));
-        }
-
-        Self::process_google_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::Google, tx).await
     }
 
     /// Stream from xAI Grok API
@@ -276,30 +677,28 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/v1/chat/completions", provider.base_url);
+        let url = provider.chat_completions_url();
         
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "model": model,
             "messages": request.messages,
             "stream": true,
-            "temperature": request.temperature.unwrap_or(0.7),
-            "max_tokens": request.max_tokens.unwrap_or(4000)
+            // Adds a final chunk with the turn's token counts
+            "stream_options": {"include_usage": true}
         });
+        SamplingSupport::for_model("xai", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        let response = client
+        let builder = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("xAI API error: {}", error_text));
-        }
-
-        Self::process_sse_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::OpenAi, tx).await
     }
 
     /// Stream from OpenRouter API
@@ -311,42 +710,28 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/v1/chat/completions", provider.base_url);
+        let url = provider.chat_completions_url();
         
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "model": model,
             "messages": request.messages,
             "stream": true,
-            "temperature": request.temperature.unwrap_or(0.7),
-            "max_tokens": request.max_tokens.unwrap_or(4000)
+            // Adds a final chunk with the turn's token counts
+            "stream_options": {"include_usage": true}
         });
+        SamplingSupport::for_model("openrouter", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        let response = client
+        let builder = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .header("HTTP-Referer", "https://bindr.dev")
-            .header("X-Title", "Bindr")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            let detail = if error_text.trim().is_empty() {
-                "<empty response>".to_string()
-            } else {
-                error_text
-            };
-            return Err(anyhow::anyhow!(
-                "OpenRouter API error (status {}): {}",
-                status,
-                detail
-            ));
-        }
-
-        Self::process_sse_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::OpenAi, tx).await
     }
 
     /// Stream from Mistral AI API
@@ -358,293 +743,124 @@ impl LlmClient {
         request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let url = format!("{}/v1/chat/completions", provider.base_url);
+        let url = provider.chat_completions_url();
         
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "model": model,
             "messages": request.messages,
-            "stream": true,
-            "temperature": request.temperature.unwrap_or(0.7),
-            "max_tokens": request.max_tokens.unwrap_or(4000)
+            // Mistral rejects unknown fields, and reports usage on its final chunk unasked
+            "stream": true
         });
+        SamplingSupport::for_model("mistral", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        let response = client
+        let builder = client
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&payload)
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
             .send()
             .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Mistral API error: {}", error_text));
-        }
-
-        Self::process_sse_stream(response, tx).await
+        Self::process_sse_stream(response, SseFormat::OpenAi, tx).await
     }
 
-    /// Process Server-Sent Events stream (OpenAI, xAI, OpenRouter, Mistral)
-    async fn process_sse_stream(
-        response: reqwest::Response,
+    /// Stream from a user-added OpenAI-compatible endpoint
+    async fn stream_custom(
+        client: reqwest::Client,
+        provider: ModelProvider,
+        model: String,
+        api_key: String,
+        request: LlmRequest,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
-        let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
-        let mut assistant_text = String::new();
+        let url = provider.chat_completions_url();
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            let text = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&text);
-
-            // Process complete lines
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].trim().to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-                    if data == "[DONE]" {
-                        // Emit final accumulated message if we have content
-                        if !assistant_text.is_empty() {
-                            let _ = tx.send(LlmEvent::ResponseComplete(assistant_text)).await;
-                        }
-                        let _ = tx.send(LlmEvent::StreamComplete).await;
-                        return Ok(());
-                    }
-
-                    if let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) {
-                        if let Some(choices) = chunk.get("choices").and_then(|c| c.get(0)) {
-                            // Handle streaming deltas
-                            if let Some(delta) = choices.get("delta") {
-                                if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
-                                    assistant_text.push_str(content);
-                                    let _ = tx.send(LlmEvent::TextDelta(content.to_string())).await;
-                                }
-                            }
-                            
-                            // Handle finish_reason
-                            if let Some(finish_reason) = choices.get("finish_reason").and_then(|v| v.as_str()) {
-                                if finish_reason == "stop" && !assistant_text.is_empty() {
-                                    let _ = tx.send(LlmEvent::ResponseComplete(assistant_text.clone())).await;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        // Leave out `stream_options`: not every compatible server accepts it
+        let mut payload = serde_json::json!({
+            "model": model,
+            "messages": request.messages,
+            "stream": true
+        });
+        // User-added providers aren't a known vendor, so they get the general limits
+        SamplingSupport::for_model("custom", &model).apply(&mut payload, request.temperature, request.max_tokens);
 
-        // Flush any remaining buffer line (without newline)
-        let line = buffer.trim();
-        if line.starts_with("data: ") {
-            let data = &line[6..];
-            if data != "[DONE]" {
-                if let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) {
-                    if let Some(choices) = chunk.get("choices").and_then(|c| c.get(0)) {
-                        if let Some(delta) = choices.get("delta") {
-                            if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
-                                assistant_text.push_str(content);
-                                let _ = tx.send(LlmEvent::TextDelta(content.to_string())).await;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let builder = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&payload);
+        let response = Self::apply_provider_extras(builder, &provider)
+            .send()
+            .await?;
+        let response = Self::ensure_success(&provider, response).await?;
 
-        // Emit final accumulated message if we have content
-        if !assistant_text.is_empty() {
-            let _ = tx.send(LlmEvent::ResponseComplete(assistant_text)).await;
-        }
-        let _ = tx.send(LlmEvent::StreamComplete).await;
-        Ok(())
+        Self::process_sse_stream(response, SseFormat::OpenAi, tx).await
     }
 
-    /// Process Anthropic streaming format
-    async fn process_anthropic_stream(
-        response: reqwest::Response,
-        tx: mpsc::Sender<LlmEvent>,
-    ) -> Result<()> {
-        let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
-        let mut assistant_text = String::new();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            let text = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&text);
-
-            // Process complete lines
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].trim().to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-                    if data == "[DONE]" {
-                        // Emit final accumulated message if we have content
-                        if !assistant_text.is_empty() {
-                            let _ = tx.send(LlmEvent::ResponseComplete(assistant_text)).await;
-                        }
-                        let _ = tx.send(LlmEvent::StreamComplete).await;
-                        return Ok(());
-                    }
-
-                    if let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) {
-                        if let Some(content_block) = chunk.get("content_block") {
-                            if let Some(text) = content_block.get("text").and_then(|t| t.as_str()) {
-                                assistant_text.push_str(text);
-                                let _ = tx.send(LlmEvent::TextDelta(text.to_string())).await;
-                            }
-                        }
-                        
-                        // Handle stop event
-                        if let Some(stop_reason) = chunk.get("stop_reason").and_then(|v| v.as_str()) {
-                            if stop_reason == "end_turn" && !assistant_text.is_empty() {
-                                let _ = tx.send(LlmEvent::ResponseComplete(assistant_text.clone())).await;
-                            }
-                        }
-                    }
-                }
-            }
+    /// Pass a successful response through; turn anything else into `ApiKeyRejected` or `LlmError`
+    async fn ensure_success(provider: &ModelProvider, response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
         }
-
-        // Flush any remaining buffer line (without newline)
-        let line = buffer.trim();
-        if line.starts_with("data: ") {
-            let data = &line[6..];
-            if data != "[DONE]" {
-                if let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) {
-                    if let Some(content_block) = chunk.get("content_block") {
-                        if let Some(text) = content_block.get("text").and_then(|t| t.as_str()) {
-                            assistant_text.push_str(text);
-                            let _ = tx.send(LlmEvent::TextDelta(text.to_string())).await;
-                        }
-                    }
-                }
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(ApiKeyRejected {
+                provider_name: provider.name.clone(),
+                status,
             }
+            .into());
         }
+        // Only the delay-seconds form; an HTTP date falls back to the backoff schedule
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await.unwrap_or_default();
+        Err(LlmError::from_response(provider.name.clone(), status, retry_after, &body).into())
+    }
 
-        // Emit final accumulated message if we have content
-        if !assistant_text.is_empty() {
-            let _ = tx.send(LlmEvent::ResponseComplete(assistant_text)).await;
+    /// Attach the provider's configured extra headers and query parameters to a request
+    fn apply_provider_extras(
+        mut builder: reqwest::RequestBuilder,
+        provider: &ModelProvider,
+    ) -> reqwest::RequestBuilder {
+        for (name, value) in &provider.extra_headers {
+            builder = builder.header(name.as_str(), value.as_str());
         }
-        let _ = tx.send(LlmEvent::StreamComplete).await;
-        Ok(())
+        if !provider.extra_query.is_empty() {
+            builder = builder.query(&provider.extra_query);
+        }
+        builder
     }
 
-    /// Process Google Gemini streaming format
-    async fn process_google_stream(
+    /// Feed a Server-Sent Events body through `SseParser`, forwarding events as they complete
+    async fn process_sse_stream(
         response: reqwest::Response,
+        format: SseFormat,
         tx: mpsc::Sender<LlmEvent>,
     ) -> Result<()> {
+        let mut parser = SseParser::new(format);
         let mut stream = response.bytes_stream();
-        let mut buffer = Vec::new();
 
         while let Some(chunk) = stream.next().await {
-            buffer.extend_from_slice(&chunk?);
-        }
-
-        let buffer_str = String::from_utf8_lossy(&buffer);
-        let mut latest_text = String::new();
-
-        // Google returns JSON objects per chunk (often wrapped in an array when complete)
-        if let Ok(response_array) = serde_json::from_str::<Vec<serde_json::Value>>(&buffer_str) {
-            for response_json in response_array {
-                if let Some(chunk_text) = Self::extract_google_text(&response_json) {
-                    if chunk_text.len() > latest_text.len() {
-                        latest_text = chunk_text;
-                    }
-                }
+            for event in parser.push(&chunk?) {
+                let _ = tx.send(event).await;
             }
-        } else if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&buffer_str) {
-            if let Some(chunk_text) = Self::extract_google_text(&response_json) {
-                if chunk_text.len() > latest_text.len() {
-                    latest_text = chunk_text;
-                }
-            }
-        } else {
-            // Attempt to parse concatenated JSON objects without delimiters
-            let mut deserializer = serde_json::Deserializer::from_str(&buffer_str).into_iter::<serde_json::Value>();
-            while let Some(Ok(value)) = deserializer.next() {
-                if let Some(chunk_text) = Self::extract_google_text(&value) {
-                    if chunk_text.len() > latest_text.len() {
-                        latest_text = chunk_text;
-                    }
-                }
+            // Nobody is listening any more; dropping the response closes the connection
+            if parser.is_done() || tx.is_closed() {
+                return Ok(());
             }
         }
 
-        if !latest_text.is_empty() {
-            Self::simulate_streaming(&latest_text, tx.clone()).await;
-            let _ = tx.send(LlmEvent::ResponseComplete(latest_text)).await;
+        for event in parser.finish() {
+            let _ = tx.send(event).await;
         }
-        let _ = tx.send(LlmEvent::StreamComplete).await;
         Ok(())
     }
-
-    /// Simulate streaming by breaking text into chunks with delays
-    async fn simulate_streaming(text: &str, tx: mpsc::Sender<LlmEvent>) {
-        // For short responses, stream character by character
-        // For longer responses, stream word by word
-        if text.len() < 50 {
-            for ch in text.chars() {
-                let _ = tx.send(LlmEvent::TextDelta(ch.to_string())).await;
-                tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
-            }
-        } else {
-            let words: Vec<&str> = text.split_whitespace().collect();
-            
-            for (i, word) in words.iter().enumerate() {
-                let chunk = if i == 0 {
-                    word.to_string()
-                } else {
-                    format!(" {}", word)
-                };
-                
-                // Send the chunk
-                let _ = tx.send(LlmEvent::TextDelta(chunk)).await;
-                
-                // Add a small delay to simulate typing
-                tokio::time::sleep(tokio::time::Duration::from_millis(80)).await;
-            }
-        }
-    }
-
-    /// Extract concatenated text from Google response JSON
-    fn extract_google_text(value: &serde_json::Value) -> Option<String> {
-        let mut collected = String::new();
-
-        if let Some(candidates) = value.get("candidates").and_then(|c| c.as_array()) {
-            for candidate in candidates {
-                let parts_iter = candidate
-                    .get("content")
-                    .and_then(|content| {
-                        if content.is_array() {
-                            content.as_array().cloned()
-                        } else {
-                            content.get("parts").and_then(|parts| parts.as_array().cloned())
-                        }
-                    })
-                    .into_iter()
-                    .flatten();
-
-                for part in parts_iter {
-                    if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                        collected.push_str(text);
-                    }
-                }
-            }
-        }
-
-        if collected.is_empty() {
-            None
-        } else {
-            Some(collected)
-        }
-    }
 }
 
 /// Helper to create system messages for different modes
@@ -689,3 +905,296 @@ impl LlmRequest {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_provider::{openai_reply, MockProvider};
+
+    #[test]
+    fn openai_error_body_is_summarized() {
+        let body = r#"{"error":{"message":"Rate limit reached for gpt-4o in organization org-x on tokens per min.","type":"tokens","param":null,"code":"rate_limit_exceeded"}}"#;
+        let error = LlmError::from_response("OpenAI".to_string(), reqwest::StatusCode::TOO_MANY_REQUESTS, None, body);
+
+        assert_eq!(error.code.as_deref(), Some("rate_limit_exceeded"));
+        assert_eq!(
+            error.to_string(),
+            "Rate limited (429, rate_limit_exceeded) from OpenAI: Rate limit reached for gpt-4o in organization org-x on tokens per min."
+        );
+    }
+
+    #[test]
+    fn anthropic_error_body_is_summarized() {
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let error = LlmError::from_response("Anthropic".to_string(), reqwest::StatusCode::from_u16(529).unwrap(), None, body);
+
+        assert_eq!(error.to_string(), "Provider overloaded (529, overloaded_error) from Anthropic: Overloaded");
+    }
+
+    #[test]
+    fn non_json_error_body_is_quoted_and_capped() {
+        let error = LlmError::from_response("Mistral AI".to_string(), reqwest::StatusCode::BAD_GATEWAY, None, "  <html>Bad Gateway</html>\n");
+        assert_eq!(error.to_string(), "Provider error (502) from Mistral AI: <html>Bad Gateway</html>");
+
+        let long = "x".repeat(1_000);
+        let error = LlmError::from_response("Mistral AI".to_string(), reqwest::StatusCode::BAD_GATEWAY, None, &long);
+        assert_eq!(error.message.chars().count(), LlmError::MAX_RAW_BODY + 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_key_points_to_ctrl_k() {
+        let body = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        let server = MockProvider::start(vec![(401, body.to_string())]).await;
+        let client = client_for("openai", &server);
+
+        let events = collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        assert_eq!(events, [
+            LlmEvent::KeyRejected("openai".to_string()),
+            LlmEvent::Error("Invalid API key (401): OpenAI rejected it — press Ctrl+K to update it".to_string()),
+        ]);
+    }
+
+    /// Client that sends `provider_id` requests to `server`, with a key set
+    fn client_for(provider_id: &str, server: &MockProvider) -> LlmClient {
+        let mut config = Config::default();
+        config.selected_provider = provider_id.to_string();
+        config.model_providers.get_mut(provider_id).unwrap().base_url = server.base_url.clone();
+        config.set_api_key(provider_id.to_string(), "test-key".to_string());
+        config.ui.retry_base_delay_ms = 1;
+        LlmClient::new(config)
+    }
+
+    fn request(model: &str) -> LlmRequest {
+        let messages = vec![LlmMessage { role: "user".to_string(), content: "Hi".to_string() }];
+        LlmRequest::new(messages, BindrMode::Brainstorm).with_model(model)
+    }
+
+    async fn collect(mut rx: mpsc::Receiver<LlmEvent>) -> Vec<LlmEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    fn reply_text(events: &[LlmEvent]) -> Option<&str> {
+        events.iter().find_map(|event| match event {
+            LlmEvent::ResponseComplete(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn mistral_requests_reach_the_mistral_endpoint() {
+        let server = MockProvider::start(vec![(200, openai_reply("Bonjour"))]).await;
+        let client = client_for("mistral", &server);
+
+        let events = collect(client.stream_response(request("mistral-large-latest")).await.unwrap()).await;
+
+        assert_eq!(reply_text(&events), Some("Bonjour"), "{:?}", events);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].target, "POST /v1/chat/completions");
+        assert_eq!(requests[0].body["model"], "mistral-large-latest");
+    }
+
+    #[tokio::test]
+    async fn usage_is_requested_and_reported() {
+        let usage = serde_json::json!({ "choices": [], "usage": { "prompt_tokens": 9, "completion_tokens": 2, "total_tokens": 11 } });
+        let reply = openai_reply("Hello").replace("data: [DONE]", &format!("data: {}\n\ndata: [DONE]", usage));
+        let server = MockProvider::start(vec![(200, reply)]).await;
+        let client = client_for("openai", &server);
+
+        let events = collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        assert_eq!(server.requests()[0].body["stream_options"]["include_usage"], true);
+        assert!(events.contains(&LlmEvent::Usage(TokenUsage { input_tokens: 9, output_tokens: 2 })), "{:?}", events);
+    }
+
+    const OPENROUTER_MODELS: &str = r#"{"data": [
+        {"id": "openai/gpt-4o", "name": "OpenAI: GPT-4o", "description": "Omni model.\nSecond line.",
+         "pricing": {"prompt": "0.0000025", "completion": "0.00001"}},
+        {"id": "meta-llama/llama-3.1-8b-instruct:free", "name": "Llama 3.1 8B (free)",
+         "pricing": {"prompt": "0", "completion": "0"}},
+        {"name": "No id, skipped"}
+    ]}"#;
+
+    #[tokio::test]
+    async fn openrouter_catalog_is_parsed_into_models() {
+        let server = MockProvider::start(vec![(200, OPENROUTER_MODELS.to_string())]).await;
+        let client = client_for("openrouter", &server);
+
+        let models = client.fetch_openrouter_models(true).await.unwrap();
+
+        assert_eq!(server.requests()[0].target, "GET /v1/models");
+        assert_eq!(models.len(), 2);
+        let gpt = &models[0];
+        assert_eq!((gpt.id.as_str(), gpt.name.as_str(), gpt.description.as_str()), ("openai/gpt-4o", "OpenAI: GPT-4o", "Omni model."));
+        assert!(gpt.is_premium);
+        assert!((gpt.input_cost.unwrap() - 2.5).abs() < 1e-9);
+        assert!((gpt.output_cost.unwrap() - 10.0).abs() < 1e-9);
+
+        let llama = &models[1];
+        assert_eq!(llama.description, "Added from the live catalog");
+        assert!(!llama.is_premium);
+        assert_eq!((llama.input_cost, llama.output_cost), (Some(0.0), Some(0.0)));
+    }
+
+    #[test]
+    fn request_timeout_comes_from_the_config() {
+        let mut config = Config::default();
+        config.ui.request_timeout_secs = 300;
+        assert_eq!(LlmClient::new(config.clone()).request_timeout, Duration::from_secs(300));
+
+        config.ui.request_timeout_secs = 0;
+        assert_eq!(LlmClient::new(config).request_timeout, Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn silent_provider_times_out_after_the_configured_wait() {
+        // Accepts the connection and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let hold = tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+        let mut config = Config::default();
+        config.selected_provider = "mistral".to_string();
+        config.model_providers.get_mut("mistral").unwrap().base_url = base_url;
+        config.set_api_key("mistral".to_string(), "test-key".to_string());
+        config.ui.request_timeout_secs = 1;
+
+        let started = std::time::Instant::now();
+        let events = collect(LlmClient::new(config).stream_response(request("mistral-small-latest")).await.unwrap()).await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(events, [LlmEvent::Error("Request timed out: Mistral AI sent nothing for 1s".to_string())]);
+        hold.abort();
+    }
+
+    #[tokio::test]
+    async fn busy_provider_is_retried_until_it_answers() {
+        let busy = (429, r#"{"error":{"message":"Rate limit reached","type":"requests"}}"#.to_string());
+        let server = MockProvider::start(vec![busy.clone(), busy, (200, openai_reply("Third time lucky"))]).await;
+        let mut client = client_for("openai", &server);
+        client.max_retries = 3;
+
+        let events = collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        assert_eq!(server.requests().len(), 3);
+        let retries = events.iter().filter(|event| matches!(event, LlmEvent::Retrying(_))).count();
+        assert_eq!(retries, 2);
+        assert_eq!(reply_text(&events), Some("Third time lucky"));
+        assert!(!events.iter().any(|event| matches!(event, LlmEvent::Error(_))));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_the_configured_limit() {
+        let busy = (503, "Service Unavailable".to_string());
+        let server = MockProvider::start(vec![busy]).await;
+        let mut client = client_for("openai", &server);
+        client.max_retries = 1;
+
+        let events = collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        assert_eq!(server.requests().len(), 2);
+        assert!(matches!(events.last(), Some(LlmEvent::Error(message)) if message.contains("503")), "{:?}", events);
+    }
+
+    #[tokio::test]
+    async fn missing_key_is_reported_on_the_channel() {
+        let mut config = Config::default();
+        config.selected_provider = "openai".to_string();
+        config.model_providers.get_mut("openai").unwrap().api_key_env = None;
+        let client = LlmClient::new(config);
+
+        let events = collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        assert_eq!(events, [
+            LlmEvent::KeyRejected("openai".to_string()),
+            LlmEvent::Error("No API key configured for OpenAI. Press Ctrl+K to add one.".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn debug_log_traces_a_request_without_secrets() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello"))]).await;
+        let mut client = client_for("openai", &server);
+        client.config.bindr_home = home.path().to_path_buf();
+        client.config.ui.debug_log = true;
+
+        collect(client.stream_response(request("gpt-4o")).await.unwrap()).await;
+
+        let log = std::fs::read_to_string(home.path().join("logs/requests.log")).unwrap();
+        assert!(log.contains("request provider=openai model=gpt-4o messages=1"), "{}", log);
+        assert!(log.contains("response provider=openai model=gpt-4o ok"), "{}", log);
+        assert!(!log.contains("test-key"));
+        assert!(!log.contains("Hi"));
+    }
+
+    #[test]
+    fn sampling_limits_follow_the_provider_id() {
+        let mistral = SamplingSupport::for_model("mistral", "mistral-large-latest");
+        assert_eq!(mistral.temperature(Some(2.0)), Some(1.5));
+
+        let anthropic = SamplingSupport::for_model("anthropic", "claude-sonnet-4-20250514");
+        assert_eq!(anthropic.temperature(Some(1.8)), Some(1.0));
+
+        let openai = SamplingSupport::for_model("openai", "gpt-4o");
+        assert_eq!(openai.temperature(Some(1.8)), Some(1.8));
+    }
+
+    #[test]
+    fn openrouter_models_use_their_vendor_limits() {
+        let mistral = SamplingSupport::for_model("openrouter", "mistralai/mistral-large");
+        assert_eq!(mistral.temperature(Some(2.0)), Some(1.5));
+
+        let reasoning = SamplingSupport::for_model("openrouter", "openai/o3-mini");
+        assert_eq!(reasoning.temperature(Some(0.7)), None);
+        assert_eq!(reasoning.max_tokens_field, "max_tokens");
+    }
+
+    #[test]
+    fn openai_reasoning_models_take_max_completion_tokens() {
+        let support = SamplingSupport::for_model("openai", "o3-mini");
+        assert_eq!(support.temperature(Some(0.7)), None);
+        assert_eq!(support.max_tokens_field, "max_completion_tokens");
+    }
+
+    fn provider_error(status: u16, body: &str) -> anyhow::Error {
+        let status = reqwest::StatusCode::from_u16(status).unwrap();
+        LlmError::from_response("OpenAI".to_string(), status, None, body).into()
+    }
+
+    #[test]
+    fn fallback_follows_the_status_code() {
+        assert!(LlmClient::is_fallback_worthy(&provider_error(503, "")));
+        assert!(LlmClient::is_fallback_worthy(&provider_error(429, r#"{"error":{"code":"insufficient_quota"}}"#)));
+        assert!(LlmClient::is_fallback_worthy(&provider_error(402, "")));
+        assert!(!LlmClient::is_fallback_worthy(&provider_error(404, "")));
+    }
+
+    #[test]
+    fn error_text_mentioning_status_codes_does_not_trigger_fallback() {
+        let bad_request = provider_error(400, r#"{"error":{"message":"max_tokens must be below 500; request timeout"}}"#);
+        assert!(!LlmClient::is_fallback_worthy(&bad_request));
+        assert!(!LlmClient::is_fallback_worthy(&anyhow::anyhow!("Unknown model gpt-500: rate limit docs")));
+    }
+
+    #[test]
+    fn rejected_keys_and_stalls_fall_back() {
+        let rejected = ApiKeyRejected {
+            provider_name: "OpenAI".to_string(),
+            status: reqwest::StatusCode::UNAUTHORIZED,
+        };
+        assert!(LlmClient::is_fallback_worthy(&rejected.into()));
+        let stalled = RequestStalled {
+            provider_name: "OpenAI".to_string(),
+            timeout: Duration::from_secs(60),
+        };
+        assert!(LlmClient::is_fallback_worthy(&stalled.into()));
+    }
+}
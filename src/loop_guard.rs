@@ -0,0 +1,36 @@
+//! Detection of replies that repeat the same text until they hit the token limit
+
+/// Longest repeating unit looked for, in bytes
+const MAX_SPAN: usize = 200;
+
+/// Repetition covering less than this many bytes is left alone (rules, table borders, ...)
+const MIN_LOOP_BYTES: usize = 200;
+
+/// Where a reply that ends in a loop should be cut so only one copy of the repeated text remains.
+///
+/// A loop is the same span of text repeated back to back at least `min_repeats`
+/// times at the end of `text`, covering at least `MIN_LOOP_BYTES`.
+pub fn loop_cut(text: &str, min_repeats: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    for span in 1..=MAX_SPAN {
+        let repeats = min_repeats.max(MIN_LOOP_BYTES.div_ceil(span));
+        let needed = span * repeats;
+        if needed > bytes.len() {
+            continue;
+        }
+        let tail = &bytes[bytes.len() - needed..];
+        if tail[span..].iter().zip(tail).all(|(later, earlier)| later == earlier) {
+            // Follow the repetition back to where it began
+            let mut start = bytes.len() - needed;
+            while start > 0 && bytes[start - 1] == bytes[start - 1 + span] {
+                start -= 1;
+            }
+            let mut cut = start + span;
+            while !text.is_char_boundary(cut) {
+                cut += 1;
+            }
+            return Some(cut);
+        }
+    }
+    None
+}
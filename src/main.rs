@@ -3,36 +3,51 @@ use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    style::Print,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
 };
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Terminal,
 };
 use std::io;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
 
 mod events;
 mod config;
 mod session;
 mod llm;
-mod streaming;
 mod agent;
 mod ui;
 mod prompts;
+mod stats;
+mod extract;
+mod handoff;
+mod persist;
+mod pricing;
+mod redact;
+mod request_log;
+mod loop_guard;
+mod sse;
+mod search;
+mod secrets;
+mod usage;
 pub mod tools;
+#[cfg(test)]
+mod mock_provider;
 
 
 use events::{AppEvent, BindrMode};
 use config::Config;
-use session::SessionManager;
+use session::{LastSession, SessionManager};
 use agent::AgentManager;
 use ui::conversation::ConversationManager;
-use tools::ToolRequestOutcome;
+use stats::ProjectStats;
 
 // Dark mode color palette
 const BG_PRIMARY: Color = Color::Rgb(16, 18, 24);      // Deep blue-black
@@ -45,11 +60,17 @@ const ACCENT_YELLOW: Color = Color::Rgb(241, 196, 15); // Warm yellow
 const ACCENT_RED: Color = Color::Rgb(255, 85, 85);     // Soft red
 const BORDER_COLOR: Color = Color::Rgb(48, 52, 70);    // Subtle border
 
+/// How long an error toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(10);
+
 #[derive(Parser)]
 #[command(name = "bindr")]
 #[command(version = "0.1.0")]
 #[command(about = "Multi-agent LLM workflow orchestration", long_about = None)]
 struct Cli {
+    /// Directory that file and command tools operate on (defaults to the launch directory)
+    #[arg(long, global = true, value_name = "DIR")]
+    workspace: Option<std::path::PathBuf>,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -59,7 +80,8 @@ struct AppState {
     show_model_selection: bool,
     current_mode: BindrMode,
     status_message: Option<String>,
-    pending_tool: Option<ToolRequestOutcome>,
+    /// When `status_message` was raised; the toast hides after `TOAST_DURATION`
+    status_shown_at: Option<Instant>,
 }
 
 impl Default for AppState {
@@ -69,7 +91,7 @@ impl Default for AppState {
             show_model_selection: false,
             current_mode: BindrMode::Brainstorm,
             status_message: None,
-            pending_tool: None,
+            status_shown_at: None,
         }
     }
 }
@@ -80,6 +102,80 @@ enum Commands {
     List,
     /// Open an existing project
     Open { name: String },
+    /// Reopen the most recently active project
+    Resume {
+        /// List the N most recently active projects instead (10 when N is omitted)
+        #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+        list: Option<usize>,
+    },
+    /// Show per-mode activity for a project
+    Stats { name: String },
+    /// Delete a project and its saved conversations
+    Delete {
+        name: String,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check that Bindr can write its config, projects, and sessions
+    Doctor,
+    /// Create the Bindr home with a commented starter config
+    Init {
+        /// Also write a template AGENTS.md into the workspace
+        #[arg(long)]
+        agents: bool,
+    },
+    /// Search conversations and project names across every project, newest first
+    Search {
+        query: String,
+        /// Treat the query as a regular expression instead of plain text
+        #[arg(long)]
+        regex: bool,
+        /// Show at most this many matches
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// List configured providers and models, optionally filtered by a search term
+    Models {
+        query: Option<String>,
+        /// Fetch the live catalog from providers that support listing
+        #[arg(long)]
+        refresh: bool,
+        /// Add newly discovered models to the config (requires --refresh)
+        #[arg(long, requires = "refresh")]
+        merge: bool,
+    },
+    /// Send one message and print the reply without opening the TUI
+    Chat {
+        /// Message to send; read from stdin when omitted
+        prompt: Option<String>,
+        /// Mode whose prompt and sampling to use: brainstorm, plan, execute, or document
+        #[arg(long, default_value = "brainstorm")]
+        mode: String,
+        /// Model id to use with the mode's provider instead of its configured model
+        #[arg(long)]
+        model: Option<String>,
+        /// Print the reply, model, and token usage as one JSON object once it is complete
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or change the configuration without opening the TUI
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective configuration, with API keys masked
+    Show,
+    /// Select the provider conversations use
+    SetProvider { id: String },
+    /// Set the default model, which the selected provider must offer
+    SetModel { id: String },
+    /// Store an API key for a provider in the config
+    SetKey { provider: String, key: String },
 }
 
 #[allow(dead_code)]
@@ -89,14 +185,40 @@ enum AppView {
     AddKey,
     SelectModel,
     CustomModelInput,
+    AddProvider,
     Conversation,
     ModelSelection,
+    ModelCatalog,
     Brainstorm,
     Plan,
     Execute,
     Document,
 }
 
+/// Labels and placeholders of the add-provider form, in the order the fields are shown
+const PROVIDER_FORM_FIELDS: [(&str, &str); 4] = [
+    ("Name", "My Ollama"),
+    ("Base URL", "http://localhost:11434/v1"),
+    ("API key variable (optional)", "OLLAMA_API_KEY"),
+    ("Model id", "llama3.1:8b"),
+];
+
+/// What has been typed into the add-provider form
+#[derive(Default)]
+struct ProviderForm {
+    values: [String; PROVIDER_FORM_FIELDS.len()],
+    /// Index of the field being edited
+    field: usize,
+    error: Option<String>,
+}
+
+/// A custom model id being looked up in OpenRouter's catalog
+struct CustomModelCheck {
+    model_id: String,
+    /// The catalog's model ids, once the lookup finishes
+    result: oneshot::Receiver<anyhow::Result<Vec<String>>>,
+}
+
 struct App {
     view: AppView,
     key_input: String,
@@ -116,6 +238,28 @@ struct App {
     provider_selection: usize,
     model_selection: usize,
     model_switch_selection: usize,
+    /// Return to the conversation (instead of model selection) once a key is saved
+    return_to_conversation: bool,
+    /// Only list favorite models in the pickers
+    favorites_only: bool,
+    /// Validation problem with the custom model id being entered
+    custom_model_error: Option<String>,
+    /// Custom model id the user chose to keep even though it could not be verified
+    custom_model_unverified: Option<String>,
+    /// Catalog lookup of the entered custom model id, while it runs
+    custom_model_check: Option<CustomModelCheck>,
+    /// Search term typed into the model catalog
+    catalog_query: String,
+    /// First visible row of the model catalog
+    catalog_scroll: usize,
+    /// Window title last sent to the terminal
+    window_title: Option<String>,
+    /// Why the add-key screen was opened, when Bindr sent the user there
+    key_notice: Option<String>,
+    /// Outcome of the last OpenRouter catalog refresh, shown in the model switcher
+    model_catalog_notice: Option<String>,
+    /// Custom provider being entered
+    provider_form: ProviderForm,
 }
 
 impl App {
@@ -139,6 +283,17 @@ impl App {
             provider_selection: 0,
             model_selection: 0,
             model_switch_selection: 0,
+            return_to_conversation: false,
+            catalog_query: String::new(),
+            catalog_scroll: 0,
+            favorites_only: false,
+            custom_model_error: None,
+            custom_model_unverified: None,
+            custom_model_check: None,
+            window_title: None,
+            key_notice: None,
+            model_catalog_notice: None,
+            provider_form: ProviderForm::default(),
         };
 
         (app, app_event_tx)
@@ -148,20 +303,83 @@ impl App {
         self.config.get_usage_info()
     }
 
-    /// Start a new conversation
-    fn start_new_conversation(&mut self) {
+    /// Persist the config, showing a toast instead of a hidden eprintln when it can't be written
+    fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            let message = self.config.describe_write_failure("Failed to save config", &e);
+            self.show_error(message);
+        }
+    }
+
+    /// Raise an error toast over the current view
+    fn show_error(&mut self, message: String) {
+        self.state.status_message = Some(message);
+        self.state.status_shown_at = Some(Instant::now());
+    }
+
+    /// Window title for the current view, e.g. `bindr — myapp — Plan ⏳` while a reply streams
+    fn terminal_title(&self) -> String {
+        match (&self.view, &self.conversation_manager) {
+            (AppView::Home, _) | (_, None) => "bindr".to_string(),
+            (_, Some(cm)) => {
+                let mut title = format!("bindr — {} — {}", cm.project_name(), cm.current_mode().display_name());
+                if cm.is_streaming() {
+                    title.push_str(" ⏳");
+                }
+                title
+            }
+        }
+    }
+
+    /// Send the window title to the terminal when it changed, unless `ui.terminal_title` is off
+    fn refresh_terminal_title(&mut self) -> io::Result<()> {
+        if !self.config.ui.terminal_title {
+            return Ok(());
+        }
+        let title = self.terminal_title();
+        if self.window_title.as_deref() != Some(title.as_str()) {
+            execute!(io::stdout(), SetTitle(&title))?;
+            self.window_title = Some(title);
+        }
+        Ok(())
+    }
+
+    /// Start a new conversation, resuming the last-used mode unless disabled in `[ui]`
+    async fn start_new_conversation(&mut self) {
         if !self.config.has_api_key() {
+            if self.config.ui.require_api_key {
+                let provider_name = self.config
+                    .get_current_provider()
+                    .map_or_else(|| self.config.selected_provider.clone(), |provider| provider.name.clone());
+                self.key_notice = Some(format!(
+                    "This setup requires your own API key (require_api_key under [ui]). Add a {} key to start, or press M to pick a different model.",
+                    provider_name
+                ));
+                self.key_input.clear();
+                self.view = AppView::AddKey;
+                return;
+            }
             // No API key configured, go to provider selection
             self.view = AppView::SelectProvider;
             return;
         }
 
+        let mode = if self.config.ui.restore_last_session {
+            LastSession::load(&self.config.bindr_home).map_or(BindrMode::Brainstorm, |last| last.mode)
+        } else {
+            BindrMode::Brainstorm
+        };
+        if let Err(e) = self.agent_manager.orchestrator_mut().switch_mode(mode).await {
+            self.show_error(format!("Failed to restore {} mode: {}", mode.display_name(), e));
+        }
+
         // Create conversation manager
         let llm_client = crate::llm::LlmClient::new(self.config.clone());
         let mut conversation_manager = ConversationManager::new(
+            self.config.clone(),
             self.agent_manager.clone(),
             llm_client,
-            BindrMode::Brainstorm,
+            self.agent_manager.orchestrator().current_mode(),
         );
 
         // Start the conversation
@@ -171,6 +389,184 @@ impl App {
         self.view = AppView::Conversation;
     }
 
+    /// Show the searchable model catalog with a fresh search
+    fn open_model_catalog(&mut self) {
+        self.catalog_query.clear();
+        self.catalog_scroll = 0;
+        self.view = AppView::ModelCatalog;
+    }
+
+    /// Leave the add-key flow, returning to the conversation if that is where it was entered from
+    fn leave_add_key(&mut self, fallback: AppView) {
+        self.key_input.clear();
+        self.key_notice = None;
+        self.leave_setup_view(fallback);
+    }
+
+    /// Open an empty add-provider form
+    fn open_provider_form(&mut self) {
+        self.provider_form = ProviderForm::default();
+        self.view = AppView::AddProvider;
+    }
+
+    /// Save the add-provider form as a new provider and select it, asking for a key if it has none
+    fn submit_provider_form(&mut self) {
+        let [name, base_url, api_key_env, model_id] = &self.provider_form.values;
+        match self.config.add_custom_provider(name, base_url, Some(api_key_env), model_id) {
+            Ok(provider_id) => {
+                self.config.switch_provider(&provider_id);
+                self.save_config();
+                self.sync_runtime_config();
+                if self.config.has_api_key_for(&provider_id) {
+                    self.leave_setup_view(AppView::Home);
+                } else {
+                    self.key_input.clear();
+                    self.view = AppView::AddKey;
+                }
+            }
+            Err(e) => self.provider_form.error = Some(e.to_string()),
+        }
+    }
+
+    /// Remove a custom provider, persist the change, and report what happened
+    fn remove_custom_provider(&mut self, provider_id: &str) {
+        if let Err(e) = self.config.remove_custom_provider(provider_id) {
+            self.show_error(e.to_string());
+            return;
+        }
+        self.save_config();
+        self.sync_runtime_config();
+        let providers = self.config.get_providers().len();
+        self.provider_selection = self.provider_selection.min(providers.saturating_sub(1));
+        if let Some(ref mut cm) = self.conversation_manager {
+            cm.notify(format!("Removed the {} provider.", provider_id));
+        }
+    }
+
+    /// Go back to the conversation that opened a setup view, or else to `fallback`
+    fn leave_setup_view(&mut self, fallback: AppView) {
+        if self.return_to_conversation {
+            self.return_to_conversation = false;
+            self.view = AppView::Conversation;
+            if let Some(ref mut cm) = self.conversation_manager {
+                cm.set_focus(true);
+            }
+        } else {
+            self.view = fallback;
+        }
+    }
+
+    /// Check the entered custom model id's shape, then start looking it up in OpenRouter's catalog.
+    ///
+    /// The lookup runs in the background so the screen keeps drawing while it waits;
+    /// [`App::finish_custom_model_check`] acts on the answer. If the catalog cannot be reached,
+    /// a second Enter on the same well-formed id saves it anyway.
+    fn check_custom_model(&mut self) {
+        // Enter while a lookup runs would only start it again
+        if self.custom_model_input.is_empty() || self.custom_model_check.is_some() {
+            return;
+        }
+        let model_id = self.custom_model_input.trim().to_string();
+        if let Some(problem) = crate::config::openrouter_model_id_problem(&model_id) {
+            self.custom_model_error = Some(problem);
+            self.custom_model_unverified = None;
+            return;
+        }
+        if self.custom_model_unverified.as_deref() == Some(model_id.as_str()) {
+            self.custom_model_unverified = None;
+            self.save_custom_model(model_id);
+            return;
+        }
+
+        self.custom_model_error = None;
+        let client = crate::llm::LlmClient::new(self.config.clone());
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tx.send(client.openrouter_model_ids().await);
+        });
+        self.custom_model_check = Some(CustomModelCheck { model_id, result: rx });
+    }
+
+    /// Save the custom model id once OpenRouter's catalog confirms it, or explain why not
+    fn finish_custom_model_check(&mut self) {
+        let Some(check) = &mut self.custom_model_check else {
+            return;
+        };
+        let outcome = match check.result.try_recv() {
+            Ok(outcome) => outcome,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => Err(anyhow::anyhow!("the lookup stopped unexpectedly")),
+        };
+        let model_id = check.model_id.clone();
+        self.custom_model_check = None;
+
+        match outcome {
+            Ok(ids) if ids.contains(&model_id) => self.save_custom_model(model_id),
+            Ok(ids) => {
+                let needle = model_id.to_lowercase();
+                let suggestions: Vec<&str> = ids
+                    .iter()
+                    .filter(|id| id.to_lowercase().contains(&needle))
+                    .take(3)
+                    .map(String::as_str)
+                    .collect();
+                let mut message = format!("OpenRouter has no model named '{}'.", model_id);
+                if !suggestions.is_empty() {
+                    message.push_str(&format!(" Did you mean: {}?", suggestions.join(", ")));
+                }
+                self.custom_model_error = Some(message);
+            }
+            Err(e) => {
+                self.custom_model_error = Some(format!(
+                    "Couldn't verify the model with OpenRouter ({}). Press Enter again to save it anyway.",
+                    e
+                ));
+                self.custom_model_unverified = Some(model_id);
+            }
+        }
+    }
+
+    /// Make `model_id` the default model, save it and leave the custom model screen
+    fn save_custom_model(&mut self, model_id: String) {
+        self.custom_model_error = None;
+        self.config.set_custom_model(model_id);
+
+        // Save the config with the custom model
+        self.save_config();
+
+        self.sync_runtime_config();
+
+        self.leave_setup_view(AppView::Home);
+        self.custom_model_input.clear();
+    }
+
+    /// Toggle favorite status on the highlighted entry of a model list and persist it
+    fn toggle_highlighted_favorite(&mut self, provider_id: Option<String>, selection: usize) {
+        let highlighted = self.config
+            .model_list(provider_id.as_deref(), self.favorites_only)
+            .get(selection)
+            .map(|(provider_id, model)| (provider_id.to_string(), model.id.clone()));
+        if let Some((provider_id, model_id)) = highlighted {
+            self.config.toggle_favorite_model(&provider_id, &model_id);
+            self.save_config();
+        }
+    }
+
+    /// Fetch OpenRouter's live model list, merge it into the catalog, and persist it
+    async fn refresh_openrouter_models(&mut self) {
+        let client = crate::llm::LlmClient::new(self.config.clone());
+        let notice = match client.fetch_openrouter_models(true).await {
+            Ok(models) => {
+                let added = self.config.merge_models("openrouter", &models);
+                self.save_config();
+                self.sync_runtime_config();
+                format!("🔄 OpenRouter lists {} models; {} new added to the catalog", models.len(), added)
+            }
+            Err(e) => format!("❌ Failed to fetch the OpenRouter catalog: {}", e),
+        };
+        self.model_catalog_notice = Some(notice);
+    }
+
     fn sync_runtime_config(&mut self) {
         let config_clone = self.config.clone();
         self.agent_manager.update_config(config_clone.clone());
@@ -219,19 +615,407 @@ async fn open_project(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn run_tui() -> Result<(), io::Error> {
+async fn resume_project(list: Option<usize>) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let mut session_manager = SessionManager::new(config);
+    session_manager.load_sessions()?;
+
+    let recent: Vec<_> = session_manager.recent_sessions().into_iter().cloned().collect();
+    let Some(latest) = recent.first() else {
+        println!("📭 No projects to resume yet. Run 'bindr' to start your first project!");
+        return Ok(());
+    };
+
+    if let Some(limit) = list {
+        println!("🕘 Recently active projects:\n");
+        for session in recent.iter().take(limit) {
+            println!(
+                "  • {} (Mode: {}, last active {})",
+                session.project_name,
+                session.current_mode.display_name(),
+                session.last_activity.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            );
+        }
+        return Ok(());
+    }
+
+    match session_manager.open_project(&latest.project_name) {
+        Ok(session_id) => {
+            println!("📂 Resuming project: {} (Mode: {})", latest.project_name, latest.current_mode.display_name());
+            println!("Session ID: {}", session_id);
+        }
+        Err(e) => {
+            println!("❌ Failed to resume project '{}': {}", latest.project_name, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn delete_project(name: &str, force: bool) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let mut session_manager = SessionManager::new(config);
+    session_manager.load_sessions()?;
+
+    if !session_manager.has_project(name) {
+        println!("❌ Project '{}' not found", name);
+        std::process::exit(1);
+    }
+    if !force {
+        use std::io::Write;
+        print!("Delete project '{}' and all its conversations? This can't be undone. [y/N] ", name);
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Kept '{}'.", name);
+            return Ok(());
+        }
+    }
+
+    match session_manager.delete_project(name) {
+        Ok(()) => println!("🗑️  Deleted project '{}'", name),
+        Err(e) => {
+            println!("❌ Failed to delete project '{}': {}", name, e);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_models(query: Option<String>, refresh: bool, merge: bool) -> anyhow::Result<()> {
+    let mut config = Config::load()?;
+    let query = query.unwrap_or_default();
+
+    if refresh {
+        let client = crate::llm::LlmClient::new(config.clone());
+        match client.fetch_openrouter_models(true).await {
+            Ok(models) => {
+                let needle = query.to_lowercase();
+                let known: Vec<&str> = config.model_providers
+                    .get("openrouter")
+                    .map(|provider| provider.models.iter().map(|model| model.id.as_str()).collect())
+                    .unwrap_or_default();
+                let new_models: Vec<config::ModelInfo> = models
+                    .into_iter()
+                    .filter(|model| !known.contains(&model.id.as_str()))
+                    .filter(|model| model.id.to_lowercase().contains(&needle))
+                    .collect();
+                println!("🔄 OpenRouter lists {} model(s) not in your config", new_models.len());
+                if merge {
+                    let added = config.merge_models("openrouter", &new_models);
+                    config.save()?;
+                    println!("✅ Added {} model(s) to the OpenRouter catalog", added);
+                } else {
+                    for model in &new_models {
+                        println!("  + {} — {}", model.id, model.name);
+                    }
+                    if !new_models.is_empty() {
+                        println!("💡 Re-run with --merge to add them to your config.");
+                    }
+                }
+                println!();
+            }
+            Err(e) => println!("❌ Failed to fetch the OpenRouter catalog: {}", e),
+        }
+    }
+
+    let entries = config.search_models(&query);
+    if entries.is_empty() {
+        println!("No models match '{}'.", query);
+        return Ok(());
+    }
+
+    let mut current_provider = None;
+    for (provider_id, provider, model) in entries {
+        if current_provider != Some(provider_id) {
+            println!("{} ({})", provider.name, provider_id);
+            current_provider = Some(provider_id);
+        }
+        let premium_indicator = if model.is_premium { "💎" } else { "🆓" };
+        println!("  {} {} — {}", premium_indicator, model.id, model.name);
+    }
+
+    Ok(())
+}
+
+async fn show_stats(name: &str) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let mut session_manager = SessionManager::new(config);
+    session_manager.load_sessions()?;
+
+    if let Err(e) = session_manager.open_project(name) {
+        println!("❌ Failed to open project '{}': {}", name, e);
+        return Ok(());
+    }
+
+    if let Some(session) = session_manager.current_session() {
+        for line in ProjectStats::from_state(&session.project_state).render() {
+            println!("{}", line);
+        }
+    }
+
+    Ok(())
+}
+
+/// One turn with the agent outside the TUI; provider errors come back as `Err` so the exit code reflects them
+async fn run_chat(
+    prompt: Option<String>,
+    mode: &str,
+    model: Option<String>,
+    json: bool,
+    workspace: Option<std::path::PathBuf>,
+) -> anyhow::Result<()> {
+    let mut config = Config::load()?;
+    if let Some(dir) = workspace {
+        config.set_workspace(&dir)?;
+    }
+    chat_command(config, prompt, mode, model, json, &mut io::stdin(), &mut io::stdout()).await
+}
+
+/// `bindr chat` with its arguments as parsed, reading the prompt from `input` when none was given
+async fn chat_command(
+    config: Config,
+    prompt: Option<String>,
+    mode: &str,
+    model: Option<String>,
+    json: bool,
+    input: &mut impl std::io::Read,
+    out: &mut impl std::io::Write,
+) -> anyhow::Result<()> {
+    let mode = BindrMode::from_name(mode)
+        .ok_or_else(|| anyhow::anyhow!("Unknown mode '{}': use brainstorm, plan, execute, or document", mode))?;
+    let prompt = match prompt {
+        Some(prompt) => prompt,
+        None => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            text
+        }
+    };
+    if prompt.trim().is_empty() {
+        anyhow::bail!("Nothing to send: pass a prompt or pipe one on stdin");
+    }
+
+    chat_turn(config, mode, model, prompt, json, out).await
+}
+
+/// Send `prompt` to `mode`'s model and write the reply to `out`, streaming it when it needn't be redacted first
+async fn chat_turn(
+    mut config: Config,
+    mode: BindrMode,
+    model: Option<String>,
+    prompt: String,
+    json: bool,
+    out: &mut impl std::io::Write,
+) -> anyhow::Result<()> {
+    if let Some(model) = model {
+        config.mode_sampling.for_mode_mut(mode).model = Some(model);
+    }
+    let (provider_id, _) = config.mode_model(mode);
+    if !config.has_api_key_for(&provider_id) {
+        anyhow::bail!("No API key configured for {}; add one with `bindr` or set the provider's key variable", provider_id);
+    }
+    // Redaction works on the whole reply, so a redacted reply is printed once it is complete
+    let stream_text = !json && !config.redaction.enabled;
+
+    let mut agent = crate::agent::AgentOrchestrator::new(config.clone(), SessionManager::new(config.clone()));
+    agent.switch_mode(mode).await?;
+    let mut rx = agent.continue_conversation(prompt).await?;
+
+    let mut reply = String::new();
+    let mut usage = pricing::TokenUsage::default();
+    while let Some(event) = rx.recv().await {
+        match event {
+            crate::llm::LlmEvent::TextDelta(chunk) => {
+                if stream_text {
+                    write!(out, "{}", chunk)?;
+                    out.flush()?;
+                }
+                reply.push_str(&chunk);
+            }
+            crate::llm::LlmEvent::Error(e) => anyhow::bail!(e),
+            crate::llm::LlmEvent::FallbackUsed { note, model_id } => {
+                eprintln!("{}", note);
+                agent.reply_fell_back_to(model_id);
+            }
+            crate::llm::LlmEvent::Retrying(note) => eprintln!("{}", note),
+            crate::llm::LlmEvent::Usage(update) => usage.merge(update),
+            _ => {}
+        }
+    }
+    let reply = agent.process_complete_response(reply);
+    config.record_usage()?;
+
+    if json {
+        let output = serde_json::json!({
+            "mode": mode.display_name().to_lowercase(),
+            "model": agent.reply_model().unwrap_or(agent.current_model()),
+            "reply": reply,
+            "usage": {
+                "input_tokens": usage.input_tokens,
+                "output_tokens": usage.output_tokens,
+            },
+        });
+        writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
+    } else if stream_text {
+        writeln!(out)?;
+    } else {
+        writeln!(out, "{}", reply)?;
+    }
+
+    Ok(())
+}
+
+fn search_conversations(query: &str, regex: bool, limit: usize) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let hits = search::SearchIndex::search(&config, query, regex, limit)?;
+
+    if hits.is_empty() {
+        println!("🔍 No matches for '{}'.", query);
+        return Ok(());
+    }
+
+    println!("🔍 {} match(es) for '{}':\n", hits.len(), query);
+    for hit in hits {
+        let source = match &hit.role {
+            Some(role) => role.to_string(),
+            None => "project name".to_string(),
+        };
+        println!(
+            "  {} · {} · {} ({})",
+            hit.project,
+            hit.mode.display_name(),
+            hit.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            source
+        );
+        println!("    {}", hit.snippet);
+    }
+
+    Ok(())
+}
+
+fn run_doctor() -> anyhow::Result<()> {
+    let config = Config::load()?;
+    println!("Bindr home: {}", config.bindr_home.display());
+
+    match config.check_writable() {
+        Ok(()) => println!("✅ Config, projects, and sessions directories are writable"),
+        Err(e) => println!("❌ {}", config.describe_write_failure("Write check failed", &e)),
+    }
+    if config.has_api_key() {
+        println!("✅ API key configured for {}", config.selected_provider);
+    } else {
+        println!("⚠️  No API key configured for {}", config.selected_provider);
+    }
+
+    Ok(())
+}
+
+fn run_config(action: ConfigAction) -> anyhow::Result<()> {
+    let mut config = Config::load()?;
+    print!("{}", config_command(&mut config, action)?);
+    Ok(())
+}
+
+/// Carry out a `bindr config` action, saving any change, and return what to print
+fn config_command(config: &mut Config, action: ConfigAction) -> anyhow::Result<String> {
+    let done = match action {
+        ConfigAction::Show => {
+            return Ok(format!(
+                "# {}\n{}",
+                config.bindr_home.join("config.toml").display(),
+                config.to_display_toml()?
+            ));
+        }
+        ConfigAction::SetProvider { id } => {
+            if !config.switch_provider(&id) {
+                anyhow::bail!("No provider called \"{}\"; run `bindr models` to see the configured ones", id);
+            }
+            let mut done = format!("✅ Selected {} with model {}", id, config.default_model);
+            if !config.has_api_key_for(&id) {
+                done.push_str(&format!("\n💡 {} has no API key yet; add one with `bindr config set-key {} <key>`.", id, id));
+            }
+            done
+        }
+        ConfigAction::SetModel { id } => {
+            config.set_default_model(&id)?;
+            format!("✅ Default model set to {}", id)
+        }
+        ConfigAction::SetKey { provider, key } => {
+            if !config.model_providers.contains_key(&provider) {
+                anyhow::bail!("No provider called \"{}\"; run `bindr models` to see the configured ones", provider);
+            }
+            let key = key.trim().to_string();
+            if key.is_empty() {
+                anyhow::bail!("The API key is empty");
+            }
+            let done = format!("✅ Stored API key {} for {}", config::mask_key(&key), provider);
+            config.set_api_key(provider, key);
+            done
+        }
+    };
+
+    config.save()?;
+    Ok(format!("{}\n", done))
+}
+
+fn run_init(agents: bool, workspace: Option<std::path::PathBuf>) -> anyhow::Result<()> {
+    let bindr_home = Config::find_bindr_home()?;
+    let mut steps = Config::init_home(&bindr_home)?;
+    if agents {
+        let dir = match workspace {
+            Some(dir) => dir,
+            None => std::env::current_dir()?,
+        };
+        let path = dir.join("AGENTS.md");
+        let created = crate::persist::write_if_missing(&path, prompts::AGENTS_TEMPLATE)?;
+        steps.push((path, created));
+    }
+
+    for (path, created) in steps {
+        if created {
+            println!("✅ Created {}", path.display());
+        } else {
+            println!("⏭️  Kept existing {}", path.display());
+        }
+    }
+    println!();
+    println!("Next: edit {} or run `bindr` and pick a provider to add an API key.", bindr_home.join("config.toml").display());
+
+    Ok(())
+}
+
+async fn run_tui(workspace: Option<std::path::PathBuf>) -> Result<(), io::Error> {
     // Load configuration
-    let config = Config::load().map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    let mut config = Config::load().map_err(|e| io::Error::other(format!("{:#}", e)))?;
+    if let Some(dir) = workspace {
+        config.set_workspace(&dir).map_err(|e| io::Error::other(format!("{:#}", e)))?;
+    }
     let mut session_manager = SessionManager::new(config.clone());
-    session_manager.load_sessions().map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    // An unwritable Bindr home shouldn't stop the TUI; report it once it is up
+    let startup_error = session_manager
+        .load_sessions()
+        .and_then(|_| config.check_writable())
+        .err();
     
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Save the user's window title on the terminal's title stack (xterm's CSI 22/23 t)
+    let terminal_title = config.ui.terminal_title;
+    if terminal_title {
+        execute!(stdout, Print("\x1b[22;0t"))?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let (mut app, _app_event_tx) = App::new(config, session_manager);
+    if let Some(e) = startup_error {
+        let message = app.config.describe_write_failure("Bindr can't save your work", &e);
+        app.show_error(message);
+    }
     let res = run_app(&mut terminal, &mut app).await;
 
     disable_raw_mode()?;
@@ -240,6 +1024,9 @@ async fn run_tui() -> Result<(), io::Error> {
         LeaveAlternateScreen,
         DisableMouseCapture
     )?;
+    if terminal_title {
+        execute!(terminal.backend_mut(), Print("\x1b[23;0t"))?;
+    }
     terminal.show_cursor()?;
 
     if let Err(err) = res {
@@ -249,7 +1036,40 @@ async fn run_tui() -> Result<(), io::Error> {
     Ok(())
 }
 
-fn draw_home_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+/// Overlay the current error toast in the top-right corner until it expires
+fn draw_error_toast(f: &mut ratatui::Frame, app: &App) {
+    let (Some(message), Some(shown_at)) = (&app.state.status_message, app.state.status_shown_at) else {
+        return;
+    };
+    if shown_at.elapsed() > TOAST_DURATION {
+        return;
+    }
+
+    let size = f.size();
+    let width = (size.width / 2).max(40).min(size.width);
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    let height = ((message.chars().count() / inner_width) as u16 + 3).min(size.height);
+    let area = ratatui::layout::Rect {
+        x: size.width - width,
+        y: 0,
+        width,
+        height,
+    };
+
+    let toast = Paragraph::new(message.as_str())
+        .style(Style::default().fg(TEXT_PRIMARY).bg(BG_SECONDARY))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(ACCENT_RED))
+                .title("⚠ Error"),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(Clear, area);
+    f.render_widget(toast, area);
+}
+
+fn draw_home_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
     // Header with usage counter
     let header_text = vec![
         Line::from(vec![
@@ -260,6 +1080,8 @@ fn draw_home_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &Ap
                     let (used, limit) = app.get_usage_info();
                     if app.config.has_api_key() {
                         format!("Unlimited Access")
+                    } else if app.config.ui.require_api_key {
+                        "API key required".to_string()
                     } else {
                         format!("Free Tier ({}/{} messages today)", used, limit)
                     }
@@ -314,6 +1136,12 @@ fn draw_home_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &Ap
             //Span::styled(" (unlimited access)", Style::default().fg(ACCENT_GREEN)),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled(" [C] ", Style::default().fg(BG_PRIMARY).bg(ACCENT_BLUE).add_modifier(Modifier::BOLD)),
+            Span::raw("  "),
+            Span::styled("Browse model catalog", Style::default().fg(TEXT_PRIMARY)),
+        ]),
+        Line::from(""),
         Line::from(vec![
             Span::styled(" [Q] ", Style::default().fg(BG_PRIMARY).bg(ACCENT_RED).add_modifier(Modifier::BOLD)),
             Span::raw("  "),
@@ -322,7 +1150,11 @@ fn draw_home_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &Ap
         Line::from(""),
         Line::from(""),
         Line::from(Span::styled(
-            "💡 Tip: Add your API key for unlimited access to premium models",
+            if app.config.ui.require_api_key {
+                "💡 Tip: Bindr uses your own API key; press K to add or change it"
+            } else {
+                "💡 Tip: Add your API key for unlimited access to premium models"
+            },
             Style::default().fg(TEXT_SECONDARY).add_modifier(Modifier::ITALIC),
         )),
     ];
@@ -368,7 +1200,7 @@ fn draw_home_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &Ap
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_select_provider_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_select_provider_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
     let providers = app.config.get_providers();
     let mut items = Vec::new();
     
@@ -379,10 +1211,7 @@ fn draw_select_provider_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
             Style::default().fg(TEXT_PRIMARY)
         };
         
-        let has_key = app.config.api_keys.contains_key(*id) || 
-            provider.api_key_env.as_ref()
-                .map(|env| std::env::var(env).is_ok())
-                .unwrap_or(false);
+        let has_key = app.config.has_api_key_for(id);
         
         let status = if has_key {
             "✓"
@@ -413,6 +1242,10 @@ fn draw_select_provider_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
             Span::styled(" navigate • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Enter", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
             Span::styled(" select • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("A", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" add custom • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("D", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" remove custom • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Esc", Style::default().fg(ACCENT_RED).add_modifier(Modifier::BOLD)),
             Span::styled(" back", Style::default().fg(TEXT_SECONDARY)),
         ]),
@@ -429,7 +1262,7 @@ fn draw_select_provider_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_add_key_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_add_key_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
     // Header
     let header = Paragraph::new("Bindr")
         .style(Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY).add_modifier(Modifier::BOLD))
@@ -445,13 +1278,19 @@ fn draw_add_key_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app:
     let current_provider = app.config.get_current_provider();
     let provider_name = current_provider.map(|p| p.name.as_str()).unwrap_or("Unknown");
     
-    let key_text = vec![
+    let mut key_text = vec![
         Line::from(""),
         Line::from(Span::styled(
             format!("Add {} API Key", provider_name),
             Style::default().fg(ACCENT_BLUE).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
+    ];
+    if let Some(notice) = &app.key_notice {
+        key_text.push(Line::from(Span::styled(notice.clone(), Style::default().fg(ACCENT_YELLOW))));
+        key_text.push(Line::from(""));
+    }
+    key_text.extend([
         Line::from(Span::styled(
             "Get your API key from: https://openrouter.ai/keys",
             Style::default().fg(TEXT_SECONDARY),
@@ -480,14 +1319,19 @@ fn draw_add_key_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app:
             "Press Enter to save and select model • ESC to cancel",
             Style::default().fg(TEXT_SECONDARY).add_modifier(Modifier::ITALIC),
         )),
-        Line::from(""),
-        Line::from(""),
-        Line::from(Span::styled("Benefits:", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD))),
-        Line::from(Span::styled("  ✓ Unlimited messages", Style::default().fg(TEXT_PRIMARY))),
-        Line::from(Span::styled("  ✓ Access to premium models (GPT-4, Claude Opus)", Style::default().fg(TEXT_PRIMARY))),
-        Line::from(Span::styled("  ✓ Faster response times", Style::default().fg(TEXT_PRIMARY))),
-        Line::from(Span::styled("  ✓ Priority support", Style::default().fg(TEXT_PRIMARY))),
-    ];
+    ]);
+    // The benefits are measured against the free tier, which isn't on offer when a key is required
+    if !app.config.ui.require_api_key {
+        key_text.extend([
+            Line::from(""),
+            Line::from(""),
+            Line::from(Span::styled("Benefits:", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled("  ✓ Unlimited messages", Style::default().fg(TEXT_PRIMARY))),
+            Line::from(Span::styled("  ✓ Access to premium models (GPT-4, Claude Opus)", Style::default().fg(TEXT_PRIMARY))),
+            Line::from(Span::styled("  ✓ Faster response times", Style::default().fg(TEXT_PRIMARY))),
+            Line::from(Span::styled("  ✓ Priority support", Style::default().fg(TEXT_PRIMARY))),
+        ]);
+    }
 
     let content = Paragraph::new(key_text)
         .style(Style::default().bg(BG_PRIMARY))
@@ -513,31 +1357,69 @@ fn draw_add_key_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app:
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_select_model_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
-    let current_provider = app.config.get_current_provider();
+/// Render a model list with a pinned "Favorites" section above the remaining models
+fn model_list_lines(
+    app: &App,
+    models: &[(&String, &config::ModelInfo)],
+    selection: usize,
+    show_provider: bool,
+) -> Vec<Line<'static>> {
     let mut items = Vec::new();
-    
-    if let Some(provider) = current_provider {
-        for (i, model) in provider.models.iter().enumerate() {
-            let style = if i == app.model_selection {
-                Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY)
-            } else {
-                Style::default().fg(TEXT_PRIMARY)
-            };
-            
-            let premium_indicator = if model.is_premium {
-                "💎 "
-            } else {
-                "🆓 "
-            };
-            
-            items.push(Line::from(vec![
-                Span::styled(premium_indicator, Style::default().fg(if model.is_premium { ACCENT_YELLOW } else { ACCENT_GREEN })),
-                Span::styled(model.name.clone(), style),
-                Span::styled(format!(" - {}", model.description), Style::default().fg(TEXT_SECONDARY)),
-            ]));
+    let mut in_favorites = false;
+
+    for (i, (provider_id, model)) in models.iter().enumerate() {
+        let is_favorite = app.config.is_favorite_model(provider_id, &model.id);
+        if is_favorite && i == 0 {
+            in_favorites = true;
+            items.push(Line::from(Span::styled("★ Favorites", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD))));
+        } else if !is_favorite && (in_favorites || i == 0) {
+            if in_favorites {
+                items.push(Line::from(""));
+            }
+            in_favorites = false;
+            items.push(Line::from(Span::styled("Available Models:", Style::default().fg(TEXT_PRIMARY).add_modifier(Modifier::BOLD))));
+        }
+
+        let style = if i == selection {
+            Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY)
+        } else {
+            Style::default().fg(TEXT_PRIMARY)
+        };
+        let premium_indicator = if model.is_premium { "💎 " } else { "🆓 " };
+        let is_current = *provider_id == &app.config.selected_provider && model.id == app.config.default_model;
+
+        let mut spans = vec![
+            Span::styled(premium_indicator, Style::default().fg(if model.is_premium { ACCENT_YELLOW } else { ACCENT_GREEN })),
+            Span::styled(model.name.clone(), style),
+        ];
+        if show_provider {
+            let provider_name = app.config.model_providers
+                .get(*provider_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| provider_id.to_string());
+            spans.push(Span::styled(format!(" ({})", provider_name), Style::default().fg(TEXT_SECONDARY)));
+        } else {
+            spans.push(Span::styled(format!(" - {}", model.description), Style::default().fg(TEXT_SECONDARY)));
         }
+        if is_current {
+            spans.push(Span::styled(" - CURRENT", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)));
+        }
+        items.push(Line::from(spans));
+    }
+
+    if models.is_empty() && app.favorites_only {
+        items.push(Line::from(Span::styled(
+            "No favorite models yet. Press O to show all models and F to pin one.",
+            Style::default().fg(TEXT_SECONDARY),
+        )));
     }
+
+    items
+}
+
+fn draw_select_model_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+    let models = app.config.model_list(Some(&app.config.selected_provider), app.favorites_only);
+    let items = model_list_lines(app, &models, app.model_selection, false);
     
     let content = Paragraph::new(items)
         .style(Style::default().bg(BG_PRIMARY))
@@ -556,6 +1438,10 @@ fn draw_select_model_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame,
             Span::styled(" navigate • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Enter", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
             Span::styled(" select • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("F", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" favorite • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("O", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" favorites only • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Esc", Style::default().fg(ACCENT_RED).add_modifier(Modifier::BOLD)),
             Span::styled(" back", Style::default().fg(TEXT_SECONDARY)),
         ]),
@@ -572,7 +1458,7 @@ fn draw_select_model_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame,
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_custom_model_input_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_custom_model_input_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
     // Header
     let header = Paragraph::new("Bindr")
         .style(Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY).add_modifier(Modifier::BOLD))
@@ -627,7 +1513,14 @@ fn draw_custom_model_input_view<B: ratatui::backend::Backend>(f: &mut ratatui::F
             Span::styled(" _", Style::default().fg(ACCENT_BLUE)),
         ]),
         Line::from(""),
-        Line::from(""),
+        match (&app.custom_model_check, &app.custom_model_error) {
+            (Some(check), _) => Line::from(Span::styled(
+                format!("Checking {} with OpenRouter…", check.model_id),
+                Style::default().fg(TEXT_SECONDARY),
+            )),
+            (None, Some(error)) => Line::from(Span::styled(error.clone(), Style::default().fg(ACCENT_RED))),
+            (None, None) => Line::from(""),
+        },
         Line::from(Span::styled(
             "Press Enter to save • ESC to cancel",
             Style::default().fg(TEXT_SECONDARY).add_modifier(Modifier::ITALIC),
@@ -658,11 +1551,98 @@ fn draw_custom_model_input_view<B: ratatui::backend::Backend>(f: &mut ratatui::F
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_brainstorm_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
-    let content = Paragraph::new("🧠 Brainstorm Mode - Coming Soon!")
-        .style(Style::default().fg(ACCENT_BLUE).bg(BG_PRIMARY))
-        .alignment(Alignment::Center)
-        .block(
+fn draw_add_provider_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+    // Header
+    let header = Paragraph::new("Bindr")
+        .style(Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+        );
+    f.render_widget(header, chunks[0]);
+
+    // Main content
+    let form = &app.provider_form;
+    let mut content_text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Add an OpenAI-compatible provider",
+            Style::default().fg(ACCENT_BLUE).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            "Any server with a /v1/chat/completions endpoint works, e.g. Ollama, LM Studio, or vLLM.",
+            Style::default().fg(TEXT_SECONDARY),
+        )),
+        Line::from(""),
+    ];
+    for (i, ((label, placeholder), value)) in PROVIDER_FORM_FIELDS.iter().zip(&form.values).enumerate() {
+        let active = i == form.field;
+        content_text.push(Line::from(Span::styled(
+            format!("{}:", label),
+            Style::default().fg(if active { ACCENT_BLUE } else { TEXT_PRIMARY }),
+        )));
+        let (text, color) = if value.is_empty() {
+            (placeholder.to_string(), TEXT_SECONDARY)
+        } else {
+            (value.clone(), ACCENT_GREEN)
+        };
+        let mut field = vec![
+            Span::styled(" ", Style::default()),
+            Span::styled(text, Style::default().fg(color).bg(BG_SECONDARY)),
+        ];
+        if active {
+            field.push(Span::styled(" _", Style::default().fg(ACCENT_BLUE)));
+        }
+        content_text.push(Line::from(field));
+        content_text.push(Line::from(""));
+    }
+    content_text.push(match &form.error {
+        Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(ACCENT_RED))),
+        None => Line::from(""),
+    });
+
+    let content = Paragraph::new(content_text)
+        .style(Style::default().bg(BG_PRIMARY))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+                .title(Span::styled(" Custom Provider ", Style::default().fg(ACCENT_YELLOW)))
+        );
+    f.render_widget(content, chunks[1]);
+
+    // Footer
+    let footer_text = vec![
+        Line::from(vec![
+            Span::styled("Tab/↑↓", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
+            Span::styled(" switch field • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("Enter", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
+            Span::styled(" next / save • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("Esc", Style::default().fg(ACCENT_RED).add_modifier(Modifier::BOLD)),
+            Span::styled(" cancel", Style::default().fg(TEXT_SECONDARY)),
+        ]),
+    ];
+
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().bg(BG_SECONDARY))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+        );
+    f.render_widget(footer, chunks[2]);
+}
+
+fn draw_brainstorm_view(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
+    let content = Paragraph::new("🧠 Brainstorm Mode - Coming Soon!")
+        .style(Style::default().fg(ACCENT_BLUE).bg(BG_PRIMARY))
+        .alignment(Alignment::Center)
+        .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(BORDER_COLOR))
@@ -671,7 +1651,7 @@ fn draw_brainstorm_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _a
     f.render_widget(content, chunks[1]);
 }
 
-fn draw_plan_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_plan_view(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
     let content = Paragraph::new("📋 Plan Mode - Coming Soon!")
         .style(Style::default().fg(ACCENT_GREEN).bg(BG_PRIMARY))
         .alignment(Alignment::Center)
@@ -684,7 +1664,7 @@ fn draw_plan_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app: &A
     f.render_widget(content, chunks[1]);
 }
 
-fn draw_execute_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_execute_view(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
     let content = Paragraph::new("⚡ Execute Mode - Coming Soon!")
         .style(Style::default().fg(ACCENT_YELLOW).bg(BG_PRIMARY))
         .alignment(Alignment::Center)
@@ -697,7 +1677,7 @@ fn draw_execute_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app:
     f.render_widget(content, chunks[1]);
 }
 
-fn draw_document_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_document_view(f: &mut ratatui::Frame, _app: &App, chunks: Vec<ratatui::layout::Rect>) {
     let content = Paragraph::new("📝 Document Mode - Coming Soon!")
         .style(Style::default().fg(ACCENT_RED).bg(BG_PRIMARY))
         .alignment(Alignment::Center)
@@ -710,7 +1690,7 @@ fn draw_document_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, _app
     f.render_widget(content, chunks[1]);
 }
 
-fn draw_model_selection_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_model_selection_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
     // Header
     let header = Paragraph::new("Bindr")
         .style(Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY).add_modifier(Modifier::BOLD))
@@ -723,9 +1703,7 @@ fn draw_model_selection_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
     f.render_widget(header, chunks[0]);
 
     // Main content - show all models from all providers
-    let providers = app.config.get_providers();
     let mut items = Vec::new();
-    let mut current_index = 0;
     
     // Show current model first
     let current_provider = app.config.get_current_provider();
@@ -744,35 +1722,14 @@ fn draw_model_selection_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
     }
     
     items.push(Line::from(""));
-    items.push(Line::from(Span::styled("Available Models:", Style::default().fg(TEXT_PRIMARY).add_modifier(Modifier::BOLD))));
-    items.push(Line::from(""));
-    
-    // Add all models from all providers
-    for (provider_id, provider) in providers.iter() {
-        for model in &provider.models {
-            let style = if current_index == app.model_switch_selection {
-                Style::default().fg(ACCENT_BLUE).bg(BG_SECONDARY)
-            } else {
-                Style::default().fg(TEXT_PRIMARY)
-            };
-            
-            let premium_indicator = if model.is_premium { "💎 " } else { "🆓 " };
-            let is_current = model.id == current_model;
-            
-            items.push(Line::from(vec![
-                Span::styled(premium_indicator, Style::default().fg(if model.is_premium { ACCENT_YELLOW } else { ACCENT_GREEN })),
-                Span::styled(model.name.clone(), style),
-                Span::styled(format!(" ({})", provider.name), Style::default().fg(TEXT_SECONDARY)),
-                if is_current {
-                    Span::styled(" - CURRENT", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD))
-                } else {
-                    Span::raw("")
-                },
-            ]));
-            current_index += 1;
-        }
+    if let Some(notice) = &app.model_catalog_notice {
+        items.push(Line::from(Span::styled(notice.clone(), Style::default().fg(ACCENT_YELLOW))));
+        items.push(Line::from(""));
     }
     
+    // Add all models from all providers, favorites first
+    items.extend(model_list_lines(app, &app.config.model_list(None, app.favorites_only), app.model_switch_selection, true));
+    
     let content = Paragraph::new(items)
         .style(Style::default().bg(BG_PRIMARY))
         .block(
@@ -790,6 +1747,12 @@ fn draw_model_selection_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
             Span::styled(" navigate • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Enter", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
             Span::styled(" select • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("F", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" favorite • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("O", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" favorites only • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("R", Style::default().fg(ACCENT_YELLOW).add_modifier(Modifier::BOLD)),
+            Span::styled(" refresh OpenRouter • ", Style::default().fg(TEXT_SECONDARY)),
             Span::styled("Esc", Style::default().fg(ACCENT_RED).add_modifier(Modifier::BOLD)),
             Span::styled(" back to conversation", Style::default().fg(TEXT_SECONDARY)),
         ]),
@@ -806,15 +1769,156 @@ fn draw_model_selection_view<B: ratatui::backend::Backend>(f: &mut ratatui::Fram
     f.render_widget(footer, chunks[2]);
 }
 
-fn draw_conversation_view<B: ratatui::backend::Backend>(f: &mut ratatui::Frame, app: &mut App, chunks: Vec<ratatui::layout::Rect>) {
+fn draw_conversation_view(f: &mut ratatui::Frame, app: &mut App, chunks: Vec<ratatui::layout::Rect>) {
     if let Some(ref mut conversation_manager) = app.conversation_manager {
         // Render conversation manager components individually
         conversation_manager.render_conversation_ui(chunks[1], f.buffer_mut());
     }
+
+    // Status bar
+    let mode = app.conversation_manager
+        .as_ref()
+        .map_or(app.state.current_mode, |cm| cm.current_mode());
+    let model = app.conversation_manager
+        .as_ref()
+        .map_or_else(|| app.config.default_model.clone(), |cm| cm.current_model().to_string());
+    let mut status_spans = vec![
+        Span::styled(mode.display_name(), Style::default().fg(ACCENT_BLUE).add_modifier(Modifier::BOLD)),
+        Span::styled(" • ", Style::default().fg(TEXT_SECONDARY)),
+        Span::styled(model, Style::default().fg(ACCENT_GREEN)),
+        Span::styled(" • workspace: ", Style::default().fg(TEXT_SECONDARY)),
+        Span::styled(app.config.cwd.display().to_string(), Style::default().fg(TEXT_PRIMARY)),
+    ];
+    if let Some(session) = app.conversation_manager.as_ref().map(|cm| cm.session_cost())
+        && session.usage.total() > 0
+    {
+        let approximate = if session.estimated { "~" } else { "" };
+        let mut usage = format!(" • {}{} tokens", approximate, pricing::format_tokens(session.usage.total()));
+        if session.cost > 0.0 || !session.unpriced_turns {
+            // A `+` marks turns to models Bindr has no prices for
+            let unpriced = if session.unpriced_turns { "+" } else { "" };
+            usage.push_str(&format!(" • {}{}", pricing::format_cost(session.cost), unpriced));
+        }
+        status_spans.push(Span::styled(usage, Style::default().fg(TEXT_SECONDARY)));
+    }
+    if let Some(saved_at) = app.conversation_manager.as_ref().and_then(|cm| cm.last_auto_save()) {
+        status_spans.push(Span::styled(
+            format!(" • saved {}", saved_at.format("%H:%M")),
+            Style::default().fg(TEXT_SECONDARY),
+        ));
+    }
+    let status_text = vec![Line::from(status_spans)];
+
+    let status = Paragraph::new(status_text)
+        .style(Style::default().bg(BG_SECONDARY))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+        );
+    f.render_widget(status, chunks[2]);
+}
+
+fn draw_model_catalog_view(f: &mut ratatui::Frame, app: &App, chunks: Vec<ratatui::layout::Rect>) {
+    // Header with search field
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled("Search: ", Style::default().fg(TEXT_SECONDARY)),
+        Span::styled(format!("{}▌", app.catalog_query), Style::default().fg(TEXT_PRIMARY)),
+    ]))
+        .style(Style::default().bg(BG_SECONDARY))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+        );
+    f.render_widget(header, chunks[0]);
+
+    let entries = app.config.search_models(&app.catalog_query);
+    let mut items = Vec::new();
+    if entries.is_empty() {
+        items.push(Line::from(Span::styled(
+            format!("No models match '{}'", app.catalog_query),
+            Style::default().fg(TEXT_SECONDARY),
+        )));
+    }
+    for (provider_id, provider, model) in entries.iter().skip(app.catalog_scroll) {
+        let premium_indicator = if model.is_premium { "💎 " } else { "🆓 " };
+        items.push(Line::from(vec![
+            Span::styled(premium_indicator, Style::default().fg(if model.is_premium { ACCENT_YELLOW } else { ACCENT_GREEN })),
+            Span::styled(format!("{:<12}", provider.name), Style::default().fg(ACCENT_BLUE)),
+            Span::styled(model.id.clone(), Style::default().fg(TEXT_PRIMARY).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {}", model.name), Style::default().fg(TEXT_SECONDARY)),
+            Span::styled(
+                if app.config.is_favorite_model(provider_id, &model.id) { " ★" } else { "" },
+                Style::default().fg(ACCENT_YELLOW),
+            ),
+        ]));
+    }
+
+    let content = Paragraph::new(items)
+        .style(Style::default().bg(BG_PRIMARY))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+                .title(Span::styled(
+                    format!(" Model Catalog ({} models) ", entries.len()),
+                    Style::default().fg(ACCENT_BLUE),
+                ))
+        );
+    f.render_widget(content, chunks[1]);
+
+    // Footer
+    let footer_text = vec![
+        Line::from(vec![
+            Span::styled("Type", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
+            Span::styled(" to search • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("↑↓", Style::default().fg(ACCENT_GREEN).add_modifier(Modifier::BOLD)),
+            Span::styled(" scroll • ", Style::default().fg(TEXT_SECONDARY)),
+            Span::styled("Esc", Style::default().fg(ACCENT_RED).add_modifier(Modifier::BOLD)),
+            Span::styled(" back", Style::default().fg(TEXT_SECONDARY)),
+        ]),
+    ];
+
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().bg(BG_SECONDARY))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR))
+        );
+    f.render_widget(footer, chunks[2]);
+}
+
+/// Suspend the TUI, run an external program on a file, and restore the TUI afterwards
+fn run_external<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    program: &str,
+    path: &std::path::Path,
+) -> io::Result<std::process::ExitStatus> {
+    let mut parts = program.split_whitespace();
+    let binary = parts.next().unwrap_or(program);
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+    let status = std::process::Command::new(binary)
+        .args(parts)
+        .arg(path)
+        .status();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    status
 }
 
 async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
     loop {
+        app.refresh_terminal_title()?;
         terminal.draw(|f| {
             let size = f.size();
 
@@ -830,24 +1934,36 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                 .split(size);
 
             match app.view {
-                AppView::Home => draw_home_view::<B>(f, app, chunks.to_vec()),
-                AppView::SelectProvider => draw_select_provider_view::<B>(f, app, chunks.to_vec()),
-                AppView::AddKey => draw_add_key_view::<B>(f, app, chunks.to_vec()),
-                AppView::SelectModel => draw_select_model_view::<B>(f, app, chunks.to_vec()),
-                AppView::CustomModelInput => draw_custom_model_input_view::<B>(f, app, chunks.to_vec()),
-                AppView::Conversation => draw_conversation_view::<B>(f, app, chunks.to_vec()),
-                AppView::ModelSelection => draw_model_selection_view::<B>(f, app, chunks.to_vec()),
-                AppView::Brainstorm => draw_brainstorm_view::<B>(f, app, chunks.to_vec()),
-                AppView::Plan => draw_plan_view::<B>(f, app, chunks.to_vec()),
-                AppView::Execute => draw_execute_view::<B>(f, app, chunks.to_vec()),
-                AppView::Document => draw_document_view::<B>(f, app, chunks.to_vec()),
+                AppView::Home => draw_home_view(f, app, chunks.to_vec()),
+                AppView::SelectProvider => draw_select_provider_view(f, app, chunks.to_vec()),
+                AppView::AddKey => draw_add_key_view(f, app, chunks.to_vec()),
+                AppView::SelectModel => draw_select_model_view(f, app, chunks.to_vec()),
+                AppView::CustomModelInput => draw_custom_model_input_view(f, app, chunks.to_vec()),
+                AppView::AddProvider => draw_add_provider_view(f, app, chunks.to_vec()),
+                AppView::Conversation => draw_conversation_view(f, app, chunks.to_vec()),
+                AppView::ModelSelection => draw_model_selection_view(f, app, chunks.to_vec()),
+                AppView::ModelCatalog => draw_model_catalog_view(f, app, chunks.to_vec()),
+                AppView::Brainstorm => draw_brainstorm_view(f, app, chunks.to_vec()),
+                AppView::Plan => draw_plan_view(f, app, chunks.to_vec()),
+                AppView::Execute => draw_execute_view(f, app, chunks.to_vec()),
+                AppView::Document => draw_document_view(f, app, chunks.to_vec()),
             }
+
+            draw_error_toast(f, app);
         })?;
 
         // Process streaming chunks for conversation
         if let Some(ref mut conversation_manager) = app.conversation_manager {
             conversation_manager.process_streaming_chunks();
+            if let Err(e) = conversation_manager.run_requested_tool().await {
+                conversation_manager.notify(format!("Tool call failed: {:#}", e));
+            }
+            if let Err(e) = conversation_manager.advance_auto_continue().await {
+                conversation_manager.notify(format!("Auto-continue failed: {:#}", e));
+            }
+            conversation_manager.auto_save();
         }
+        app.finish_custom_model_check();
 
         // Handle keyboard input with a short timeout to keep the loop responsive
         if event::poll(std::time::Duration::from_millis(50))? {
@@ -856,7 +1972,7 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                     AppView::Home => match key.code {
                         KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
                         KeyCode::Char('n') | KeyCode::Char('N') => {
-                            app.start_new_conversation();
+                            app.start_new_conversation().await;
                         }
                         KeyCode::Char('p') | KeyCode::Char('P') => {
                             // TODO: Show projects
@@ -865,26 +1981,54 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                         KeyCode::Char('k') | KeyCode::Char('K') => {
                             app.view = AppView::SelectProvider;
                         }
+                        KeyCode::Char('c') | KeyCode::Char('C') => {
+                            app.open_model_catalog();
+                        }
+                        _ => {}
+                    },
+                    AppView::ModelCatalog => match key.code {
+                        KeyCode::Up => {
+                            app.catalog_scroll = app.catalog_scroll.saturating_sub(1);
+                        }
+                        KeyCode::Down => {
+                            let total = app.config.search_models(&app.catalog_query).len();
+                            if app.catalog_scroll < total.saturating_sub(1) {
+                                app.catalog_scroll += 1;
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            app.catalog_query.push(c);
+                            app.catalog_scroll = 0;
+                        }
+                        KeyCode::Backspace => {
+                            app.catalog_query.pop();
+                            app.catalog_scroll = 0;
+                        }
+                        KeyCode::Esc => {
+                            if let Some(ref mut cm) = app.conversation_manager {
+                                cm.set_focus(true);
+                                app.view = AppView::Conversation;
+                            } else {
+                                app.view = AppView::Home;
+                            }
+                        }
                         _ => {}
                     },
                     AppView::AddKey => match key.code {
                         KeyCode::Esc => {
-                            app.view = AppView::Home;
-                            app.key_input.clear();
+                            app.leave_add_key(AppView::Home);
                         }
                         KeyCode::Enter => {
                             if !app.key_input.is_empty() {
                                 let provider_id = app.config.selected_provider.clone();
                                 app.config.set_api_key(provider_id, app.key_input.clone());
-                                if let Err(e) = app.config.save() {
-                                    eprintln!("Failed to save config: {}", e);
-                                }
+                                app.save_config();
 
                                 app.sync_runtime_config();
 
-                                app.key_input.clear();
-                                app.view = AppView::SelectModel;
-                                if let Some(ref mut cm) = app.conversation_manager {
+                                let returning = app.return_to_conversation;
+                                app.leave_add_key(AppView::SelectModel);
+                                if !returning && let Some(ref mut cm) = app.conversation_manager {
                                     cm.set_focus(false);
                                 }
                             }
@@ -917,19 +2061,14 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                         }
                         KeyCode::Enter => {
                             let providers = app.config.get_providers();
-                            if let Some((provider_id, provider)) = providers.get(app.provider_selection) {
+                            if let Some((provider_id, _)) = providers.get(app.provider_selection) {
                                 let provider_id_str = provider_id.to_string();
 
                                 // Check if API key already exists for this provider
-                                let has_api_key = app.config.api_keys.contains_key(*provider_id)
-                                    || provider
-                                        .api_key_env
-                                        .as_ref()
-                                        .map(|env| std::env::var(env).is_ok())
-                                        .unwrap_or(false);
+                                let has_api_key = app.config.has_api_key_for(provider_id);
 
                                 // Now we can safely mutate config
-                                app.config.set_selected_provider(provider_id_str);
+                                app.config.switch_provider(&provider_id_str);
                                 app.sync_runtime_config();
 
                                 if has_api_key {
@@ -941,8 +2080,51 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                                 }
                             }
                         }
+                        KeyCode::Char('a') | KeyCode::Char('A') => {
+                            app.open_provider_form();
+                        }
+                        KeyCode::Char('d') | KeyCode::Char('D') => {
+                            let highlighted = app.config
+                                .get_providers()
+                                .get(app.provider_selection)
+                                .map(|(provider_id, _)| provider_id.to_string());
+                            if let Some(provider_id) = highlighted {
+                                app.remove_custom_provider(&provider_id);
+                            }
+                        }
                         KeyCode::Esc => {
-                            app.view = AppView::Home;
+                            app.leave_setup_view(AppView::Home);
+                        }
+                        _ => {}
+                    },
+                    AppView::AddProvider => match key.code {
+                        KeyCode::Esc => {
+                            app.leave_setup_view(AppView::SelectProvider);
+                        }
+                        KeyCode::Tab | KeyCode::Down => {
+                            app.provider_form.field = (app.provider_form.field + 1) % PROVIDER_FORM_FIELDS.len();
+                        }
+                        KeyCode::BackTab | KeyCode::Up => {
+                            app.provider_form.field = app.provider_form.field
+                                .checked_sub(1)
+                                .unwrap_or(PROVIDER_FORM_FIELDS.len() - 1);
+                        }
+                        KeyCode::Enter => {
+                            if app.provider_form.field + 1 < PROVIDER_FORM_FIELDS.len() {
+                                app.provider_form.field += 1;
+                            } else {
+                                app.submit_provider_form();
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            let form = &mut app.provider_form;
+                            form.values[form.field].push(c);
+                            form.error = None;
+                        }
+                        KeyCode::Backspace => {
+                            let form = &mut app.provider_form;
+                            form.values[form.field].pop();
+                            form.error = None;
                         }
                         _ => {}
                     },
@@ -953,29 +2135,35 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                             }
                         }
                         KeyCode::Down => {
-                            if let Some(provider) = app.config.get_current_provider() {
-                                if app.model_selection < provider.models.len().saturating_sub(1) {
-                                    app.model_selection += 1;
-                                }
+                            let count = app.config.model_list(Some(&app.config.selected_provider), app.favorites_only).len();
+                            if app.model_selection < count.saturating_sub(1) {
+                                app.model_selection += 1;
                             }
                         }
+                        KeyCode::Char('f') | KeyCode::Char('F') => {
+                            app.toggle_highlighted_favorite(Some(app.config.selected_provider.clone()), app.model_selection);
+                        }
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            app.favorites_only = !app.favorites_only;
+                            app.model_selection = 0;
+                        }
                         KeyCode::Enter => {
-                            if let Some(provider) = app.config.get_current_provider() {
-                                if let Some(model) = provider.models.get(app.model_selection) {
-                                    if model.id == "custom-model" {
-                                        app.view = AppView::CustomModelInput;
-                                    } else {
-                                        app.config.default_model = model.id.clone();
-
-                                        // Save the config with the new model
-                                        if let Err(e) = app.config.save() {
-                                            eprintln!("Failed to save config: {}", e);
-                                        }
+                            let selected = app.config
+                                .model_list(Some(&app.config.selected_provider), app.favorites_only)
+                                .get(app.model_selection)
+                                .map(|(_, model)| model.id.clone());
+                            if let Some(model_id) = selected {
+                                if model_id == "custom-model" {
+                                    app.view = AppView::CustomModelInput;
+                                } else {
+                                    app.config.default_model = model_id;
 
-                                        app.sync_runtime_config();
+                                    // Save the config with the new model
+                                    app.save_config();
 
-                                        app.view = AppView::Home;
-                                    }
+                                    app.sync_runtime_config();
+
+                                    app.leave_setup_view(AppView::Home);
                                 }
                             }
                         }
@@ -988,27 +2176,23 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                         KeyCode::Esc => {
                             app.view = AppView::SelectModel;
                             app.custom_model_input.clear();
+                            app.custom_model_error = None;
+                            app.custom_model_unverified = None;
+                            app.custom_model_check = None;
                         }
                         KeyCode::Enter => {
-                            if !app.custom_model_input.is_empty() {
-                                app.config.set_custom_model(app.custom_model_input.clone());
-
-                                // Save the config with the custom model
-                                if let Err(e) = app.config.save() {
-                                    eprintln!("Failed to save config: {}", e);
-                                }
-
-                                app.sync_runtime_config();
-
-                                app.view = AppView::Home;
-                                app.custom_model_input.clear();
-                            }
+                            app.check_custom_model();
                         }
                         KeyCode::Char(c) => {
                             app.custom_model_input.push(c);
+                            app.custom_model_error = None;
+                            // The lookup was for the id before this edit
+                            app.custom_model_check = None;
                         }
                         KeyCode::Backspace => {
                             app.custom_model_input.pop();
+                            app.custom_model_error = None;
+                            app.custom_model_check = None;
                         }
                         _ => {}
                     },
@@ -1023,17 +2207,105 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                                     crate::ui::conversation::manager::ConversationAction::Exit => {
                                         return Ok(());
                                     }
+                                    crate::ui::conversation::manager::ConversationAction::ShowModelCatalog => {
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.set_focus(false);
+                                        }
+                                        app.open_model_catalog();
+                                    }
                                     crate::ui::conversation::manager::ConversationAction::ShowModelSelection => {
                                         if let Some(ref mut cm) = app.conversation_manager {
                                             cm.set_focus(false);
                                         }
+                                        app.model_catalog_notice = None;
                                         app.view = AppView::ModelSelection;
                                         app.model_switch_selection = 0;
                                     }
+                                    crate::ui::conversation::manager::ConversationAction::ShowProviderSelection => {
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.set_focus(false);
+                                        }
+                                        app.provider_selection = app.config
+                                            .get_providers()
+                                            .iter()
+                                            .position(|(id, _)| **id == app.config.selected_provider)
+                                            .unwrap_or(0);
+                                        app.return_to_conversation = true;
+                                        app.view = AppView::SelectProvider;
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::SwitchModel { provider_id, model_id } => {
+                                        app.config.set_selected_provider(provider_id.clone());
+                                        app.config.default_model = model_id.clone();
+                                        app.save_config();
+                                        app.sync_runtime_config();
+                                        let name = app.config.model_providers
+                                            .get(&provider_id)
+                                            .map_or(provider_id.clone(), |provider| provider.name.clone());
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.notify(format!("Switched to {} ({}).", model_id, name));
+                                        }
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::ShowAddProvider => {
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.set_focus(false);
+                                        }
+                                        app.return_to_conversation = true;
+                                        app.open_provider_form();
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::RemoveProvider(provider_id) => {
+                                        app.remove_custom_provider(&provider_id);
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::SwitchProvider(provider_id) => {
+                                        app.config.switch_provider(&provider_id);
+                                        app.save_config();
+                                        app.sync_runtime_config();
+                                        let name = app.config.model_providers
+                                            .get(&provider_id)
+                                            .map_or(provider_id.clone(), |provider| provider.name.clone());
+                                        let model = app.config.default_model.clone();
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.notify(format!("Switched to {} ({}). /model picks another of its models.", name, model));
+                                        }
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::UpdateApiKey(provider_id) => {
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            cm.set_focus(false);
+                                        }
+                                        app.config.switch_provider(&provider_id);
+                                        app.sync_runtime_config();
+                                        app.key_input.clear();
+                                        app.return_to_conversation = true;
+                                        app.view = AppView::AddKey;
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::OpenExternal { program, path } => {
+                                        let result = run_external(terminal, &program, &path);
+                                        if let Some(ref mut cm) = app.conversation_manager {
+                                            match result {
+                                                Ok(status) if status.success() => {}
+                                                Ok(status) => cm.notify(format!(
+                                                    "{} exited with {}; the response is saved at {}",
+                                                    program,
+                                                    status,
+                                                    path.display()
+                                                )),
+                                                Err(e) => cm.notify(format!(
+                                                    "Failed to launch {}: {}; the response is saved at {}",
+                                                    program,
+                                                    e,
+                                                    path.display()
+                                                )),
+                                            }
+                                        }
+                                    }
+                                    crate::ui::conversation::manager::ConversationAction::WorkspaceChanged(workspace) => {
+                                        app.config.cwd = workspace;
+                                        app.config.reload_user_instructions();
+                                    }
                                     crate::ui::conversation::manager::ConversationAction::None => {}
                                 },
                                 Err(e) => {
-                                    eprintln!("Error handling input: {}", e);
+                                    // Stderr is hidden behind the alternate screen
+                                    conversation_manager.report_error(&e);
                                 }
                             }
                         }
@@ -1045,45 +2317,35 @@ async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app:
                             }
                         }
                         KeyCode::Down => {
-                            let providers = app.config.get_providers();
-                            let total_models: usize = providers
-                                .iter()
-                                .map(|(_, provider)| provider.models.len())
-                                .sum();
+                            let total_models = app.config.model_list(None, app.favorites_only).len();
                             if app.model_switch_selection < total_models.saturating_sub(1) {
                                 app.model_switch_selection += 1;
                             }
                         }
+                        KeyCode::Char('f') | KeyCode::Char('F') => {
+                            app.toggle_highlighted_favorite(None, app.model_switch_selection);
+                        }
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            app.favorites_only = !app.favorites_only;
+                            app.model_switch_selection = 0;
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            app.refresh_openrouter_models().await;
+                        }
                         KeyCode::Enter => {
                             // Find the selected model across all providers
-                            let providers = app.config.get_providers();
-                            let mut current_index = 0;
-                            let mut selected_provider_id = None;
-                            let mut selected_model_id = None;
-
-                            for (provider_id, provider) in providers.iter() {
-                                for model in &provider.models {
-                                    if current_index == app.model_switch_selection {
-                                        selected_provider_id = Some(provider_id.to_string());
-                                        selected_model_id = Some(model.id.clone());
-                                        break;
-                                    }
-                                    current_index += 1;
-                                }
-                                if selected_provider_id.is_some() {
-                                    break;
-                                }
-                            }
+                            let selected = app.config
+                                .model_list(None, app.favorites_only)
+                                .get(app.model_switch_selection)
+                                .map(|(provider_id, model)| (provider_id.to_string(), model.id.clone()));
 
-                            if let (Some(provider_id), Some(model_id)) = (selected_provider_id, selected_model_id) {
+                            if let Some((provider_id, model_id)) = selected {
                                 // Switch to this provider and model
                                 app.config.set_selected_provider(provider_id);
                                 app.config.default_model = model_id;
 
                                 // Save the config
-                                if let Err(e) = app.config.save() {
-                                    eprintln!("Failed to save config: {}", e);
-                                }
+                                app.save_config();
 
                                 app.sync_runtime_config();
 
@@ -1124,7 +2386,7 @@ async fn main() -> anyhow::Result<()> {
 
     match cli.command {
         None => {
-            if let Err(e) = run_tui().await {
+            if let Err(e) = run_tui(cli.workspace).await {
                 eprintln!("Error running TUI: {}", e);
                 std::process::exit(1);
             }
@@ -1135,7 +2397,254 @@ async fn main() -> anyhow::Result<()> {
         Some(Commands::Open { name }) => {
             open_project(&name).await?;
         }
+        Some(Commands::Resume { list }) => {
+            resume_project(list).await?;
+        }
+        Some(Commands::Stats { name }) => {
+            show_stats(&name).await?;
+        }
+        Some(Commands::Delete { name, force }) => {
+            delete_project(&name, force)?;
+        }
+        Some(Commands::Doctor) => {
+            run_doctor()?;
+        }
+        Some(Commands::Init { agents }) => {
+            run_init(agents, cli.workspace)?;
+        }
+        Some(Commands::Search { query, regex, limit }) => {
+            search_conversations(&query, regex, limit)?;
+        }
+        Some(Commands::Models { query, refresh, merge }) => {
+            list_models(query, refresh, merge).await?;
+        }
+        Some(Commands::Config { action }) => {
+            run_config(action)?;
+        }
+        Some(Commands::Chat { prompt, mode, model, json }) => {
+            if let Err(e) = run_chat(prompt, &mode, model, json, cli.workspace).await {
+                eprintln!("❌ {:#}", e);
+                std::process::exit(1);
+            }
+        }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_provider::{openai_reply, MockProvider};
+
+    /// Config on a throwaway Bindr home whose OpenAI provider is `server`
+    fn config_against(home: &tempfile::TempDir, server: &MockProvider) -> Config {
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+        config.switch_provider("openai");
+        config.default_model = "gpt-4o-mini".to_string();
+        config.model_providers.get_mut("openai").unwrap().base_url = server.base_url.clone();
+        config.set_api_key("openai".to_string(), "test-key".to_string());
+        config.ui.retry_base_delay_ms = 1;
+        config
+    }
+
+    async fn chat(config: Config, mode: BindrMode, model: Option<&str>, json: bool) -> anyhow::Result<String> {
+        let mut out = Vec::new();
+        chat_turn(config, mode, model.map(str::to_string), "Say hello".to_string(), json, &mut out).await?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[tokio::test]
+    async fn chat_prints_the_streamed_reply() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello there"))]).await;
+
+        let output = chat(config_against(&home, &server), BindrMode::Brainstorm, None, false).await.unwrap();
+
+        assert_eq!(output, "Hello there\n");
+        let body = &server.requests()[0].body;
+        assert_eq!(body["model"], "gpt-4o-mini");
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.last().unwrap()["content"], "Say hello");
+    }
+
+    #[tokio::test]
+    async fn chat_json_reports_mode_model_and_reply() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello there"))]).await;
+
+        let output = chat(config_against(&home, &server), BindrMode::Plan, Some("gpt-4.1"), true).await.unwrap();
+
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["mode"], "plan");
+        assert_eq!(output["model"], "gpt-4.1");
+        assert_eq!(output["reply"], "Hello there");
+        assert_eq!(server.requests()[0].body["model"], "gpt-4.1");
+    }
+
+    #[tokio::test]
+    async fn chat_fails_on_a_provider_error() {
+        let home = tempfile::tempdir().unwrap();
+        let body = r#"{"error": {"message": "Incorrect API key provided", "type": "invalid_request_error"}}"#;
+        let server = MockProvider::start(vec![(401, body.to_string())]).await;
+
+        let error = chat(config_against(&home, &server), BindrMode::Brainstorm, None, false).await.unwrap_err();
+
+        assert!(error.to_string().contains("Invalid API key (401)"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn chat_without_a_key_fails_before_sending() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello"))]).await;
+        let mut config = config_against(&home, &server);
+        config.remove_api_key("openai");
+        config.model_providers.get_mut("openai").unwrap().api_key_env = None;
+
+        let error = chat(config, BindrMode::Brainstorm, None, false).await.unwrap_err();
+
+        assert!(error.to_string().starts_with("No API key configured for openai"));
+        assert!(server.requests().is_empty());
+    }
+
+    /// Run `bindr <args>` as clap parses it, with `stdin` piped in
+    async fn run_cli(config: Config, args: &[&str], stdin: &str) -> anyhow::Result<String> {
+        let cli = Cli::try_parse_from(std::iter::once("bindr").chain(args.iter().copied()))?;
+        let Some(Commands::Chat { prompt, mode, model, json }) = cli.command else {
+            panic!("not a chat command: {:?}", args);
+        };
+        let mut out = Vec::new();
+        chat_command(config, prompt, &mode, model, json, &mut stdin.as_bytes(), &mut out).await?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[tokio::test]
+    async fn chat_flags_and_piped_prompt_reach_the_provider() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Start with tomatoes"))]).await;
+
+        let args = ["chat", "--mode", "plan", "--model", "gpt-4.1", "--json"];
+        let output = run_cli(config_against(&home, &server), &args, "Plan a garden\n").await.unwrap();
+
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["mode"], "plan");
+        assert_eq!(output["reply"], "Start with tomatoes");
+        let body = &server.requests()[0].body;
+        assert_eq!(body["model"], "gpt-4.1");
+        assert_eq!(body["messages"].as_array().unwrap().last().unwrap()["content"], "Plan a garden\n");
+    }
+
+    #[tokio::test]
+    async fn chat_refuses_an_unknown_mode_or_an_empty_prompt() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello"))]).await;
+
+        let error = run_cli(config_against(&home, &server), &["chat", "--mode", "sideways", "Hi"], "").await.unwrap_err();
+        assert!(error.to_string().starts_with("Unknown mode 'sideways'"), "{}", error);
+
+        let error = run_cli(config_against(&home, &server), &["chat"], "  \n").await.unwrap_err();
+        assert!(error.to_string().starts_with("Nothing to send"), "{}", error);
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn set_provider_is_saved() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+
+        let output = config_command(&mut config, ConfigAction::SetProvider { id: "mistral".to_string() }).unwrap();
+
+        assert!(output.starts_with("✅ Selected mistral with model "));
+        let reloaded = Config::load_from(home.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.selected_provider, "mistral");
+        assert_eq!(reloaded.default_model, config.default_model);
+    }
+
+    #[test]
+    fn unknown_provider_is_not_saved() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+
+        assert!(config_command(&mut config, ConfigAction::SetProvider { id: "nowhere".to_string() }).is_err());
+        assert!(!home.path().join("config.toml").exists());
+    }
+
+    #[test]
+    fn show_masks_every_key() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+        let key = "sk-proj-abcdefghijklmnop1234";
+        let set_key = ConfigAction::SetKey { provider: "openai".to_string(), key: key.to_string() };
+        assert_eq!(config_command(&mut config, set_key).unwrap(), "✅ Stored API key ****1234 for openai\n");
+
+        let mut reloaded = Config::load_from(home.path().to_path_buf()).unwrap();
+        let shown = config_command(&mut reloaded, ConfigAction::Show).unwrap();
+
+        assert!(!shown.contains(key));
+        assert!(!shown.contains("abcdefgh"));
+        assert!(shown.contains("****1234"));
+    }
+
+    /// App on a throwaway Bindr home whose OpenRouter catalog is `server`
+    fn app_against(home: &tempfile::TempDir, server: &MockProvider) -> App {
+        let mut config = Config::load_from(home.path().to_path_buf()).unwrap();
+        config.model_providers.get_mut("openrouter").unwrap().base_url = server.base_url.clone();
+        // Other tests share the catalog cache
+        config.ui.model_catalog_ttl_secs = 0;
+        let session_manager = SessionManager::new(config.clone());
+        let (mut app, _) = App::new(config, session_manager);
+        app.view = AppView::CustomModelInput;
+        app
+    }
+
+    /// Wait for the custom model lookup to come back and be acted on
+    async fn finish_check(app: &mut App) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while app.custom_model_check.is_some() {
+                app.finish_custom_model_check();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    const CATALOG: &str = r#"{"data":[{"id":"meta-llama/llama-3.1-8b-instruct","name":"Llama 3.1 8B"}]}"#;
+
+    #[tokio::test]
+    async fn custom_model_is_checked_in_the_background() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, CATALOG.to_string())]).await;
+        let mut app = app_against(&home, &server);
+        app.custom_model_input = "meta-llama/llama-3.1-8b-instruct".to_string();
+
+        app.check_custom_model();
+        // Nothing is saved until the catalog answers
+        assert!(app.custom_model_check.is_some());
+        assert!(matches!(app.view, AppView::CustomModelInput));
+
+        finish_check(&mut app).await;
+        assert_eq!(server.requests()[0].target, "GET /v1/models");
+        assert_eq!(app.config.default_model, "meta-llama/llama-3.1-8b-instruct");
+        assert!(matches!(app.view, AppView::Home));
+    }
+
+    #[tokio::test]
+    async fn unknown_custom_model_is_not_saved() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, CATALOG.to_string())]).await;
+        let mut app = app_against(&home, &server);
+        let default_model = app.config.default_model.clone();
+        app.custom_model_input = "meta-llama/llama-3.1".to_string();
+
+        app.check_custom_model();
+        finish_check(&mut app).await;
+
+        assert_eq!(
+            app.custom_model_error.as_deref(),
+            Some("OpenRouter has no model named 'meta-llama/llama-3.1'. Did you mean: meta-llama/llama-3.1-8b-instruct?")
+        );
+        assert_eq!(app.config.default_model, default_model);
+        assert!(matches!(app.view, AppView::CustomModelInput));
+    }
+}
@@ -0,0 +1,98 @@
+//! Local HTTP server standing in for an LLM provider in tests
+
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// One request the server received
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// Method and path, e.g. `POST /v1/chat/completions`
+    pub target: String,
+    pub body: serde_json::Value,
+}
+
+/// Answers each request with the next queued `(status, body)`, repeating the last once the queue runs out
+pub struct MockProvider {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockProvider {
+    pub async fn start(replies: Vec<(u16, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            let mut replies = replies.into_iter();
+            let mut last = (200, String::new());
+            while let Ok((mut socket, _)) = listener.accept().await {
+                if let Some(reply) = replies.next() {
+                    last = reply;
+                }
+                let Some(request) = read_request(&mut socket).await else {
+                    continue;
+                };
+                recorded.lock().unwrap().push(request);
+                let (status, body) = &last;
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// SSE body of an OpenAI-style reply that streams `text` in two pieces
+pub fn openai_reply(text: &str) -> String {
+    let (head, tail) = text.split_at(text.len() / 2);
+    let mut body = String::new();
+    for piece in [head, tail] {
+        let chunk = serde_json::json!({ "choices": [{ "delta": { "content": piece } }] });
+        body.push_str(&format!("data: {}\n\n", chunk));
+    }
+    body.push_str("data: [DONE]\n\n");
+    body
+}
+
+async fn read_request(socket: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut bytes = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let header_end = loop {
+        let read = socket.read(&mut buffer).await.ok().filter(|read| *read > 0)?;
+        bytes.extend_from_slice(&buffer[..read]);
+        if let Some(end) = bytes.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&bytes[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while bytes.len() < header_end + content_length {
+        let read = socket.read(&mut buffer).await.ok().filter(|read| *read > 0)?;
+        bytes.extend_from_slice(&buffer[..read]);
+    }
+
+    let target = head.lines().next()?.rsplit_once(' ')?.0.to_string();
+    let body = serde_json::from_slice(&bytes[header_end..header_end + content_length]).unwrap_or_default();
+    Some(RecordedRequest { target, body })
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How long to wait for another instance to release a lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Delay between attempts to take a held lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Locks older than this are assumed to belong to a crashed instance
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10);
+
+/// Write a file by renaming a fully written sibling temp file over it.
+///
+/// Readers never observe a partial write, and a crash before the rename
+/// leaves the previous contents intact.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path {}", path.display()))?
+        .to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let written = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Create `path` with `contents` unless it already exists; returns whether it was written
+pub fn write_if_missing(path: &Path, contents: impl AsRef<[u8]>) -> Result<bool> {
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(false),
+        Err(e) => return Err(e).with_context(|| format!("Failed to create {}", path.display())),
+    };
+    file.write_all(contents.as_ref())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+/// Check that files can be created in `dir`, creating it if needed
+pub fn probe_writable(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let probe = dir.join(format!(".bindr-write-test.{}", std::process::id()));
+    write_atomic(&probe, b"ok")?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Whether an error comes from missing write permission or a read-only filesystem
+pub fn is_unwritable(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|io| matches!(io.kind(), ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem))
+}
+
+/// Advisory lock held via a `<file>.lock` sibling; released on drop
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Wait for exclusive access to `target`, clearing locks left behind by crashed instances
+    pub fn acquire(target: &Path) -> Result<Self> {
+        let mut lock_name = target.as_os_str().to_owned();
+        lock_name.push(".lock");
+        let path = PathBuf::from(lock_name);
+        let started = SystemTime::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                        anyhow::bail!(
+                            "Timed out waiting for {}; another Bindr instance may be writing it",
+                            path.display()
+                        );
+                    }
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create lock {}", path.display()));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_write_leaves_the_old_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "theme = \"dark\"\n").unwrap();
+        // A directory where the temp file goes makes the write fail before the rename
+        fs::create_dir(dir.path().join(format!(".config.toml.{}.tmp", std::process::id()))).unwrap();
+
+        assert!(write_atomic(&path, "theme = \"li").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "theme = \"dark\"\n");
+    }
+
+    #[test]
+    fn config_survives_a_crash_before_rename() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = crate::config::Config::load_from(home.path().to_path_buf()).unwrap();
+        config.set_api_key("openai".to_string(), "sk-old".to_string());
+        config.save().unwrap();
+        // What a crash mid-write leaves behind: a partial temp file and no rename
+        fs::write(home.path().join(".config.toml.999.tmp"), "[api_keys]\nopenai = \"sk-ne").unwrap();
+
+        let reloaded = crate::config::Config::load_from(home.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.get_api_key_for("openai").as_deref(), Some("sk-old"));
+    }
+
+    #[test]
+    fn write_replaces_the_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+}
@@ -0,0 +1,216 @@
+/// Rough characters-per-token ratio used in place of a provider tokenizer
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximate list prices in USD per million input and output tokens, matched by model id fragment.
+///
+/// More specific fragments come first so `gpt-4o-mini` isn't priced as `gpt-4o`.
+const PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-5", 1.25, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("opus", 15.00, 75.00),
+    ("sonnet", 3.00, 15.00),
+    ("haiku", 0.80, 4.00),
+    ("gemini-2.5-pro", 1.25, 10.00),
+    ("gemini-2.5-flash", 0.30, 2.50),
+    ("grok-4", 3.00, 15.00),
+    ("grok-3", 3.00, 15.00),
+    ("grok-beta", 5.00, 15.00),
+    ("mistral-large", 2.00, 6.00),
+    ("mistral-medium", 0.40, 2.00),
+    ("mistral-small", 0.10, 0.30),
+];
+
+/// Estimate how many tokens a piece of text will use
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// What a model charges, in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rates {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl Rates {
+    /// Approximate list prices for a model, or `None` when it isn't known
+    pub fn for_model(model_id: &str) -> Option<Self> {
+        let model_id = model_id.to_lowercase();
+        if model_id.ends_with(":free") {
+            return Some(Self { input: 0.0, output: 0.0 });
+        }
+        // OpenRouter ids are namespaced by vendor, e.g. `openai/gpt-5`
+        let name = model_id.rsplit('/').next().unwrap_or(&model_id);
+        PRICES
+            .iter()
+            .find(|(fragment, _, _)| name.contains(fragment))
+            .map(|&(_, input, output)| Self { input, output })
+    }
+
+    /// USD cost of sending `tokens` input tokens
+    pub fn input_cost(&self, tokens: usize) -> f64 {
+        self.input * tokens as f64 / 1_000_000.0
+    }
+
+    /// USD cost of a whole turn
+    pub fn cost(&self, usage: TokenUsage) -> f64 {
+        self.input_cost(usage.input_tokens) + self.output * usage.output_tokens as f64 / 1_000_000.0
+    }
+}
+
+/// Tokens one turn sent and received, as reported by the provider or estimated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+}
+
+impl TokenUsage {
+    /// Take each count `update` reports; providers send input and output counts in separate events
+    pub fn merge(&mut self, update: TokenUsage) {
+        if update.input_tokens > 0 {
+            self.input_tokens = update.input_tokens;
+        }
+        if update.output_tokens > 0 {
+            self.output_tokens = update.output_tokens;
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Running token and cost totals for the turns of one session
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionCost {
+    pub usage: TokenUsage,
+    /// USD spent on turns whose model has known rates
+    pub cost: f64,
+    /// Some turn went to a model without known rates, so `cost` is a lower bound
+    pub unpriced_turns: bool,
+    /// Some turn's token counts were estimated rather than reported
+    pub estimated: bool,
+}
+
+impl SessionCost {
+    /// Add one turn to the totals and return what it cost; `exact` when the provider reported the counts
+    pub fn add_turn(&mut self, usage: TokenUsage, exact: bool, rates: Option<Rates>) -> TurnCost {
+        self.usage.input_tokens += usage.input_tokens;
+        self.usage.output_tokens += usage.output_tokens;
+        self.estimated |= !exact;
+        let cost = rates.map(|rates| rates.cost(usage));
+        match cost {
+            Some(cost) => self.cost += cost,
+            None => self.unpriced_turns = true,
+        }
+        TurnCost { usage, exact, cost }
+    }
+}
+
+/// Tokens and cost of a single reply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnCost {
+    pub usage: TokenUsage,
+    /// Both counts came from the provider rather than Bindr's estimate
+    pub exact: bool,
+    /// `None` when the model's rates aren't known
+    pub cost: Option<f64>,
+}
+
+/// Token count in compact form, e.g. `850` or `12.3k`
+pub fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1000 {
+        format!("{:.1}k", tokens as f64 / 1000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Format a small dollar amount with enough precision to be meaningful
+pub fn format_cost(usd: f64) -> String {
+    if usd >= 0.1 {
+        format!("~${:.2}", usd)
+    } else {
+        format!("~${:.4}", usd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input_tokens: usize, output_tokens: usize) -> TokenUsage {
+        TokenUsage { input_tokens, output_tokens }
+    }
+
+    #[test]
+    fn tokens_are_estimated_from_length() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens(&"x".repeat(400)), 100);
+    }
+
+    #[test]
+    fn rates_match_the_most_specific_fragment() {
+        assert_eq!(Rates::for_model("gpt-4o-mini"), Some(Rates { input: 0.15, output: 0.60 }));
+        assert_eq!(Rates::for_model("GPT-4o"), Some(Rates { input: 2.50, output: 10.00 }));
+        assert_eq!(Rates::for_model("anthropic/claude-sonnet-4"), Some(Rates { input: 3.00, output: 15.00 }));
+        assert_eq!(Rates::for_model("meta-llama/llama-3:free"), Some(Rates { input: 0.0, output: 0.0 }));
+        assert_eq!(Rates::for_model("some-local-model"), None);
+    }
+
+    #[test]
+    fn cost_adds_input_and_output() {
+        let rates = Rates { input: 2.0, output: 10.0 };
+        assert!((rates.cost(usage(1_000_000, 0)) - 2.0).abs() < 1e-9);
+        assert!((rates.cost(usage(500_000, 100_000)) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn session_totals_add_up_across_turns() {
+        let rates = Some(Rates { input: 1.0, output: 4.0 });
+        let mut session = SessionCost::default();
+
+        let first = session.add_turn(usage(1_000_000, 250_000), true, rates);
+        assert_eq!(first.cost, Some(2.0));
+        session.add_turn(usage(500_000, 0), true, rates);
+
+        assert_eq!(session.usage, usage(1_500_000, 250_000));
+        assert!((session.cost - 2.5).abs() < 1e-9);
+        assert!(!session.estimated);
+        assert!(!session.unpriced_turns);
+    }
+
+    #[test]
+    fn estimated_and_unpriced_turns_are_flagged() {
+        let mut session = SessionCost::default();
+        session.add_turn(usage(100, 50), false, Some(Rates { input: 1.0, output: 1.0 }));
+        let unpriced = session.add_turn(usage(100, 50), true, None);
+
+        assert_eq!(unpriced.cost, None);
+        assert_eq!(session.usage.total(), 300);
+        assert!(session.estimated);
+        assert!(session.unpriced_turns);
+    }
+
+    #[test]
+    fn merge_keeps_counts_from_separate_events() {
+        let mut total = TokenUsage::default();
+        total.merge(usage(120, 0));
+        total.merge(usage(0, 30));
+        assert_eq!(total, usage(120, 30));
+    }
+
+    #[test]
+    fn totals_are_formatted_compactly() {
+        assert_eq!(format_tokens(850), "850");
+        assert_eq!(format_tokens(12_345), "12.3k");
+        assert_eq!(format_cost(0.25), "~$0.25");
+        assert_eq!(format_cost(0.0123), "~$0.0123");
+    }
+}
@@ -10,6 +10,95 @@ pub fn mode_prompt(mode: BindrMode) -> &'static str {
     }
 }
 
+/// Instruction appended to the system prompt so replies come back in the user's language.
+pub fn locale_instruction(locale: &str) -> String {
+    format!(
+        "Respond in the language for the locale \"{}\" unless the user explicitly asks for another. \
+         Keep code, identifiers, file paths, commands, and the JSON handoff keys in their original form.",
+        locale
+    )
+}
+
+/// Opening guidance shown when entering a mode, orienting the user to its purpose and limits.
+pub fn mode_greeting(mode: BindrMode) -> &'static str {
+    match mode {
+        BindrMode::Brainstorm => "Tell me about the problem you're exploring. I'll ask questions and capture requirements, but won't touch any files.",
+        BindrMode::Plan => "Describe the project you want to structure. I'll propose a layout and roadmap and ask before scaffolding anything.",
+        BindrMode::Execute => "Describe what to build; I'll show diffs before writing and ask before running commands.",
+        BindrMode::Document => "Tell me what needs documenting. I'll draft docs and changelogs and leave your code untouched.",
+    }
+}
+
+/// Built-in troubleshooting for common provider failures, so they don't need a model round-trip.
+pub fn explain_error(error: &str) -> Option<&'static str> {
+    let error = error.to_lowercase();
+    let mentions = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+
+    if mentions(&["401", "403", "unauthorized", "forbidden", "invalid api key", "was rejected"]) {
+        Some("The provider rejected your API key. It may be mistyped, revoked, or missing access to this model. Press Ctrl+K (or K on the home screen) to enter a new key.")
+    } else if mentions(&["429", "rate limit", "too many requests", "quota"]) {
+        Some("You've hit the provider's rate limit or exhausted your quota. Wait a moment and retry, check your plan's billing and limits, or switch to another model with /model.")
+    } else if mentions(&["context_length", "context length", "maximum context", "too many tokens", "prompt is too long"]) {
+        Some("The conversation no longer fits in the model's context window. Start a fresh conversation from /home or switch to a model with a larger context via /model.")
+    } else if mentions(&["model_not_found", "model not found", "does not exist", "not a valid model", "unknown model", "404"]) {
+        Some("The provider doesn't recognise the selected model id. It may be misspelled, retired, or unavailable on your account. Use /catalog to look up a valid id and /model to switch.")
+    } else {
+        None
+    }
+}
+
+/// Request sent to the model when an error has no built-in explanation.
+pub fn explain_error_prompt(error: &str) -> String {
+    format!(
+        "My last request to the model provider failed with this error:\n\n{}\n\nExplain briefly what it means and how I can fix it.",
+        error
+    )
+}
+
+/// Message with the files the user attached via /attach placed ahead of it.
+pub fn with_attachments<'a>(message: &str, files: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut prompt = String::from("I attached these files for context:\n\n");
+    for (path, contents) in files {
+        prompt.push_str(&format!("<file path=\"{}\">\n{}\n</file>\n\n", path, contents));
+    }
+    prompt.push_str(message);
+    prompt
+}
+
+/// Starting point for a project's AGENTS.md, written by `bindr init --agents`.
+pub const AGENTS_TEMPLATE: &str = r#"# AGENTS.md
+
+Instructions for AI assistants working in this repository. Bindr reads this file
+from the workspace (or its parent directory) and passes it to the model.
+
+## Project overview
+- What this project does and who it is for.
+
+## Build and test
+- How to build: `...`
+- How to run the tests: `...`
+
+## Conventions
+- Code style, naming, and formatting rules to follow.
+- Directories or files that must not be edited.
+
+## Review checklist
+- What a change needs before it is ready (tests, docs, changelog).
+"#;
+
+/// Reply marker the model uses to end an /auto run once the checklist is done.
+pub const AUTO_CONTINUE_DONE: &str = "ALL STEPS COMPLETE";
+
+/// Message sent on the user's behalf to move an /auto run to the next checklist item.
+pub fn auto_continue_prompt(step: usize, max_steps: usize) -> String {
+    format!(
+        "Auto-continue step {} of {}: proceed to the next unchecked item in the approved checklist. \
+         Request approval for every file change or command as usual. \
+         If every item is done, reply with \"{}\" and a short summary.",
+        step, max_steps, AUTO_CONTINUE_DONE
+    )
+}
+
 const BRAINSTORM_PROMPT: &str = r#"You are in **Brainstorm mode** inside Bindr.
 
 Core Objectives:
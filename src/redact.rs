@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::borrow::Cow;
+
+use crate::config::RedactionConfig;
+
+/// Secrets the model commonly echoes back, checked when `[redaction].builtin_detectors` is on
+const BUILTIN_DETECTORS: &[(&str, &str)] = &[
+    ("private-key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----"),
+    ("aws-access-key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    ("aws-secret-key", r"(?i)\baws_secret_access_key\b\s*[:=]\s*[A-Za-z0-9/+=]{40}"),
+    ("github-token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("api-key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+    ("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b"),
+];
+
+/// Masks configured patterns in assistant output before it is shown, stored, or exported
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    rules: Vec<(String, Regex)>,
+}
+
+impl Redactor {
+    /// Build the redactor described by `[redaction]`; `None` when it is turned off
+    pub fn from_config(config: &RedactionConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let mut rules = Vec::new();
+        if config.builtin_detectors {
+            for (label, pattern) in BUILTIN_DETECTORS {
+                rules.push((label.to_string(), Regex::new(pattern).expect("built-in pattern is valid")));
+            }
+        }
+        for pattern in &config.patterns {
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("Invalid redaction pattern {:?}", pattern))?;
+            rules.push(("custom".to_string(), regex));
+        }
+
+        Ok(Some(Self { rules }))
+    }
+
+    /// Replace every match with a `[redacted: <label>]` placeholder
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut result = Cow::Borrowed(text);
+        for (label, regex) in &self.rules {
+            if regex.is_match(&result) {
+                let replaced = regex
+                    .replace_all(&result, format!("[redacted: {}]", label).as_str())
+                    .into_owned();
+                result = Cow::Owned(replaced);
+            }
+        }
+        result
+    }
+}
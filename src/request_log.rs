@@ -0,0 +1,128 @@
+//! Optional trace of provider requests under `<bindr home>/logs`, for working out what went wrong.
+//!
+//! Only metadata is written (provider, model, message count, and the outcome with any error the
+//! provider returned), never API keys or message text.
+
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Environment variable that turns the log on without `[ui].debug_log`
+pub const LOG_ENV: &str = "BINDR_LOG";
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "requests.log";
+
+/// Size at which the log moves to `requests.log.1`, replacing the one kept before
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Where request traces go, or nowhere when logging is off
+#[derive(Debug, Clone, Default)]
+pub struct RequestLog {
+    path: Option<PathBuf>,
+}
+
+impl RequestLog {
+    /// Logging to the Bindr home when `[ui].debug_log` is on or `BINDR_LOG` is set to anything but `0`
+    pub fn from_config(config: &Config) -> Self {
+        let from_env = std::env::var(LOG_ENV).is_ok_and(|value| !value.is_empty() && value != "0");
+        let path = (config.ui.debug_log || from_env)
+            .then(|| config.bindr_home.join(LOG_DIR).join(LOG_FILE));
+        Self { path }
+    }
+
+    /// A request is about to be sent
+    pub fn request(&self, provider_id: &str, model: &str, messages: usize) {
+        self.write(&format!("request provider={} model={} messages={}", provider_id, model, messages));
+    }
+
+    /// How a request ended; provider errors carry their status and the message from the response body
+    pub fn outcome(&self, provider_id: &str, model: &str, result: &Result<()>) {
+        let line = match result {
+            Ok(()) => format!("response provider={} model={} ok", provider_id, model),
+            Err(e) => format!("response provider={} model={} error: {}", provider_id, model, e),
+        };
+        self.write(&line);
+    }
+
+    fn write(&self, line: &str) {
+        if let Some(path) = &self.path {
+            // The log is a diagnostic aid, so failing to write it must not fail the request
+            let _ = Self::append(path, line);
+        }
+    }
+
+    fn append(path: &Path, line: &str) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_BYTES) {
+            fs::rename(path, path.with_extension("log.1"))?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{} {}", Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true), line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_in(home: &Path, debug_log: bool) -> RequestLog {
+        let mut config = Config::default();
+        config.bindr_home = home.to_path_buf();
+        config.ui.debug_log = debug_log;
+        RequestLog::from_config(&config)
+    }
+
+    fn lines(home: &Path) -> Vec<String> {
+        fs::read_to_string(home.join(LOG_DIR).join(LOG_FILE))
+            .unwrap()
+            .lines()
+            .map(|line| line.split_once(' ').unwrap().1.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn request_lifecycle_is_logged() {
+        let home = tempfile::tempdir().unwrap();
+        let log = log_in(home.path(), true);
+
+        log.request("openai", "gpt-4o", 3);
+        log.outcome("openai", "gpt-4o", &Ok(()));
+        log.outcome("openai", "gpt-4o", &Err(anyhow::anyhow!("Rate limited (429): slow down")));
+
+        assert_eq!(lines(home.path()), [
+            "request provider=openai model=gpt-4o messages=3",
+            "response provider=openai model=gpt-4o ok",
+            "response provider=openai model=gpt-4o error: Rate limited (429): slow down",
+        ]);
+    }
+
+    #[test]
+    fn nothing_is_written_when_logging_is_off() {
+        if std::env::var(LOG_ENV).is_ok() {
+            return;
+        }
+        let home = tempfile::tempdir().unwrap();
+        log_in(home.path(), false).request("openai", "gpt-4o", 1);
+        assert!(!home.path().join(LOG_DIR).exists());
+    }
+
+    #[test]
+    fn full_log_is_rotated() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = home.path().join(LOG_DIR);
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join(LOG_FILE), vec![b'x'; MAX_LOG_BYTES as usize]).unwrap();
+
+        log_in(home.path(), true).request("openai", "gpt-4o", 1);
+
+        assert_eq!(fs::metadata(dir.join("requests.log.1")).unwrap().len(), MAX_LOG_BYTES);
+        assert_eq!(lines(home.path()), ["request provider=openai model=gpt-4o messages=1"]);
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::events::{BindrMode, ConversationRole, ProjectState};
+use crate::persist::{write_atomic, FileLock};
+
+const INDEX_FILE: &str = "search_index.json";
+
+/// Characters of context shown either side of a match
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Word index over every project's persisted conversation, kept under the Bindr home
+/// so a search only has to load the projects that can match.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    projects: HashMap<String, ProjectIndex>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectIndex {
+    /// Modification time of `state.json` when it was indexed; a newer file means the entry is stale
+    modified: DateTime<Utc>,
+    current_mode: BindrMode,
+    last_activity: DateTime<Utc>,
+    /// Lowercased word → positions in `conversation_history` of the entries containing it
+    terms: BTreeMap<String, Vec<usize>>,
+}
+
+/// One place a query matched
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub project: String,
+    pub mode: BindrMode,
+    pub timestamp: DateTime<Utc>,
+    /// `None` when the project name matched rather than a message
+    pub role: Option<ConversationRole>,
+    pub snippet: String,
+}
+
+impl ProjectIndex {
+    fn build(state: &ProjectState, modified: DateTime<Utc>) -> Self {
+        let mut terms: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (position, entry) in state.conversation_history.iter().enumerate() {
+            let words: BTreeSet<String> = words(&entry.content).collect();
+            for word in words {
+                terms.entry(word).or_default().push(position);
+            }
+        }
+        Self {
+            modified,
+            current_mode: state.current_mode,
+            last_activity: state.last_activity,
+            terms,
+        }
+    }
+
+    /// Entries that contain every query word somewhere inside one of their words.
+    ///
+    /// A plain-text match implies this, so entries outside the set can be skipped.
+    fn candidates(&self, query_words: &[String]) -> BTreeSet<usize> {
+        let mut result: Option<BTreeSet<usize>> = None;
+        for query_word in query_words {
+            let positions: BTreeSet<usize> = self.terms
+                .iter()
+                .filter(|(term, _)| term.contains(query_word.as_str()))
+                .flat_map(|(_, positions)| positions.iter().copied())
+                .collect();
+            result = Some(match result {
+                Some(previous) => previous.intersection(&positions).copied().collect(),
+                None => positions,
+            });
+        }
+        result.unwrap_or_default()
+    }
+}
+
+impl SearchIndex {
+    fn path(bindr_home: &Path) -> PathBuf {
+        bindr_home.join(INDEX_FILE)
+    }
+
+    /// Load the index, starting empty when it is missing or unreadable
+    fn load(bindr_home: &Path) -> Self {
+        fs::read_to_string(Self::path(bindr_home))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, bindr_home: &Path) -> Result<()> {
+        let content = serde_json::to_string(self).context("Failed to serialize search index")?;
+        write_atomic(&Self::path(bindr_home), content).context("Failed to write search index")
+    }
+
+    /// Re-index a project after its `state.json` was written
+    pub fn update(bindr_home: &Path, state: &ProjectState, state_path: &Path) -> Result<()> {
+        let modified = modified_at(state_path)?;
+        let _lock = FileLock::acquire(&Self::path(bindr_home))?;
+        let mut index = Self::load(bindr_home);
+        index.projects.insert(state.name.clone(), ProjectIndex::build(state, modified));
+        index.save(bindr_home)
+    }
+
+    /// Drop a deleted project from the index
+    pub fn remove(bindr_home: &Path, name: &str) -> Result<()> {
+        let _lock = FileLock::acquire(&Self::path(bindr_home))?;
+        let mut index = Self::load(bindr_home);
+        if index.projects.remove(name).is_some() {
+            index.save(bindr_home)?;
+        }
+        Ok(())
+    }
+
+    /// Search every project's conversation history and name, newest hits first.
+    ///
+    /// `query` is a case-insensitive substring, or a regular expression when `is_regex` is set.
+    /// Projects whose index entry is missing or stale are re-indexed along the way.
+    pub fn search(config: &Config, query: &str, is_regex: bool, limit: usize) -> Result<Vec<SearchHit>> {
+        let pattern = if is_regex { query.to_string() } else { regex::escape(query) };
+        let matcher = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .with_context(|| format!("Invalid search pattern '{}'", query))?;
+        // Regexes can match across or inside words in ways the index can't rule out
+        let query_words: Option<Vec<String>> = (!is_regex)
+            .then(|| words(query).collect::<Vec<_>>())
+            .filter(|words| !words.is_empty());
+
+        let _lock = FileLock::acquire(&Self::path(&config.bindr_home))?;
+        let mut index = Self::load(&config.bindr_home);
+        let mut index_changed = false;
+        let mut hits = Vec::new();
+
+        for (name, state_path) in project_states(&config.projects_dir)? {
+            let Ok(modified) = modified_at(&state_path) else {
+                continue;
+            };
+            let mut state = None;
+            if index.projects.get(&name).is_none_or(|entry| entry.modified < modified) {
+                let Some(loaded) = load_state(&state_path) else {
+                    continue;
+                };
+                index.projects.insert(name.clone(), ProjectIndex::build(&loaded, modified));
+                index_changed = true;
+                state = Some(loaded);
+            }
+            let entry = &index.projects[&name];
+
+            if let Some(found) = matcher.find(&name) {
+                hits.push(SearchHit {
+                    project: name.clone(),
+                    mode: entry.current_mode,
+                    timestamp: entry.last_activity,
+                    role: None,
+                    snippet: snippet(&name, found.start(), found.end()),
+                });
+            }
+
+            let candidates = query_words.as_ref().map(|words| entry.candidates(words));
+            if candidates.as_ref().is_some_and(|positions| positions.is_empty()) {
+                continue;
+            }
+            let Some(state) = state.or_else(|| load_state(&state_path)) else {
+                continue;
+            };
+            for (position, message) in state.conversation_history.iter().enumerate() {
+                if candidates.as_ref().is_some_and(|positions| !positions.contains(&position)) {
+                    continue;
+                }
+                if let Some(found) = matcher.find(&message.content) {
+                    hits.push(SearchHit {
+                        project: name.clone(),
+                        mode: message.mode,
+                        timestamp: message.timestamp,
+                        role: Some(message.role.clone()),
+                        snippet: snippet(&message.content, found.start(), found.end()),
+                    });
+                }
+            }
+        }
+
+        if index_changed {
+            // Saving only speeds up the next search, so a failure here shouldn't lose the results
+            let _ = index.save(&config.bindr_home);
+        }
+
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.timestamp));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+}
+
+/// Lowercased alphanumeric runs of `text`
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Project names with the path of their `state.json`
+fn project_states(projects_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(projects_dir)
+        .with_context(|| format!("Failed to read {}", projects_dir.display()))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("state.json"))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.parent()?.file_name()?.to_string_lossy().into_owned();
+            Some((name, path))
+        })
+        .collect())
+}
+
+fn load_state(path: &Path) -> Option<ProjectState> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn modified_at(path: &Path) -> Result<DateTime<Utc>> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(modified.into())
+}
+
+/// One line of `text` around the byte range `start..end`, trimmed to a little context
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let before: Vec<char> = text[..start].chars().rev().take(SNIPPET_CONTEXT + 1).collect();
+    let after: Vec<char> = text[end..].chars().take(SNIPPET_CONTEXT + 1).collect();
+
+    let mut result = String::new();
+    if before.len() > SNIPPET_CONTEXT {
+        result.push('…');
+    }
+    result.extend(before.iter().take(SNIPPET_CONTEXT).rev());
+    result.push_str(&text[start..end]);
+    result.extend(after.iter().take(SNIPPET_CONTEXT));
+    if after.len() > SNIPPET_CONTEXT {
+        result.push('…');
+    }
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
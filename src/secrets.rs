@@ -0,0 +1,116 @@
+//! Passphrase encryption of the API keys saved in `config.toml`
+
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::collections::HashMap;
+
+/// Environment variable holding the passphrase that encrypts and unlocks the saved API keys
+pub const PASSPHRASE_ENV: &str = "BINDR_PASSPHRASE";
+
+/// Marks the layout of an encrypted blob so it can change without breaking old configs
+const FORMAT_PREFIX: &str = "v1:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// The passphrase from `BINDR_PASSPHRASE`, or an error explaining how to provide it
+pub fn passphrase() -> Result<String> {
+    std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+        .ok_or_else(|| anyhow!("API keys are encrypted: set {} to your passphrase", PASSPHRASE_ENV))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive the encryption key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt an API key map into the text stored as `encrypted_api_keys`.
+///
+/// Each save draws a fresh salt and nonce, so the same keys never encrypt to the same text.
+pub fn encrypt_api_keys(keys: &HashMap<String, String>, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let plaintext = serde_json::to_vec(keys).context("Failed to serialize API keys")?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| anyhow!("Failed to encrypt API keys"))?;
+
+    let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", FORMAT_PREFIX, STANDARD.encode(blob)))
+}
+
+/// Recover the API key map from `encrypted_api_keys`; a wrong passphrase is an error, never an empty map
+pub fn decrypt_api_keys(encrypted: &str, passphrase: &str) -> Result<HashMap<String, String>> {
+    let encoded = encrypted
+        .trim()
+        .strip_prefix(FORMAT_PREFIX)
+        .context("encrypted_api_keys is in an unknown format")?;
+    let blob = STANDARD.decode(encoded).context("encrypted_api_keys is not valid base64")?;
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        bail!("encrypted_api_keys is truncated");
+    }
+    let (salt, rest) = blob.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Couldn't decrypt the API keys: {} is wrong or config.toml is damaged", PASSPHRASE_ENV))?;
+    serde_json::from_slice(&plaintext).context("Decrypted API keys are not valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> HashMap<String, String> {
+        HashMap::from([
+            ("openai".to_string(), "sk-openai-secret".to_string()),
+            ("anthropic".to_string(), "sk-ant-secret".to_string()),
+        ])
+    }
+
+    #[test]
+    fn keys_survive_an_encrypt_decrypt_round_trip() {
+        let encrypted = encrypt_api_keys(&keys(), "correct horse").unwrap();
+
+        assert!(encrypted.starts_with(FORMAT_PREFIX));
+        assert!(!encrypted.contains("sk-openai-secret"));
+        assert_eq!(decrypt_api_keys(&encrypted, "correct horse").unwrap(), keys());
+    }
+
+    #[test]
+    fn each_encryption_is_different() {
+        let first = encrypt_api_keys(&keys(), "correct horse").unwrap();
+        let second = encrypt_api_keys(&keys(), "correct horse").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn wrong_passphrase_is_an_error() {
+        let encrypted = encrypt_api_keys(&keys(), "correct horse").unwrap();
+        let error = decrypt_api_keys(&encrypted, "battery staple").unwrap_err();
+        assert!(error.to_string().starts_with("Couldn't decrypt the API keys"));
+    }
+
+    #[test]
+    fn damaged_text_is_an_error() {
+        assert!(decrypt_api_keys("v2:AAAA", "pw").unwrap_err().to_string().contains("unknown format"));
+        assert!(decrypt_api_keys("v1:not base64!", "pw").unwrap_err().to_string().contains("not valid base64"));
+        assert!(decrypt_api_keys("v1:AAAA", "pw").unwrap_err().to_string().contains("truncated"));
+    }
+}
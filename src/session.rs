@@ -2,11 +2,46 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::config::Config;
-use crate::events::{BindrMode, ProjectState, SessionInfo, ConversationEntry, ConversationRole};
+use crate::persist::write_atomic;
+use crate::search::SearchIndex;
+use crate::events::{BindrMode, ProjectState, SessionInfo, ConversationEntry, ConversationRole, FlaggedMessage};
+use crate::handoff::ModeHandoff;
+
+const LAST_SESSION_FILE: &str = "last_session.json";
+
+/// Tracks when `ui.auto_save_interval` has passed since the last save
+#[derive(Debug, Clone)]
+pub struct AutoSave {
+    /// `None` when the interval is 0 and auto-save is off
+    interval: Option<Duration>,
+    last_save: Instant,
+}
+
+impl AutoSave {
+    pub fn new(interval_secs: u64, now: Instant) -> Self {
+        Self {
+            interval: (interval_secs > 0).then(|| Duration::from_secs(interval_secs)),
+            last_save: now,
+        }
+    }
+
+    /// Whether a save is due at `now`; when it is, the next one is an interval away
+    pub fn due(&mut self, now: Instant) -> bool {
+        match self.interval {
+            Some(interval) if now.duration_since(self.last_save) >= interval => {
+                self.last_save = now;
+                true
+            }
+            _ => false,
+        }
+    }
+}
 
 /// Session manager for handling project state and persistence
 #[derive(Clone)]
@@ -23,12 +58,45 @@ pub struct ActiveSession {
     pub session_id: String,
     #[allow(dead_code)]
     pub project_state: ProjectState,
-    #[allow(dead_code)]
     pub is_dirty: bool,
     #[allow(dead_code)]
     pub last_save: DateTime<Utc>,
 }
 
+/// UI context remembered between launches so a fresh start resumes where the user left off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastSession {
+    pub mode: BindrMode,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LastSession {
+    pub fn new(mode: BindrMode) -> Self {
+        Self {
+            mode,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Load the last session from the Bindr home directory, if one was recorded
+    pub fn load(bindr_home: &Path) -> Option<Self> {
+        fs::read_to_string(bindr_home.join(LAST_SESSION_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Persist the last session to the Bindr home directory
+    pub fn save(&self, bindr_home: &Path) -> Result<()> {
+        fs::create_dir_all(bindr_home)
+            .context("Failed to create Bindr home directory")?;
+        let content = serde_json::to_string_pretty(self)
+            .context("Failed to serialize last session")?;
+        write_atomic(&bindr_home.join(LAST_SESSION_FILE), content)
+            .context("Failed to write last session")?;
+        Ok(())
+    }
+}
+
 impl SessionManager {
     pub fn new(config: Config) -> Self {
         Self {
@@ -67,7 +135,6 @@ impl SessionManager {
     }
     
     /// Create a new project and session
-    #[allow(dead_code)]
     pub fn create_project(&mut self, name: String, project_path: PathBuf) -> Result<String> {
         let session_id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -96,6 +163,9 @@ impl SessionManager {
             conversation_history: Vec::new(),
             conversation_count: 0,
             last_activity: now,
+            response_locale: None,
+            flagged_messages: Vec::new(),
+            handoffs: Vec::new(),
         };
         
         // Create session info
@@ -148,6 +218,50 @@ impl SessionManager {
         Ok(session_info.session_id)
     }
     
+    /// Whether a project has a session or a directory under the projects folder
+    pub fn has_project(&self, name: &str) -> bool {
+        self.sessions.values().any(|s| s.project_name == name) || self.config.project_dir(name).is_dir()
+    }
+
+    /// Delete a project: its session files and its directory under the projects folder
+    pub fn delete_project(&mut self, name: &str) -> Result<()> {
+        // The name becomes a path under the projects folder, so it must not climb out of it
+        let mut components = Path::new(name).components();
+        if !matches!((components.next(), components.next()), (Some(std::path::Component::Normal(_)), None)) {
+            anyhow::bail!("'{}' is not a valid project name", name);
+        }
+
+        let session_ids: Vec<String> = self.sessions.values()
+            .filter(|s| s.project_name == name)
+            .map(|s| s.session_id.clone())
+            .collect();
+        if !self.has_project(name) {
+            anyhow::bail!("Project '{}' not found", name);
+        }
+
+        let sessions_dir = self.config.bindr_home.join("sessions");
+        for session_id in session_ids {
+            let session_path = sessions_dir.join(format!("{}.json", session_id));
+            if session_path.exists() {
+                fs::remove_file(&session_path)
+                    .with_context(|| format!("Failed to delete {}", session_path.display()))?;
+            }
+            self.sessions.remove(&session_id);
+        }
+        let project_dir = self.config.project_dir(name);
+        if project_dir.exists() {
+            fs::remove_dir_all(&project_dir)
+                .with_context(|| format!("Failed to delete {}", project_dir.display()))?;
+        }
+        // A stale index entry is never searched, since search walks the projects on disk
+        let _ = SearchIndex::remove(&self.config.bindr_home, name);
+
+        if self.current_session.as_ref().is_some_and(|session| session.project_state.name == name) {
+            self.current_session = None;
+        }
+        Ok(())
+    }
+
     /// Get current session
     #[allow(dead_code)]
     pub fn current_session(&self) -> Option<&ActiveSession> {
@@ -161,7 +275,6 @@ impl SessionManager {
     }
     
     /// Save current session
-    #[allow(dead_code)]
     pub fn save_current_session(&mut self) -> Result<()> {
         // Extract data from current session to avoid borrow checker issues
         let (project_state, session_id, current_mode) = if let Some(session) = &self.current_session {
@@ -194,15 +307,26 @@ impl SessionManager {
         Ok(())
     }
     
+    /// Whether the open project has changes that haven't been saved
+    pub fn is_dirty(&self) -> bool {
+        self.current_session.as_ref().is_some_and(|session| session.is_dirty)
+    }
+    
     /// Add conversation entry to current session
-    #[allow(dead_code)]
-    pub fn add_conversation_entry(&mut self, role: ConversationRole, content: String, mode: BindrMode) -> Result<()> {
+    pub fn add_conversation_entry(
+        &mut self,
+        role: ConversationRole,
+        content: String,
+        mode: BindrMode,
+        model_id: Option<String>,
+    ) -> Result<()> {
         if let Some(session) = &mut self.current_session {
             let entry = ConversationEntry {
                 mode,
                 role,
                 content,
                 timestamp: Utc::now(),
+                model_id,
             };
             
             session.project_state.conversation_history.push(entry);
@@ -223,16 +347,102 @@ impl SessionManager {
         Ok(())
     }
     
+    /// Record the current project's response language and save it
+    pub fn set_response_locale(&mut self, locale: Option<String>) -> Result<()> {
+        if let Some(session) = &mut self.current_session {
+            session.project_state.response_locale = locale;
+            session.project_state.last_modified = Utc::now().to_rfc3339();
+            session.is_dirty = true;
+        }
+        self.save_current_session()
+    }
+    
+    /// Replace the current project's conversation and save it
+    pub fn set_conversation_history(&mut self, entries: Vec<ConversationEntry>, mode: BindrMode) -> Result<()> {
+        if let Some(session) = &mut self.current_session {
+            let now = Utc::now();
+            session.project_state.conversation_count = entries.len();
+            session.project_state.conversation_history = entries;
+            session.project_state.current_mode = mode;
+            session.project_state.last_modified = now.to_rfc3339();
+            session.project_state.last_activity = now;
+            session.is_dirty = true;
+        }
+        self.save_current_session()
+    }
+    
+    /// Write one mode's conversation to `conversations/<mode>.json` in the open project;
+    /// does nothing when no project is open
+    pub fn save_mode_history(&self, mode: BindrMode, entries: &[ConversationEntry]) -> Result<()> {
+        let Some(path) = self.mode_history_path(mode) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .context("Failed to create conversations directory")?;
+        }
+        let content = serde_json::to_string_pretty(entries)
+            .context("Failed to serialize conversation")?;
+        write_atomic(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+    
+    /// Read one mode's conversation from the open project, if it was saved
+    pub fn load_mode_history(&self, mode: BindrMode) -> Result<Option<Vec<ConversationEntry>>> {
+        let Some(path) = self.mode_history_path(mode).filter(|path| path.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+    
+    fn mode_history_path(&self, mode: BindrMode) -> Option<PathBuf> {
+        let session = self.current_session.as_ref()?;
+        Some(self.config
+            .project_dir(&session.project_state.name)
+            .join("conversations")
+            .join(format!("{}.json", mode.display_name().to_lowercase())))
+    }
+    
+    /// Record the current project's flagged messages and save them
+    pub fn set_flagged_messages(&mut self, flagged: Vec<FlaggedMessage>) -> Result<()> {
+        if let Some(session) = &mut self.current_session {
+            session.project_state.flagged_messages = flagged;
+            session.project_state.last_modified = Utc::now().to_rfc3339();
+            session.is_dirty = true;
+        }
+        self.save_current_session()
+    }
+    
+    /// Record the current project's mode handoffs and save them
+    pub fn set_handoffs(&mut self, handoffs: Vec<ModeHandoff>) -> Result<()> {
+        if let Some(session) = &mut self.current_session {
+            session.project_state.handoffs = handoffs;
+            session.project_state.last_modified = Utc::now().to_rfc3339();
+            session.is_dirty = true;
+        }
+        self.save_current_session()
+    }
+    
     /// Get all available sessions
     pub fn list_sessions(&self) -> Vec<&SessionInfo> {
         self.sessions.values().collect()
     }
+
+    /// All sessions, most recently active first
+    pub fn recent_sessions(&self) -> Vec<&SessionInfo> {
+        let mut sessions = self.list_sessions();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_activity));
+        sessions
+    }
     
     /// Load project state from disk
     fn load_project_state(&self, session_info: &SessionInfo) -> Result<ProjectState> {
-        let state_path = self.config.bindr_home
-            .join("projects")
-            .join(&session_info.project_name)
+        let state_path = self.config
+            .project_dir(&session_info.project_name)
             .join("state.json");
         
         if state_path.exists() {
@@ -244,7 +454,7 @@ impl SessionManager {
             // Create default state if not found
             Ok(ProjectState {
                 name: session_info.project_name.clone(),
-                path: self.config.projects_dir.join(&session_info.project_name),
+                path: self.config.project_dir(&session_info.project_name),
                 current_mode: session_info.current_mode,
                 created_at: session_info.created_at.to_rfc3339(),
                 last_modified: session_info.last_activity.to_rfc3339(),
@@ -252,14 +462,16 @@ impl SessionManager {
                 conversation_history: Vec::new(),
                 conversation_count: 0,
                 last_activity: session_info.last_activity,
+                response_locale: None,
+                flagged_messages: Vec::new(),
+                handoffs: Vec::new(),
             })
         }
     }
     
     /// Save project state to disk
-    #[allow(dead_code)]
     fn save_project_state(&self, project_state: &ProjectState) -> Result<()> {
-        let project_dir = self.config.projects_dir.join(&project_state.name);
+        let project_dir = self.config.project_dir(&project_state.name);
         fs::create_dir_all(&project_dir)
             .context("Failed to create project directory")?;
         
@@ -267,19 +479,20 @@ impl SessionManager {
         let state_path = project_dir.join("state.json");
         let content = serde_json::to_string_pretty(project_state)
             .context("Failed to serialize project state")?;
-        fs::write(&state_path, content)
+        write_atomic(&state_path, content)
             .context("Failed to write project state")?;
+        // Search re-indexes stale projects itself, so a failed update only costs it time
+        let _ = SearchIndex::update(&self.config.bindr_home, project_state, &state_path);
         
         // Save bindr.md
         let bindr_md_path = project_dir.join("bindr.md");
-        fs::write(&bindr_md_path, &project_state.bindr_md_content)
+        write_atomic(&bindr_md_path, &project_state.bindr_md_content)
             .context("Failed to write bindr.md")?;
         
         Ok(())
     }
     
     /// Save session info to disk
-    #[allow(dead_code)]
     fn save_session_info(&self, session_info: &SessionInfo) -> Result<()> {
         let sessions_dir = self.config.bindr_home.join("sessions");
         fs::create_dir_all(&sessions_dir)
@@ -288,9 +501,163 @@ impl SessionManager {
         let session_path = sessions_dir.join(format!("{}.json", session_info.session_id));
         let content = serde_json::to_string_pretty(session_info)
             .context("Failed to serialize session info")?;
-        fs::write(&session_path, content)
+        write_atomic(&session_path, content)
             .context("Failed to write session info")?;
         
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_manager(home: &Path) -> SessionManager {
+        let mut manager = SessionManager::new(Config::load_from(home.to_path_buf()).unwrap());
+        manager.load_sessions().unwrap();
+        manager
+    }
+
+    #[test]
+    fn project_saves_and_loads_under_a_custom_home() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+        let project_dir = manager.config.project_dir("demo");
+        assert!(project_dir.starts_with(home.path()));
+
+        manager.create_project("demo".to_string(), project_dir.clone()).unwrap();
+        manager.add_conversation_entry(ConversationRole::User, "hello".to_string(), BindrMode::Brainstorm, None).unwrap();
+        manager.save_current_session().unwrap();
+        assert!(project_dir.join("state.json").is_file());
+
+        let mut reopened = session_manager(home.path());
+        reopened.open_project("demo").unwrap();
+        let state = &reopened.current_session().unwrap().project_state;
+        assert_eq!(state.conversation_history.len(), 1);
+        assert_eq!(state.conversation_history[0].content, "hello");
+    }
+
+    #[test]
+    fn auto_save_is_due_once_per_interval() {
+        let start = Instant::now();
+        let mut auto_save = AutoSave::new(30, start);
+
+        let due: Vec<u64> = (1..=75)
+            .filter(|&secs| auto_save.due(start + Duration::from_secs(secs)))
+            .collect();
+        assert_eq!(due, [30, 60]);
+    }
+
+    #[test]
+    fn zero_interval_turns_auto_save_off() {
+        let start = Instant::now();
+        let mut auto_save = AutoSave::new(0, start);
+        assert!(!auto_save.due(start + Duration::from_secs(24 * 60 * 60)));
+    }
+
+    #[test]
+    fn dirty_session_is_auto_saved_once() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+        let project_dir = manager.config.project_dir("demo");
+        manager.create_project("demo".to_string(), project_dir.clone()).unwrap();
+        manager.add_conversation_entry(ConversationRole::User, "hello".to_string(), BindrMode::Brainstorm, None).unwrap();
+        assert!(manager.is_dirty());
+
+        let start = Instant::now();
+        let mut auto_save = AutoSave::new(30, start);
+        let mut saves = 0;
+        for secs in 1..=45 {
+            if auto_save.due(start + Duration::from_secs(secs)) && manager.is_dirty() {
+                manager.save_current_session().unwrap();
+                saves += 1;
+            }
+        }
+
+        assert_eq!(saves, 1);
+        assert!(!manager.is_dirty());
+        let state = fs::read_to_string(project_dir.join("state.json")).unwrap();
+        assert!(state.contains("\"hello\""));
+    }
+
+    /// Create and save `name`, returning the path of its session file
+    fn saved_project(manager: &mut SessionManager, name: &str) -> std::path::PathBuf {
+        let session_id = manager.create_project(name.to_string(), manager.config.project_dir(name)).unwrap();
+        manager.save_current_session().unwrap();
+        manager.config.bindr_home.join("sessions").join(format!("{}.json", session_id))
+    }
+
+    #[test]
+    fn delete_removes_the_session_file_and_project_dir() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+        let kept = saved_project(&mut manager, "kept");
+        let session_file = saved_project(&mut manager, "demo");
+        let project_dir = manager.config.project_dir("demo");
+        assert!(session_file.is_file() && project_dir.is_dir());
+
+        manager.delete_project("demo").unwrap();
+
+        assert!(!session_file.exists());
+        assert!(!project_dir.exists());
+        assert!(manager.current_session().is_none());
+        assert!(kept.is_file());
+        let reloaded = session_manager(home.path());
+        assert!(!reloaded.has_project("demo"));
+        assert!(reloaded.has_project("kept"));
+    }
+
+    #[test]
+    fn deleting_a_missing_project_is_an_error() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+
+        let error = manager.delete_project("ghost").unwrap_err();
+        assert_eq!(error.to_string(), "Project 'ghost' not found");
+        // Names that would reach outside the projects folder are refused outright
+        assert!(manager.delete_project("../elsewhere").unwrap_err().to_string().contains("not a valid project name"));
+    }
+
+    fn recent_names(manager: &SessionManager) -> Vec<&str> {
+        manager.recent_sessions().iter().map(|session| session.project_name.as_str()).collect()
+    }
+
+    #[test]
+    fn recent_sessions_start_with_the_latest_activity() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+        for name in ["first", "second", "third"] {
+            saved_project(&mut manager, name);
+        }
+        assert_eq!(recent_names(&manager), ["third", "second", "first"]);
+
+        // Saving an older project again makes it the most recent
+        manager.open_project("first").unwrap();
+        manager.save_current_session().unwrap();
+
+        let reloaded = session_manager(home.path());
+        assert_eq!(recent_names(&reloaded), ["first", "third", "second"]);
+    }
+
+    #[test]
+    fn recent_sessions_are_empty_without_projects() {
+        let home = tempfile::tempdir().unwrap();
+        assert!(session_manager(home.path()).recent_sessions().is_empty());
+    }
+
+    #[test]
+    fn reply_model_is_saved_with_the_project() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = session_manager(home.path());
+        saved_project(&mut manager, "demo");
+        manager
+            .add_conversation_entry(ConversationRole::Assistant, "Hello".to_string(), BindrMode::Brainstorm, Some("gpt-4o".to_string()))
+            .unwrap();
+        manager.save_current_session().unwrap();
+
+        let mut reopened = session_manager(home.path());
+        reopened.open_project("demo").unwrap();
+        let history = &reopened.current_session().unwrap().project_state.conversation_history;
+        assert_eq!(history[0].model_id.as_deref(), Some("gpt-4o"));
+    }
+}
@@ -0,0 +1,566 @@
+//! Server-Sent Events parsing for streamed provider replies, kept free of I/O
+//! so the HTTP client only has to feed it bytes
+
+use crate::llm::LlmEvent;
+use crate::pricing::TokenUsage;
+
+/// Splits an SSE byte stream into the `data` of each complete event
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    /// Unfinished line, kept as bytes so a UTF-8 sequence split across chunks joins up
+    line: Vec<u8>,
+    /// `data:` lines of the event being read
+    data: Vec<String>,
+    /// The last byte was `\r`, so a following `\n` ends the same line
+    after_cr: bool,
+}
+
+impl SseDecoder {
+    /// Feed the next chunk of the body; returns the events it completed
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            match byte {
+                b'\n' if self.after_cr => self.after_cr = false,
+                b'\n' | b'\r' => {
+                    self.after_cr = byte == b'\r';
+                    self.end_line(&mut events);
+                }
+                _ => {
+                    self.after_cr = false;
+                    self.line.push(byte);
+                }
+            }
+        }
+        events
+    }
+
+    /// The body ended; dispatch an event that wasn't followed by a blank line
+    pub fn finish(&mut self) -> Vec<String> {
+        let mut events = Vec::new();
+        if !self.line.is_empty() {
+            self.end_line(&mut events);
+        }
+        if !self.data.is_empty() {
+            events.push(std::mem::take(&mut self.data).join("\n"));
+        }
+        events
+    }
+
+    fn end_line(&mut self, events: &mut Vec<String>) {
+        let line = std::mem::take(&mut self.line);
+        if line.is_empty() {
+            // A blank line ends the event; multi-line data joins with newlines
+            if !self.data.is_empty() {
+                events.push(std::mem::take(&mut self.data).join("\n"));
+            }
+            return;
+        }
+
+        let line = String::from_utf8_lossy(&line);
+        if let Some(value) = line.strip_prefix("data:") {
+            self.data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+        }
+        // Comments and the event/id/retry fields carry nothing Bindr uses
+    }
+}
+
+/// JSON dialect carried in each event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SseFormat {
+    /// Chat completion chunks (OpenAI, xAI, OpenRouter, Mistral)
+    OpenAi,
+    /// Messages API events
+    Anthropic,
+    /// Gemini `streamGenerateContent?alt=sse` chunks
+    Google,
+}
+
+/// Turns a provider's SSE body into `LlmEvent`s
+#[derive(Debug)]
+pub struct SseParser {
+    format: SseFormat,
+    decoder: SseDecoder,
+    /// Reply text so far, reported with `ResponseComplete`
+    text: String,
+    /// `ResponseComplete` was sent; providers can signal the end more than once
+    responded: bool,
+    done: bool,
+}
+
+impl SseParser {
+    pub fn new(format: SseFormat) -> Self {
+        Self {
+            format,
+            decoder: SseDecoder::default(),
+            text: String::new(),
+            responded: false,
+            done: false,
+        }
+    }
+
+    /// Feed the next chunk of the body; returns the events it completed
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<LlmEvent> {
+        let payloads = self.decoder.push(bytes);
+        self.handle(payloads)
+    }
+
+    /// The body ended; flush what is left and close the reply if `[DONE]` never came
+    pub fn finish(&mut self) -> Vec<LlmEvent> {
+        let payloads = self.decoder.finish();
+        let mut events = self.handle(payloads);
+        if !self.done {
+            events.extend(self.complete());
+        }
+        events
+    }
+
+    /// Whether the stream has been closed by `[DONE]`, `message_stop`, or an error; anything after it is ignored
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn handle(&mut self, payloads: Vec<String>) -> Vec<LlmEvent> {
+        let mut events = Vec::new();
+        for data in payloads {
+            if self.done {
+                break;
+            }
+            if data == "[DONE]" {
+                events.extend(self.complete());
+                continue;
+            }
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&data) else {
+                continue;
+            };
+            match self.format {
+                SseFormat::OpenAi => self.openai_chunk(&value, &mut events),
+                SseFormat::Anthropic => self.anthropic_event(&value, &mut events),
+                SseFormat::Google => self.google_chunk(&value, &mut events),
+            }
+        }
+        events
+    }
+
+    fn openai_chunk(&mut self, chunk: &serde_json::Value, events: &mut Vec<LlmEvent>) {
+        // The usage chunk, when asked for, comes last and has no choices
+        if let Some(usage) = chunk.get("usage") {
+            push_usage(usage, ["prompt_tokens", "completion_tokens", "total_tokens"], events);
+        }
+        let Some(choice) = chunk.get("choices").and_then(|c| c.get(0)) else {
+            return;
+        };
+        // DeepSeek and vLLM call it `reasoning_content`; OpenRouter and others `reasoning`
+        if let Some(reasoning) = choice
+            .get("delta")
+            .and_then(|delta| delta.get("reasoning_content").or_else(|| delta.get("reasoning")))
+            .and_then(|r| r.as_str())
+            .filter(|r| !r.is_empty())
+        {
+            events.push(LlmEvent::ReasoningDelta(reasoning.to_string()));
+        }
+        if let Some(content) = choice
+            .get("delta")
+            .and_then(|delta| delta.get("content"))
+            .and_then(|c| c.as_str())
+        {
+            self.push_text(content, events);
+        }
+        if choice.get("finish_reason").and_then(|v| v.as_str()) == Some("stop") {
+            self.respond(events);
+        }
+    }
+
+    /// Messages API events are typed: text arrives in `content_block_delta` and `message_stop` ends the reply
+    fn anthropic_event(&mut self, event: &serde_json::Value, events: &mut Vec<LlmEvent>) {
+        match event.get("type").and_then(|t| t.as_str()) {
+            Some("content_block_start") => {
+                // Blocks usually start empty, but a non-empty start is still part of the reply
+                if let Some(text) = event
+                    .get("content_block")
+                    .and_then(|block| block.get("text"))
+                    .and_then(|t| t.as_str())
+                    .filter(|text| !text.is_empty())
+                {
+                    self.push_text(text, events);
+                }
+            }
+            Some("content_block_delta") => {
+                let Some(delta) = event.get("delta") else {
+                    return;
+                };
+                match delta.get("type").and_then(|t| t.as_str()) {
+                    Some("text_delta") => {
+                        if let Some(text) = delta.get("text").and_then(|t| t.as_str()) {
+                            self.push_text(text, events);
+                        }
+                    }
+                    Some("thinking_delta") => {
+                        if let Some(thinking) = delta.get("thinking").and_then(|t| t.as_str()) {
+                            events.push(LlmEvent::ReasoningDelta(thinking.to_string()));
+                        }
+                    }
+                    // Tool input and signature deltas carry nothing Bindr shows
+                    _ => {}
+                }
+            }
+            // Input tokens are counted up front, output tokens as the reply ends
+            Some("message_start") => {
+                if let Some(usage) = event.get("message").and_then(|message| message.get("usage")) {
+                    push_usage(usage, ["input_tokens", "", ""], events);
+                }
+            }
+            Some("message_delta") => {
+                if let Some(usage) = event.get("usage") {
+                    push_usage(usage, ["", "output_tokens", ""], events);
+                }
+            }
+            Some("message_stop") => events.extend(self.complete()),
+            Some("error") => {
+                let message = event
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("unknown error");
+                events.push(LlmEvent::Error(format!("Anthropic API error: {}", message)));
+                self.done = true;
+            }
+            // content_block_stop and ping need no handling
+            _ => {}
+        }
+    }
+
+    /// Each Gemini chunk carries only the text generated since the previous one; the body simply ends
+    fn google_chunk(&mut self, chunk: &serde_json::Value, events: &mut Vec<LlmEvent>) {
+        if let Some(message) = chunk
+            .get("error")
+            .and_then(|error| error.get("message"))
+            .and_then(|m| m.as_str())
+        {
+            events.push(LlmEvent::Error(format!("Google API error: {}", message)));
+            self.done = true;
+            return;
+        }
+        if let Some(usage) = chunk.get("usageMetadata") {
+            push_usage(usage, ["promptTokenCount", "candidatesTokenCount", "totalTokenCount"], events);
+        }
+        let parts = chunk
+            .get("candidates")
+            .and_then(|c| c.get(0))
+            .and_then(|candidate| candidate.get("content"))
+            .and_then(|content| content.get("parts"))
+            .and_then(|parts| parts.as_array());
+        for part in parts.into_iter().flatten() {
+            let Some(text) = part.get("text").and_then(|t| t.as_str()) else {
+                continue;
+            };
+            if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+                events.push(LlmEvent::ReasoningDelta(text.to_string()));
+            } else if !text.is_empty() {
+                self.push_text(text, events);
+            }
+        }
+    }
+
+    fn push_text(&mut self, text: &str, events: &mut Vec<LlmEvent>) {
+        self.text.push_str(text);
+        events.push(LlmEvent::TextDelta(text.to_string()));
+    }
+
+    /// Report the whole reply, at most once per stream
+    fn respond(&mut self, events: &mut Vec<LlmEvent>) {
+        if !self.responded && !self.text.is_empty() {
+            self.responded = true;
+            events.push(LlmEvent::ResponseComplete(self.text.clone()));
+        }
+    }
+
+    /// Report the whole reply if that hasn't happened yet, and close the stream
+    fn complete(&mut self) -> Vec<LlmEvent> {
+        self.done = true;
+        let mut events = Vec::new();
+        self.respond(&mut events);
+        events.push(LlmEvent::StreamComplete);
+        events
+    }
+}
+
+/// Emit the token counts in a provider's usage object, named by its `[input, output, total]` fields.
+///
+/// An empty name means the object never carries that count; an output count left out is taken from the total.
+fn push_usage(usage: &serde_json::Value, [input_field, output_field, total_field]: [&str; 3], events: &mut Vec<LlmEvent>) {
+    let count = |field: &str| usage.get(field).and_then(|n| n.as_u64()).unwrap_or(0) as usize;
+    let input_tokens = count(input_field);
+    let output_tokens = match count(output_field) {
+        0 => count(total_field).saturating_sub(input_tokens),
+        output => output,
+    };
+    let usage = TokenUsage { input_tokens, output_tokens };
+    if usage.total() > 0 {
+        events.push(LlmEvent::Usage(usage));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `chunks` one at a time, then end the body
+    fn parse(format: SseFormat, chunks: &[&[u8]]) -> Vec<LlmEvent> {
+        let mut parser = SseParser::new(format);
+        let mut events: Vec<LlmEvent> = chunks.iter().flat_map(|chunk| parser.push(chunk)).collect();
+        events.extend(parser.finish());
+        events
+    }
+
+    fn openai_delta(text: &str) -> String {
+        format!("data: {}\n\n", serde_json::json!({ "choices": [{ "delta": { "content": text } }] }))
+    }
+
+    fn text(events: &[LlmEvent]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                LlmEvent::TextDelta(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn frame_split_across_chunks_is_joined() {
+        let frame = openai_delta("Hello");
+        let (head, tail) = frame.split_at(12);
+        let mut decoder = SseDecoder::default();
+
+        assert!(decoder.push(head.as_bytes()).is_empty());
+        assert_eq!(decoder.push(tail.as_bytes()), [r#"{"choices":[{"delta":{"content":"Hello"}}]}"#]);
+    }
+
+    #[test]
+    fn character_split_across_chunks_is_decoded() {
+        let frame = openai_delta("café ☕");
+        let split = frame.find('☕').unwrap() + 1;
+        let (head, tail) = frame.as_bytes().split_at(split);
+
+        let events = parse(SseFormat::OpenAi, &[head, tail]);
+        assert_eq!(text(&events), "café ☕");
+    }
+
+    #[test]
+    fn multi_line_data_joins_with_newlines() {
+        let mut decoder = SseDecoder::default();
+        let events = decoder.push(b"event: message\ndata: first\r\ndata: second\r\n\r\n: keep-alive\n\n");
+        assert_eq!(events, ["first\nsecond"]);
+    }
+
+    #[test]
+    fn event_without_a_trailing_blank_line_is_flushed() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.push(b"data: last").is_empty());
+        assert_eq!(decoder.finish(), ["last"]);
+    }
+
+    #[test]
+    fn completion_is_reported_once() {
+        let body = format!(
+            "{}{}data: [DONE]\n\ndata: [DONE]\n\n",
+            openai_delta("Hi"),
+            openai_delta(" there"),
+        );
+        let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+
+        assert_eq!(events, [
+            LlmEvent::TextDelta("Hi".to_string()),
+            LlmEvent::TextDelta(" there".to_string()),
+            LlmEvent::ResponseComplete("Hi there".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+    }
+
+    #[test]
+    fn body_ending_without_done_still_completes() {
+        let body = openai_delta("cut short");
+        let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+
+        assert_eq!(events[1..], [LlmEvent::ResponseComplete("cut short".to_string()), LlmEvent::StreamComplete]);
+    }
+
+    #[test]
+    fn malformed_json_is_skipped() {
+        let body = format!("data: {{not json\n\n{}", openai_delta("ok"));
+        let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+        assert_eq!(text(&events), "ok");
+    }
+
+    const ANTHROPIC_TRANSCRIPT: &str = "event: message_start\n\
+data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_01\",\"type\":\"message\",\"role\":\"assistant\",\"content\":[],\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}\n\n\
+event: content_block_start\n\
+data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n\
+event: ping\n\
+data: {\"type\":\"ping\"}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\", world\"}}\n\n\
+event: content_block_stop\n\
+data: {\"type\":\"content_block_stop\",\"index\":0}\n\n\
+event: message_delta\n\
+data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":5}}\n\n\
+event: message_stop\n\
+data: {\"type\":\"message_stop\"}\n\n";
+
+    #[test]
+    fn anthropic_transcript_is_reconstructed() {
+        let events = parse(SseFormat::Anthropic, &[ANTHROPIC_TRANSCRIPT.as_bytes()]);
+
+        assert_eq!(text(&events), "Hello, world");
+        let tail: Vec<&LlmEvent> = events.iter().filter(|event| !matches!(event, LlmEvent::Usage(_))).skip(2).collect();
+        assert_eq!(tail, [&LlmEvent::ResponseComplete("Hello, world".to_string()), &LlmEvent::StreamComplete]);
+    }
+
+    #[test]
+    fn anthropic_error_event_ends_the_stream() {
+        let body = "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n";
+        let events = parse(SseFormat::Anthropic, &[body.as_bytes()]);
+        assert_eq!(events, [LlmEvent::Error("Anthropic API error: Overloaded".to_string())]);
+    }
+
+    fn gemini_chunk(text: &str) -> String {
+        let chunk = serde_json::json!({ "candidates": [{ "content": { "role": "model", "parts": [{ "text": text }] } }] });
+        format!("data: {}\r\n\r\n", chunk)
+    }
+
+    #[test]
+    fn gemini_chunks_stream_as_they_arrive() {
+        let mut parser = SseParser::new(SseFormat::Google);
+
+        assert_eq!(parser.push(gemini_chunk("The sky").as_bytes()), [LlmEvent::TextDelta("The sky".to_string())]);
+        assert_eq!(parser.push(gemini_chunk(" is blue.").as_bytes()), [LlmEvent::TextDelta(" is blue.".to_string())]);
+        // Gemini has no end marker; the reply completes when the body ends
+        assert_eq!(parser.finish(), [
+            LlmEvent::ResponseComplete("The sky is blue.".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+    }
+
+    #[test]
+    fn gemini_error_is_reported() {
+        let body = "data: {\"error\":{\"code\":400,\"message\":\"API key not valid\"}}\n\n";
+        let events = parse(SseFormat::Google, &[body.as_bytes()]);
+        assert_eq!(events, [LlmEvent::Error("Google API error: API key not valid".to_string())]);
+    }
+
+    #[test]
+    fn stop_and_done_complete_once() {
+        let stop = serde_json::json!({ "choices": [{ "delta": {}, "finish_reason": "stop" }] });
+        let body = format!("{}data: {}\n\ndata: [DONE]\n\n", openai_delta("Done."), stop);
+        let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+
+        assert_eq!(events, [
+            LlmEvent::TextDelta("Done.".to_string()),
+            LlmEvent::ResponseComplete("Done.".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+    }
+
+    fn reasoning(events: &[LlmEvent]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                LlmEvent::ReasoningDelta(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn openai_reasoning_fields_become_reasoning_deltas() {
+        for field in ["reasoning_content", "reasoning"] {
+            let thought = serde_json::json!({ "choices": [{ "delta": { field: "Check the sky." } }] });
+            let body = format!("data: {}\n\n{}data: [DONE]\n\n", thought, openai_delta("Blue."));
+            let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+
+            assert_eq!(reasoning(&events), "Check the sky.", "{}", field);
+            // Reasoning is kept out of the reply itself
+            assert_eq!(events.iter().find(|e| matches!(e, LlmEvent::ResponseComplete(_))), Some(&LlmEvent::ResponseComplete("Blue.".to_string())));
+        }
+    }
+
+    #[test]
+    fn anthropic_thinking_deltas_become_reasoning_deltas() {
+        let body = "event: content_block_start\n\
+data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"thinking\",\"thinking\":\"\"}}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Rayleigh \"}}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"scattering.\"}}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"abc\"}}\n\n\
+event: content_block_delta\n\
+data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"Blue.\"}}\n\n\
+event: message_stop\n\
+data: {\"type\":\"message_stop\"}\n\n";
+        let events = parse(SseFormat::Anthropic, &[body.as_bytes()]);
+
+        assert_eq!(reasoning(&events), "Rayleigh scattering.");
+        assert_eq!(text(&events), "Blue.");
+    }
+
+    #[test]
+    fn gemini_thought_parts_become_reasoning_deltas() {
+        let chunk = serde_json::json!({ "candidates": [{ "content": { "parts": [
+            { "text": "Think about light.", "thought": true },
+            { "text": "Blue." },
+        ] } }] });
+        let events = parse(SseFormat::Google, &[format!("data: {}\n\n", chunk).as_bytes()]);
+
+        assert_eq!(events, [
+            LlmEvent::ReasoningDelta("Think about light.".to_string()),
+            LlmEvent::TextDelta("Blue.".to_string()),
+            LlmEvent::ResponseComplete("Blue.".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+    }
+
+    #[test]
+    fn openai_usage_chunk_is_reported() {
+        let usage = serde_json::json!({ "choices": [], "usage": { "prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15 } });
+        let body = format!("{}data: {}\n\ndata: [DONE]\n\n", openai_delta("Blue."), usage);
+        let events = parse(SseFormat::OpenAi, &[body.as_bytes()]);
+
+        assert_eq!(events, [
+            LlmEvent::TextDelta("Blue.".to_string()),
+            LlmEvent::Usage(TokenUsage { input_tokens: 12, output_tokens: 3 }),
+            LlmEvent::ResponseComplete("Blue.".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+    }
+
+    #[test]
+    fn output_tokens_fall_back_to_the_total() {
+        let usage = serde_json::json!({ "choices": [], "usage": { "prompt_tokens": 12, "total_tokens": 20 } });
+        let events = parse(SseFormat::OpenAi, &[format!("data: {}\n\n", usage).as_bytes()]);
+        assert!(events.contains(&LlmEvent::Usage(TokenUsage { input_tokens: 12, output_tokens: 8 })));
+    }
+
+    #[test]
+    fn anthropic_usage_arrives_in_two_parts() {
+        let events = parse(SseFormat::Anthropic, &[ANTHROPIC_TRANSCRIPT.as_bytes()]);
+        let usage: Vec<_> = events.iter().filter(|event| matches!(event, LlmEvent::Usage(_))).collect();
+        assert_eq!(usage, [
+            &LlmEvent::Usage(TokenUsage { input_tokens: 12, output_tokens: 0 }),
+            &LlmEvent::Usage(TokenUsage { input_tokens: 0, output_tokens: 5 }),
+        ]);
+    }
+
+    #[test]
+    fn gemini_usage_metadata_is_reported() {
+        let chunk = serde_json::json!({
+            "candidates": [{ "content": { "parts": [{ "text": "Blue." }] } }],
+            "usageMetadata": { "promptTokenCount": 7, "candidatesTokenCount": 2, "totalTokenCount": 9 }
+        });
+        let events = parse(SseFormat::Google, &[format!("data: {}\n\n", chunk).as_bytes()]);
+        assert_eq!(events[0], LlmEvent::Usage(TokenUsage { input_tokens: 7, output_tokens: 2 }));
+    }
+}
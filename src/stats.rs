@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+use crate::events::{BindrMode, ConversationEntry, ConversationRole, ProjectState};
+use crate::pricing;
+
+/// Gaps between turns longer than this are treated as breaks, not time spent
+const IDLE_THRESHOLD_MINUTES: i64 = 30;
+
+/// Aggregated activity for a single mode
+#[derive(Debug, Clone, Default)]
+pub struct ModeStats {
+    pub user_turns: usize,
+    pub assistant_turns: usize,
+    pub estimated_tokens: usize,
+    pub active_time: Duration,
+    pub first_active: Option<DateTime<Utc>>,
+    pub last_active: Option<DateTime<Utc>>,
+}
+
+/// Activity summary for a project, derived from its persisted conversation history
+#[derive(Debug, Clone)]
+pub struct ProjectStats {
+    pub project_name: String,
+    pub per_mode: HashMap<BindrMode, ModeStats>,
+}
+
+impl ProjectStats {
+    pub fn from_state(state: &ProjectState) -> Self {
+        Self::from_history(&state.name, &state.conversation_history)
+    }
+
+    pub fn from_history(project_name: &str, history: &[ConversationEntry]) -> Self {
+        let mut per_mode: HashMap<BindrMode, ModeStats> = HashMap::new();
+
+        for (index, entry) in history.iter().enumerate() {
+            let stats = per_mode.entry(entry.mode).or_default();
+            match entry.role {
+                ConversationRole::User => stats.user_turns += 1,
+                ConversationRole::Assistant => stats.assistant_turns += 1,
+                ConversationRole::System => {}
+            }
+            stats.estimated_tokens += pricing::estimate_tokens(&entry.content);
+            stats.first_active = Some(stats.first_active.map_or(entry.timestamp, |t| t.min(entry.timestamp)));
+            stats.last_active = Some(stats.last_active.map_or(entry.timestamp, |t| t.max(entry.timestamp)));
+
+            // Attribute the time until the next turn to this entry's mode
+            if let Some(next) = history.get(index + 1) {
+                let gap = next.timestamp - entry.timestamp;
+                if gap > Duration::zero() && gap <= Duration::minutes(IDLE_THRESHOLD_MINUTES) {
+                    stats.active_time += gap;
+                }
+            }
+        }
+
+        Self {
+            project_name: project_name.to_string(),
+            per_mode,
+        }
+    }
+
+    pub fn total_turns(&self) -> usize {
+        self.per_mode
+            .values()
+            .map(|stats| stats.user_turns + stats.assistant_turns)
+            .sum()
+    }
+
+    pub fn total_tokens(&self) -> usize {
+        self.per_mode.values().map(|stats| stats.estimated_tokens).sum()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.per_mode
+            .values()
+            .fold(Duration::zero(), |total, stats| total + stats.active_time)
+    }
+
+    /// Plain-text report with one line per mode that has any activity
+    pub fn render(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Stats for {}", self.project_name),
+            format!(
+                "Total: {} turns • ~{} tokens • {} active",
+                self.total_turns(),
+                self.total_tokens(),
+                format_duration(self.total_time())
+            ),
+        ];
+
+        for mode in BindrMode::ALL {
+            let Some(stats) = self.per_mode.get(&mode) else {
+                continue;
+            };
+            let span = match (stats.first_active, stats.last_active) {
+                (Some(first), Some(last)) => format!(
+                    "{} → {}",
+                    first.format("%Y-%m-%d %H:%M"),
+                    last.format("%Y-%m-%d %H:%M")
+                ),
+                _ => "never".to_string(),
+            };
+            lines.push(format!(
+                "{:<10} {:>3} turns  ~{:>6} tokens  {:>7} active  ({})",
+                mode.display_name(),
+                stats.user_turns + stats.assistant_turns,
+                stats.estimated_tokens,
+                format_duration(stats.active_time),
+                span
+            ));
+        }
+
+        lines
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
@@ -0,0 +1,252 @@
+//! Tool calls the model writes into its replies as fenced ```tool blocks of JSON
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use super::diff::DEFAULT_CONTEXT_LINES;
+use super::{
+    ApplyPatchOptions, BindrTool, CommandOptions, DiffFileOptions, ListDirectoryOptions, ModelSelection,
+    ReadFileOptions, ToolInvocation, ToolKind, WriteFileOptions,
+};
+use crate::events::BindrMode;
+use crate::extract;
+
+/// Fence tag that marks a tool call
+pub const TOOL_FENCE: &str = "tool";
+
+/// Wire format of a call; `tool` names the variant and the remaining fields fill its options
+#[derive(Debug, Deserialize)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+enum ToolCall {
+    ReadFile {
+        path: PathBuf,
+        #[serde(default)]
+        max_bytes: Option<usize>,
+    },
+    WriteFile {
+        path: PathBuf,
+        contents: String,
+        #[serde(default = "default_true")]
+        create_if_missing: bool,
+    },
+    ListDirectory {
+        #[serde(default = "workspace_root")]
+        path: PathBuf,
+        #[serde(default)]
+        recursive: bool,
+        #[serde(default)]
+        include_hidden: bool,
+        #[serde(default)]
+        max_entries: Option<usize>,
+    },
+    DiffFile {
+        path: PathBuf,
+        proposed: String,
+        #[serde(default = "default_context_lines")]
+        context_lines: usize,
+    },
+    ApplyPatch {
+        path: PathBuf,
+        patch: String,
+    },
+    RunCommand {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default = "workspace_root")]
+        working_dir: PathBuf,
+        #[serde(default)]
+        allow_network: bool,
+    },
+    ListModels,
+    SelectModel {
+        provider_id: String,
+        model_id: String,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(".")
+}
+
+fn default_context_lines() -> usize {
+    DEFAULT_CONTEXT_LINES
+}
+
+impl From<ToolCall> for BindrTool {
+    fn from(call: ToolCall) -> Self {
+        match call {
+            ToolCall::ReadFile { path, max_bytes } => BindrTool::ReadFile(ReadFileOptions { path, max_bytes }),
+            ToolCall::WriteFile { path, contents, create_if_missing } => BindrTool::WriteFile(WriteFileOptions {
+                path,
+                contents,
+                create_if_missing,
+            }),
+            ToolCall::ListDirectory { path, recursive, include_hidden, max_entries } => {
+                BindrTool::ListDirectory(ListDirectoryOptions {
+                    path,
+                    recursive,
+                    include_hidden,
+                    max_entries,
+                })
+            }
+            ToolCall::DiffFile { path, proposed, context_lines } => BindrTool::DiffFile(DiffFileOptions {
+                path,
+                proposed,
+                context_lines,
+            }),
+            ToolCall::ApplyPatch { path, patch } => BindrTool::ApplyPatch(ApplyPatchOptions { path, patch }),
+            ToolCall::RunCommand { command, args, working_dir, allow_network } => {
+                BindrTool::RunCommand(CommandOptions {
+                    command,
+                    args,
+                    working_dir,
+                    allow_network,
+                })
+            }
+            ToolCall::ListModels => BindrTool::ListModels,
+            ToolCall::SelectModel { provider_id, model_id } => {
+                BindrTool::SelectModel(ModelSelection { provider_id, model_id })
+            }
+        }
+    }
+}
+
+/// The first well-formed tool call in an assistant reply; malformed blocks are ignored
+pub fn parse_tool_call(text: &str, mode: BindrMode) -> Option<ToolInvocation> {
+    extract::tagged_json_blocks(text, TOOL_FENCE)
+        .into_iter()
+        .find_map(|value| {
+            let description = value.get("description").and_then(|d| d.as_str()).map(str::to_string);
+            let tool: BindrTool = serde_json::from_value::<ToolCall>(value).ok()?.into();
+            let description = description.unwrap_or_else(|| match tool.target() {
+                target if target.is_empty() => tool.kind().label().to_string(),
+                target => format!("{}: {}", tool.kind().label(), target),
+            });
+            Some(ToolInvocation::new(tool, mode, description))
+        })
+}
+
+/// Example call for each tool the executor can run
+fn example(kind: &ToolKind) -> Option<&'static str> {
+    Some(match kind {
+        ToolKind::ReadFile => r#"{"tool": "read_file", "path": "src/main.rs"}"#,
+        ToolKind::ListDirectory => r#"{"tool": "list_directory", "path": ".", "recursive": true}"#,
+        ToolKind::DiffFile => r#"{"tool": "diff_file", "path": "src/lib.rs", "proposed": "<full new contents>"}"#,
+        ToolKind::WriteFile => r#"{"tool": "write_file", "path": "src/lib.rs", "contents": "<full file contents>"}"#,
+        ToolKind::ApplyPatch => r#"{"tool": "apply_patch", "path": "src/lib.rs", "patch": "<unified diff>"}"#,
+        ToolKind::RunCommand => r#"{"tool": "run_command", "command": "cargo", "args": ["test"]}"#,
+        ToolKind::ListModels | ToolKind::SelectModel => return None,
+    })
+}
+
+/// System prompt section explaining how to call the tools a mode allows
+pub fn instructions(allowed: &[ToolKind]) -> Option<String> {
+    let examples: Vec<&str> = allowed.iter().filter_map(example).collect();
+    if examples.is_empty() {
+        return None;
+    }
+    let mut text = String::from(
+        "Tools: to use one, end your reply with a fenced block tagged `tool` holding a single JSON call, \
+         then wait for the [tool result] message. Paths are relative to the workspace. One call per reply. Available calls:",
+    );
+    for example in examples {
+        text.push_str("\n```tool\n");
+        text.push_str(example);
+        text.push_str("\n```");
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(json: &str) -> Option<BindrTool> {
+        let reply = format!("Let me check.\n```tool\n{}\n```", json);
+        parse_tool_call(&reply, BindrMode::Execute).map(|invocation| invocation.tool)
+    }
+
+    #[test]
+    fn read_file_call() {
+        let tool = call(r#"{"tool": "read_file", "path": "src/main.rs", "max_bytes": 100}"#);
+        assert!(matches!(tool, Some(BindrTool::ReadFile(options))
+            if options.path.as_path() == std::path::Path::new("src/main.rs") && options.max_bytes == Some(100)));
+    }
+
+    #[test]
+    fn write_file_call_creates_by_default() {
+        let tool = call(r#"{"tool": "write_file", "path": "a.txt", "contents": "hi"}"#);
+        assert!(matches!(tool, Some(BindrTool::WriteFile(options))
+            if options.contents == "hi" && options.create_if_missing));
+    }
+
+    #[test]
+    fn list_directory_call_defaults_to_the_root() {
+        let tool = call(r#"{"tool": "list_directory"}"#);
+        assert!(matches!(tool, Some(BindrTool::ListDirectory(options))
+            if options.path.as_path() == std::path::Path::new(".") && !options.recursive && !options.include_hidden));
+    }
+
+    #[test]
+    fn diff_file_call_uses_the_default_context() {
+        let tool = call(r#"{"tool": "diff_file", "path": "a.txt", "proposed": "new"}"#);
+        assert!(matches!(tool, Some(BindrTool::DiffFile(options))
+            if options.proposed == "new" && options.context_lines == DEFAULT_CONTEXT_LINES));
+    }
+
+    #[test]
+    fn apply_patch_call() {
+        let tool = call(r#"{"tool": "apply_patch", "path": "a.txt", "patch": "@@ -1 +1 @@\n-a\n+b\n"}"#);
+        assert!(matches!(tool, Some(BindrTool::ApplyPatch(options)) if options.patch.starts_with("@@ -1 +1 @@")));
+    }
+
+    #[test]
+    fn run_command_call() {
+        let tool = call(r#"{"tool": "run_command", "command": "cargo", "args": ["test", "--quiet"]}"#);
+        assert!(matches!(tool, Some(BindrTool::RunCommand(options))
+            if options.command_line() == "cargo test --quiet" && !options.allow_network));
+    }
+
+    #[test]
+    fn model_calls() {
+        assert!(matches!(call(r#"{"tool": "list_models"}"#), Some(BindrTool::ListModels)));
+        let tool = call(r#"{"tool": "select_model", "provider_id": "openai", "model_id": "gpt-4o"}"#);
+        assert!(matches!(tool, Some(BindrTool::SelectModel(selection)) if selection.model_id == "gpt-4o"));
+    }
+
+    #[test]
+    fn description_defaults_to_the_target() {
+        let reply = "```tool\n{\"tool\": \"read_file\", \"path\": \"Cargo.toml\"}\n```";
+        let invocation = parse_tool_call(reply, BindrMode::Plan).unwrap();
+        assert_eq!(invocation.description, "read files: Cargo.toml");
+        assert_eq!(invocation.mode, BindrMode::Plan);
+    }
+
+    #[test]
+    fn malformed_blocks_are_ignored() {
+        assert!(call(r#"{"tool": "read_file"}"#).is_none());
+        assert!(call(r#"{"tool": "format_disk", "path": "/"}"#).is_none());
+        assert!(call(r#"{"tool": "read_file", "path": "#).is_none());
+        assert!(parse_tool_call(r#"```json
+{"tool": "read_file", "path": "a.txt"}
+```"#, BindrMode::Execute).is_none());
+
+        // A later well-formed call is still found
+        let reply = "```tool\n{\"tool\": \"nope\"}\n```\n```tool\n{\"tool\": \"list_models\"}\n```";
+        assert!(matches!(parse_tool_call(reply, BindrMode::Execute).map(|i| i.tool), Some(BindrTool::ListModels)));
+    }
+
+    #[test]
+    fn every_example_parses() {
+        for kind in ToolKind::ALL.iter() {
+            if let Some(example) = example(kind) {
+                assert_eq!(call(example).map(|tool| tool.kind()).as_ref(), Some(kind), "{}", example);
+            }
+        }
+    }
+}
@@ -7,8 +7,6 @@ use crate::events::BindrMode;
 pub struct ModeCapabilities {
     pub allowed_tools: Vec<ToolKind>,
     pub auto_approve: Vec<ToolKind>,
-    pub default_provider: Option<String>,
-    pub default_model: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -23,6 +21,72 @@ pub enum ToolKind {
     SelectModel,
 }
 
+impl ToolKind {
+    /// Every tool kind known to the dispatcher, in display order.
+    pub const ALL: [ToolKind; 8] = [
+        ToolKind::ReadFile,
+        ToolKind::ListDirectory,
+        ToolKind::DiffFile,
+        ToolKind::WriteFile,
+        ToolKind::ApplyPatch,
+        ToolKind::RunCommand,
+        ToolKind::ListModels,
+        ToolKind::SelectModel,
+    ];
+
+    /// Short verb phrase describing what the tool does.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolKind::ReadFile => "read files",
+            ToolKind::ListDirectory => "list directories",
+            ToolKind::DiffFile => "diff files",
+            ToolKind::WriteFile => "write files",
+            ToolKind::ApplyPatch => "apply patches",
+            ToolKind::RunCommand => "run commands",
+            ToolKind::ListModels => "list models",
+            ToolKind::SelectModel => "select models",
+        }
+    }
+}
+
+impl ModeCapabilities {
+    /// Human-readable summary of what a mode may do, derived from the enforced capabilities.
+    pub fn summary(&self, mode: BindrMode) -> String {
+        let labels = |kinds: Vec<&ToolKind>| {
+            kinds.iter().map(|kind| kind.label()).collect::<Vec<_>>().join(", ")
+        };
+
+        let (auto, gated): (Vec<&ToolKind>, Vec<&ToolKind>) = self
+            .allowed_tools
+            .iter()
+            .partition(|kind| self.auto_approve.contains(kind));
+        let denied: Vec<&ToolKind> = ToolKind::ALL
+            .iter()
+            .filter(|kind| !self.allowed_tools.contains(kind))
+            .collect();
+
+        let mut parts = Vec::new();
+        if !auto.is_empty() {
+            parts.push(format!("{} (auto)", labels(auto)));
+        }
+        if !gated.is_empty() {
+            parts.push(format!("{} (with approval)", labels(gated)));
+        }
+
+        let mut summary = format!("{} mode: ", mode.display_name());
+        if parts.is_empty() {
+            summary.push_str("cannot use any tools");
+        } else {
+            summary.push_str(&format!("can {}", parts.join("; ")));
+        }
+        if !denied.is_empty() && !parts.is_empty() {
+            summary.push_str(&format!("; cannot {}", labels(denied)));
+        }
+        summary.push('.');
+        summary
+    }
+}
+
 pub static MODE_CAPABILITIES: Lazy<HashMap<BindrMode, ModeCapabilities>> = Lazy::new(|| {
     use BindrMode::*;
 
@@ -38,8 +102,6 @@ pub static MODE_CAPABILITIES: Lazy<HashMap<BindrMode, ModeCapabilities>> = Lazy:
                 ToolKind::SelectModel,
             ],
             auto_approve: vec![ToolKind::ReadFile, ToolKind::ListDirectory, ToolKind::ListModels],
-            default_provider: None,
-            default_model: None,
         },
     );
 
@@ -53,8 +115,6 @@ pub static MODE_CAPABILITIES: Lazy<HashMap<BindrMode, ModeCapabilities>> = Lazy:
                 ToolKind::SelectModel,
             ],
             auto_approve: vec![ToolKind::ReadFile, ToolKind::ListDirectory, ToolKind::ListModels],
-            default_provider: None,
-            default_model: None,
         },
     );
 
@@ -71,8 +131,6 @@ pub static MODE_CAPABILITIES: Lazy<HashMap<BindrMode, ModeCapabilities>> = Lazy:
                 ToolKind::SelectModel,
             ],
             auto_approve: vec![ToolKind::ReadFile, ToolKind::ListDirectory, ToolKind::DiffFile, ToolKind::ListModels],
-            default_provider: None,
-            default_model: None,
         },
     );
 
@@ -88,8 +146,6 @@ pub static MODE_CAPABILITIES: Lazy<HashMap<BindrMode, ModeCapabilities>> = Lazy:
                 ToolKind::SelectModel,
             ],
             auto_approve: vec![ToolKind::ReadFile, ToolKind::ListDirectory, ToolKind::ListModels],
-            default_provider: None,
-            default_model: None,
         },
     );
 
@@ -0,0 +1,409 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::files::{self, FileContents};
+use super::{BindrTool, ToolExecutor, ToolInvocation};
+
+/// Unchanged lines shown around each hunk
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Files longer than this are summarized instead of diffed line by line
+const MAX_DIFF_LINES: usize = 5_000;
+
+/// Proposed change to one file, rendered for review
+#[derive(Debug, Clone)]
+pub struct ChangePreview {
+    pub path: PathBuf,
+    /// Unified diff, or a one-line note when no diff can be shown
+    pub diff: String,
+}
+
+/// Whether an invocation edits files and should wait in the pending queue
+pub fn is_file_change(invocation: &ToolInvocation) -> bool {
+    matches!(invocation.tool, BindrTool::WriteFile(_) | BindrTool::ApplyPatch(_))
+}
+
+/// Describe what a queued file change would do, without touching disk
+pub fn preview(invocation: &ToolInvocation, workspace: &Path) -> Option<ChangePreview> {
+    match &invocation.tool {
+        BindrTool::WriteFile(options) => {
+            let full_path = match files::resolve_for_write(workspace, &options.path) {
+                Ok(full_path) => full_path,
+                Err(e) => {
+                    return Some(ChangePreview {
+                        path: options.path.clone(),
+                        diff: format!("{:#}; it can't be written.", e),
+                    });
+                }
+            };
+            Some(ChangePreview {
+                path: options.path.clone(),
+                diff: diff_against_disk(&full_path, &options.path, &options.contents, DEFAULT_CONTEXT_LINES),
+            })
+        }
+        BindrTool::ApplyPatch(options) => Some(ChangePreview {
+            path: options.path.clone(),
+            diff: options.patch.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Carry out an approved file change, returning a short summary
+pub fn apply(invocation: &ToolInvocation, workspace: &Path) -> Result<String> {
+    match &invocation.tool {
+        BindrTool::WriteFile(_) | BindrTool::ApplyPatch(_) => Ok(ToolExecutor::new(workspace).execute(invocation)?.render()),
+        _ => bail!("{} is not a file change", invocation.tool.kind().label()),
+    }
+}
+
+/// Unified diff from the file at `full_path` (empty if missing) to `proposed`, labelled with `path`
+pub fn diff_against_disk(full_path: &Path, path: &Path, proposed: &str, context: usize) -> String {
+    match files::read_text(full_path, None) {
+        Ok(FileContents::Text { truncated: true, .. }) => {
+            format!("{} is too large to diff; it would be replaced.", path.display())
+        }
+        Ok(FileContents::Text { content, .. }) => unified_diff(path, &content, proposed, context),
+        Ok(FileContents::Binary { .. }) => {
+            format!("{} is a binary file; it would be replaced with text.", path.display())
+        }
+        Err(_) if !full_path.exists() => unified_diff(path, "", proposed, context),
+        Err(e) => format!("{:#}", e),
+    }
+}
+
+/// Line-based unified diff between two versions of a file
+pub fn unified_diff(path: &Path, old: &str, new: &str, context: usize) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let name = path.display();
+
+    if old_lines == new_lines {
+        return format!("{} is unchanged", name);
+    }
+    if old_lines.len().max(new_lines.len()) > MAX_DIFF_LINES {
+        return format!(
+            "{} is too large to diff ({} lines → {} lines)",
+            name,
+            old_lines.len(),
+            new_lines.len()
+        );
+    }
+
+    let ops = diff_ops(&old_lines, &new_lines);
+    let mut output = if old_lines.is_empty() {
+        format!("--- /dev/null\n+++ b/{}\n", name)
+    } else {
+        format!("--- a/{}\n+++ b/{}\n", name, name)
+    };
+
+    for hunk in hunks(&ops, context) {
+        let (old_start, new_start) = line_numbers(&ops, hunk.start);
+        let old_count = ops[hunk.clone()].iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+        let new_count = ops[hunk.clone()].iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk_start(old_start, old_count),
+            old_count,
+            hunk_start(new_start, new_count),
+            new_count
+        ));
+        for op in &ops[hunk] {
+            let (prefix, line) = match op {
+                Op::Equal(i) => (' ', old_lines[*i]),
+                Op::Delete(i) => ('-', old_lines[*i]),
+                Op::Insert(j) => ('+', new_lines[*j]),
+            };
+            output.push(prefix);
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Edit script from the longest common subsequence of lines
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push(Op::Equal(i));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// Ranges of ops to print: each change plus `context` equal lines either side, merged when they touch
+fn hunks(ops: &[Op], context: usize) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (index, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Equal(_)) {
+            continue;
+        }
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+/// 1-based old/new line numbers at an op index
+fn line_numbers(ops: &[Op], index: usize) -> (usize, usize) {
+    let old = ops[..index].iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+    let new = ops[..index].iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+    (old + 1, new + 1)
+}
+
+/// Unified diff convention: an empty side starts at the line before the hunk
+fn hunk_start(start: usize, count: usize) -> usize {
+    if count == 0 { start - 1 } else { start }
+}
+
+/// Result of applying a unified diff to a file's text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchedText {
+    pub content: String,
+    pub hunks: usize,
+}
+
+#[derive(Debug, Default)]
+struct Hunk {
+    /// 1-based line the hunk claims to start at in the old file
+    old_start: usize,
+    /// Body lines with their ' ', '-', or '+' prefix split off
+    lines: Vec<(char, String)>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines.iter().filter(|(kind, _)| *kind != '+').map(|(_, line)| line.as_str()).collect()
+    }
+
+    fn new_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter(|(kind, _)| *kind != '-').map(|(_, line)| line.as_str())
+    }
+}
+
+/// Apply a unified diff to `original`, all or nothing.
+///
+/// Each hunk must match the text exactly, though it may sit at a different line than its
+/// header says (the nearest match wins), so edits elsewhere in the file don't block it.
+pub fn apply_patch(original: &str, patch: &str) -> Result<PatchedText> {
+    let (hunks, old_missing_newline, new_missing_newline) = parse_patch(patch)?;
+    let old_lines: Vec<&str> = original.lines().collect();
+    let mut lines: Vec<&str> = Vec::with_capacity(old_lines.len());
+    let mut cursor = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        // A hunk that only adds lines gives the line before the insertion point
+        let expected = if old.is_empty() { hunk.old_start } else { hunk.old_start.saturating_sub(1) };
+        let Some(position) = find_block(&old_lines, &old, cursor, expected) else {
+            bail!(
+                "Hunk {} of {} (at line {}) doesn't match the current file; no changes were made",
+                index + 1,
+                hunks.len(),
+                hunk.old_start
+            );
+        };
+        lines.extend(&old_lines[cursor..position]);
+        lines.extend(hunk.new_lines());
+        cursor = position + old.len();
+    }
+    lines.extend(&old_lines[cursor..]);
+
+    let line_ending = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    let trailing_newline = if new_missing_newline {
+        false
+    } else {
+        old_missing_newline || original.is_empty() || original.ends_with('\n')
+    };
+    let mut content = lines.join(line_ending);
+    if trailing_newline && !lines.is_empty() {
+        content.push_str(line_ending);
+    }
+
+    Ok(PatchedText {
+        content,
+        hunks: hunks.len(),
+    })
+}
+
+/// Hunks of a unified diff, plus whether the old and new sides end without a newline
+fn parse_patch(patch: &str) -> Result<(Vec<Hunk>, bool, bool)> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut old_missing_newline = false;
+    let mut new_missing_newline = false;
+
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("@@ ") {
+            let old_range = header
+                .split_whitespace()
+                .next()
+                .and_then(|range| range.strip_prefix('-'))
+                .with_context(|| format!("Malformed hunk header: {}", line))?;
+            let old_start = old_range
+                .split(',')
+                .next()
+                .and_then(|start| start.parse().ok())
+                .with_context(|| format!("Malformed hunk header: {}", line))?;
+            hunks.push(Hunk {
+                old_start,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            // File headers and anything else before the first hunk
+            continue;
+        };
+        if line.starts_with("--- ") || line.starts_with("+++ ") || line.starts_with("diff ") {
+            bail!("Patches must change a single file");
+        }
+        if line.starts_with('\\') {
+            // "\ No newline at end of file" applies to the line just above it
+            match hunk.lines.last() {
+                Some(('-', _)) => old_missing_newline = true,
+                Some(('+', _)) => new_missing_newline = true,
+                _ => {
+                    old_missing_newline = true;
+                    new_missing_newline = true;
+                }
+            }
+            continue;
+        }
+        let mut chars = line.chars();
+        match chars.next() {
+            Some(kind @ (' ' | '-' | '+')) => hunk.lines.push((kind, chars.as_str().to_string())),
+            // Some editors strip the space from blank context lines
+            None => hunk.lines.push((' ', String::new())),
+            Some(_) => bail!("Unexpected line in patch: {}", line),
+        }
+    }
+
+    if hunks.is_empty() {
+        bail!("The patch has no hunks");
+    }
+    Ok((hunks, old_missing_newline, new_missing_newline))
+}
+
+/// Start of the occurrence of `block` at or after `from` that is closest to `expected`
+fn find_block(lines: &[&str], block: &[&str], from: usize, expected: usize) -> Option<usize> {
+    if block.is_empty() {
+        return Some(expected.clamp(from, lines.len()));
+    }
+    (from..=lines.len().checked_sub(block.len())?)
+        .filter(|&start| lines[start..start + block.len()] == *block)
+        .min_by_key(|&start| start.abs_diff(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n";
+
+    #[test]
+    fn additions_are_marked() {
+        let diff = unified_diff(Path::new("list.txt"), "one\ntwo\n", "one\ntwo\nthree\n", 3);
+        assert_eq!(diff, "--- a/list.txt\n+++ b/list.txt\n@@ -1,2 +1,3 @@\n one\n two\n+three\n");
+    }
+
+    #[test]
+    fn new_file_diffs_from_dev_null() {
+        let diff = unified_diff(Path::new("list.txt"), "", "one\n", 3);
+        assert_eq!(diff, "--- /dev/null\n+++ b/list.txt\n@@ -0,0 +1,1 @@\n+one\n");
+    }
+
+    #[test]
+    fn deletions_are_marked() {
+        let diff = unified_diff(Path::new("list.txt"), "one\ntwo\nthree\n", "one\nthree\n", 3);
+        assert_eq!(diff, "--- a/list.txt\n+++ b/list.txt\n@@ -1,3 +1,2 @@\n one\n-two\n three\n");
+    }
+
+    #[test]
+    fn context_is_trimmed_to_the_requested_lines() {
+        let new = OLD.replace("five", "FIVE");
+        let diff = unified_diff(Path::new("list.txt"), OLD, &new, 1);
+        assert_eq!(diff, "--- a/list.txt\n+++ b/list.txt\n@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n");
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let new = OLD.replace("two", "TWO").replace("seven", "SEVEN");
+        let diff = unified_diff(Path::new("list.txt"), OLD, &new, 1);
+        assert_eq!(diff.matches("@@ -").count(), 2, "{}", diff);
+
+        let diff = unified_diff(Path::new("list.txt"), OLD, &new, 3);
+        assert_eq!(diff.matches("@@ -").count(), 1, "{}", diff);
+    }
+
+    #[test]
+    fn identical_text_is_unchanged() {
+        assert_eq!(unified_diff(Path::new("list.txt"), OLD, OLD, 3), "list.txt is unchanged");
+    }
+
+    #[test]
+    fn patch_round_trips_a_generated_diff() {
+        let new = OLD.replace("two", "TWO").replace("seven", "SEVEN");
+        let patch = unified_diff(Path::new("list.txt"), OLD, &new, 1);
+
+        assert_eq!(apply_patch(OLD, &patch).unwrap(), PatchedText { content: new, hunks: 2 });
+    }
+
+    #[test]
+    fn patch_applies_after_lines_shift() {
+        let patch = "@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n";
+        let shifted = format!("zero\n{}", OLD);
+
+        let patched = apply_patch(&shifted, patch).unwrap();
+        assert_eq!(patched.content, shifted.replace("five", "FIVE"));
+    }
+
+    #[test]
+    fn patch_against_drifted_content_is_rejected() {
+        let patch = "@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n";
+        let drifted = OLD.replace("five", "5");
+
+        let error = apply_patch(&drifted, patch).unwrap_err();
+        assert_eq!(error.to_string(), "Hunk 1 of 1 (at line 4) doesn't match the current file; no changes were made");
+    }
+
+    #[test]
+    fn malformed_patches_are_rejected() {
+        assert!(apply_patch(OLD, "just some text\n").is_err());
+        assert!(apply_patch(OLD, "@@ -1,1 +1,1 @@\n-one\n+ONE\n--- a/other\n").is_err());
+    }
+}
@@ -2,9 +2,66 @@ use anyhow::{anyhow, Result};
 
 use crate::events::BindrMode;
 
-use super::capabilities::{ModeCapabilities, MODE_CAPABILITIES};
+use super::capabilities::{ModeCapabilities, ToolKind, MODE_CAPABILITIES};
 use super::{ToolInvocation, ToolRequestOutcome};
 
+/// Error raised when the active mode's capabilities don't include a requested tool
+#[derive(Debug)]
+pub struct ToolNotPermitted {
+    pub kind: ToolKind,
+    pub mode: BindrMode,
+}
+
+impl ToolNotPermitted {
+    /// Modes whose capabilities do include this tool
+    pub fn permitted_in(&self) -> Vec<BindrMode> {
+        BindrMode::ALL
+            .into_iter()
+            .filter(|mode| {
+                MODE_CAPABILITIES
+                    .get(mode)
+                    .is_some_and(|capabilities| capabilities.allowed_tools.contains(&self.kind))
+            })
+            .collect()
+    }
+
+    /// Friendly explanation for the user, suggesting a mode that can do it
+    pub fn user_note(&self) -> String {
+        let mut note = format!(
+            "The assistant tried to {}, but {} mode can't do that.",
+            self.kind.label(),
+            self.mode.display_name()
+        );
+        if let Some(mode) = self.permitted_in().first() {
+            let name = mode.display_name();
+            note.push_str(&format!(
+                " Switch with /mode {} to let it {} in {} mode.",
+                name[..1].to_lowercase(),
+                self.kind.label(),
+                name
+            ));
+        }
+        note
+    }
+
+    /// Tool result fed back to the model so it can carry on without the tool
+    pub fn tool_result(&self) -> String {
+        format!(
+            "[tool result: {}] Tool unavailable in {} mode. Continue without it, or suggest the user switch modes if it is essential.",
+            self.kind.label(),
+            self.mode.display_name()
+        )
+    }
+}
+
+impl std::fmt::Display for ToolNotPermitted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tool {:?} is not permitted in {:?} mode", self.kind, self.mode)
+    }
+}
+
+impl std::error::Error for ToolNotPermitted {}
+
 /// Validates and routes tool invocations according to the active mode's capabilities.
 pub struct ToolDispatcher;
 
@@ -14,11 +71,7 @@ impl ToolDispatcher {
         let kind = invocation.tool.kind();
 
         if !capabilities.allowed_tools.contains(&kind) {
-            return Err(anyhow!(
-                "Tool {:?} is not permitted in {:?} mode",
-                kind,
-                mode
-            ));
+            return Err(ToolNotPermitted { kind, mode }.into());
         }
 
         let requires_approval = !capabilities.auto_approve.contains(&kind);
@@ -29,9 +82,48 @@ impl ToolDispatcher {
         })
     }
 
+    /// Describe the tools available in a mode and which of them need approval.
+    pub fn describe(mode: BindrMode) -> Result<String> {
+        Ok(Self::capabilities_for(mode)?.summary(mode))
+    }
+
     pub fn capabilities_for(mode: BindrMode) -> Result<&'static ModeCapabilities> {
         MODE_CAPABILITIES
             .get(&mode)
             .ok_or_else(|| anyhow!("No capabilities registered for mode {:?}", mode))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{BindrTool, WriteFileOptions};
+
+    fn write_file() -> ToolInvocation {
+        let options = WriteFileOptions { path: "notes.md".into(), contents: "hello".to_string(), create_if_missing: true };
+        ToolInvocation::new(BindrTool::WriteFile(options), BindrMode::Brainstorm, "test")
+    }
+
+    #[test]
+    fn write_file_is_rejected_in_brainstorm() {
+        let error = ToolDispatcher::review(BindrMode::Brainstorm, write_file()).unwrap_err();
+        let denied = error.downcast::<ToolNotPermitted>().unwrap();
+
+        assert_eq!((&denied.kind, denied.mode), (&ToolKind::WriteFile, BindrMode::Brainstorm));
+        assert!(denied.user_note().contains("Brainstorm mode can't do that. Switch with /mode "));
+        assert!(denied.tool_result().contains("Tool unavailable in Brainstorm mode"));
+    }
+
+    #[test]
+    fn write_file_is_allowed_in_document_with_approval() {
+        let outcome = ToolDispatcher::review(BindrMode::Document, write_file()).unwrap();
+        assert!(outcome.requires_approval);
+    }
+
+    #[test]
+    fn plan_cannot_write_or_run() {
+        assert!(ToolDispatcher::review(BindrMode::Plan, write_file()).is_err());
+        let permitted = ToolNotPermitted { kind: ToolKind::RunCommand, mode: BindrMode::Plan }.permitted_in();
+        assert!(!permitted.contains(&BindrMode::Brainstorm) && !permitted.contains(&BindrMode::Plan));
+    }
+}
@@ -0,0 +1,650 @@
+use anyhow::{bail, Context, Result};
+use futures::future::BoxFuture;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use super::diff;
+use super::files::{self, FileContents};
+use crate::persist::write_atomic;
+use super::{BindrTool, CommandOptions, ListDirectoryOptions, ToolInvocation};
+
+/// Cap on directory entries when the invocation doesn't set `max_entries`
+pub const MAX_LIST_ENTRIES: usize = 1_000;
+
+/// Commands still running after this long are killed
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Bytes of stdout and of stderr kept from a command
+const MAX_COMMAND_OUTPUT: usize = 64 * 1024;
+
+/// Programs whose whole purpose is talking to the network
+const NETWORK_PROGRAMS: &[&str] = &[
+    "curl", "wget", "ssh", "scp", "sftp", "rsync", "nc", "ncat", "netcat", "telnet", "ftp", "ping", "dig", "nslookup",
+];
+
+/// Global options of those tools that take a separate value
+const OPTIONS_WITH_VALUES: &[&str] = &["-C", "-c", "--git-dir", "--work-tree", "--manifest-path", "--prefix", "--cwd"];
+
+/// Subcommands of common tools that fetch or publish over the network
+const NETWORK_SUBCOMMANDS: &[(&str, &[&str])] = &[
+    ("git", &["clone", "fetch", "pull", "push", "ls-remote", "submodule"]),
+    ("cargo", &["install", "publish", "search", "update", "fetch"]),
+    ("npm", &["install", "i", "ci", "add", "publish", "update"]),
+    ("pnpm", &["install", "i", "add", "publish", "update"]),
+    ("yarn", &["install", "add", "publish", "upgrade"]),
+    ("pip", &["install", "download"]),
+    ("pip3", &["install", "download"]),
+];
+
+/// What a read-only tool produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolOutput {
+    File {
+        /// Path relative to the workspace root
+        path: PathBuf,
+        contents: FileContents,
+    },
+    Directory {
+        /// Path relative to the workspace root
+        path: PathBuf,
+        entries: Vec<DirEntry>,
+        /// Whether the listing stopped at the entry limit
+        truncated: bool,
+    },
+    Diff {
+        /// Path relative to the workspace root
+        path: PathBuf,
+        /// Unified diff, or a one-line note when no diff can be shown
+        diff: String,
+    },
+    Patched {
+        /// Path relative to the workspace root
+        path: PathBuf,
+        hunks: usize,
+        /// Whether the patch created the file
+        created: bool,
+    },
+    Command {
+        /// Command line as it was run
+        command: String,
+        /// `None` when the process was ended by a signal
+        exit_code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+    Written {
+        /// Path relative to the workspace root
+        path: PathBuf,
+        bytes: usize,
+        /// Whether the file didn't exist before
+        created: bool,
+    },
+}
+
+/// One entry of a directory listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    /// Path relative to the listed directory
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl ToolOutput {
+    /// One line for the conversation history
+    pub fn summary(&self) -> String {
+        match self {
+            ToolOutput::File { path, contents: FileContents::Text { content, .. } } => {
+                format!("Read {} ({} bytes)", path.display(), content.len())
+            }
+            ToolOutput::File { path, contents: FileContents::Binary { .. } } => {
+                format!("Read {} (binary)", path.display())
+            }
+            ToolOutput::Directory { path, entries, .. } => {
+                format!("Listed {} ({} entries)", path.display(), entries.len())
+            }
+            ToolOutput::Diff { path, .. } => format!("Diffed {}", path.display()),
+            ToolOutput::Command { command, exit_code, .. } => match exit_code {
+                Some(code) => format!("Ran {} (exit code {})", command, code),
+                None => format!("Ran {} (terminated by a signal)", command),
+            },
+            ToolOutput::Patched { .. } | ToolOutput::Written { .. } => self.render(),
+        }
+    }
+
+    /// Tool-result text for the model
+    pub fn render(&self) -> String {
+        match self {
+            ToolOutput::File { path, contents } => contents.render(path),
+            ToolOutput::Directory { path, entries, truncated } => {
+                let mut lines: Vec<String> = entries
+                    .iter()
+                    .map(|entry| {
+                        let marker = if entry.is_dir { "/" } else { "" };
+                        format!("{}{}", entry.path.display(), marker)
+                    })
+                    .collect();
+                if entries.is_empty() {
+                    lines.push(format!("{} is empty", path.display()));
+                }
+                if *truncated {
+                    lines.push(format!("[truncated: showing the first {} entries of {}]", entries.len(), path.display()));
+                }
+                lines.join("\n")
+            }
+            ToolOutput::Diff { diff, .. } => diff.clone(),
+            ToolOutput::Patched { path, hunks, created } => format!(
+                "{} {} ({} hunk{})",
+                if *created { "Created" } else { "Patched" },
+                path.display(),
+                hunks,
+                if *hunks == 1 { "" } else { "s" }
+            ),
+            ToolOutput::Command { command, exit_code, stdout, stderr } => {
+                let status = match exit_code {
+                    Some(code) => format!("exit code {}", code),
+                    None => "terminated by a signal".to_string(),
+                };
+                let mut text = format!("$ {}\n[{}]", command, status);
+                for (label, output) in [("stdout", stdout), ("stderr", stderr)] {
+                    if !output.is_empty() {
+                        text.push_str(&format!("\n{}:\n{}", label, output.trim_end()));
+                    }
+                }
+                text
+            }
+            ToolOutput::Written { path, bytes, created: true } => {
+                format!("Created {} ({} bytes)", path.display(), bytes)
+            }
+            ToolOutput::Written { path, bytes, created: false } => {
+                format!("Wrote {} ({} bytes)", path.display(), bytes)
+            }
+        }
+    }
+}
+
+/// Runs approved tools against the workspace, refusing paths that resolve outside it
+#[derive(Debug, Clone)]
+pub struct ToolExecutor {
+    workspace: PathBuf,
+}
+
+impl ToolExecutor {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+
+    pub fn execute(&self, invocation: &ToolInvocation) -> Result<ToolOutput> {
+        match &invocation.tool {
+            BindrTool::ReadFile(options) => {
+                let full_path = files::resolve_in_workspace(&self.workspace, &options.path)?;
+                if full_path.is_dir() {
+                    bail!("{} is a directory", options.path.display());
+                }
+                let contents = files::read_text(&full_path, options.max_bytes)?;
+                Ok(ToolOutput::File {
+                    path: self.relative(&full_path),
+                    contents,
+                })
+            }
+            BindrTool::ListDirectory(options) => {
+                let full_path = files::resolve_in_workspace(&self.workspace, &options.path)?;
+                if !full_path.is_dir() {
+                    bail!("{} is not a directory", options.path.display());
+                }
+                let limit = options.max_entries.unwrap_or(MAX_LIST_ENTRIES).min(MAX_LIST_ENTRIES);
+                let mut entries = Vec::new();
+                let truncated = list_dir(&full_path, Path::new(""), options, limit, &mut entries)?;
+                Ok(ToolOutput::Directory {
+                    path: self.relative(&full_path),
+                    entries,
+                    truncated,
+                })
+            }
+            BindrTool::DiffFile(options) => {
+                let full_path = files::resolve_for_write(&self.workspace, &options.path)?;
+                if full_path.is_dir() {
+                    bail!("{} is a directory", options.path.display());
+                }
+                let path = self.relative(&full_path);
+                let diff = diff::diff_against_disk(&full_path, &path, &options.proposed, options.context_lines);
+                Ok(ToolOutput::Diff { path, diff })
+            }
+            BindrTool::ApplyPatch(options) => {
+                let full_path = files::resolve_for_write(&self.workspace, &options.path)?;
+                let created = !full_path.exists();
+                let original = if created {
+                    String::new()
+                } else {
+                    match files::read_text(&full_path, None)? {
+                        FileContents::Text { truncated: true, .. } => {
+                            bail!("{} is too large to patch", options.path.display())
+                        }
+                        FileContents::Text { content, .. } => content,
+                        FileContents::Binary { .. } => bail!("{} is a binary file", options.path.display()),
+                    }
+                };
+                let patched = diff::apply_patch(&original, &options.patch)
+                    .with_context(|| format!("Failed to patch {}", options.path.display()))?;
+                if let Some(parent) = full_path.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                // The whole result is written in one rename, so a failed patch never leaves a partial file
+                write_atomic(&full_path, &patched.content)?;
+                Ok(ToolOutput::Patched {
+                    path: self.relative(&full_path),
+                    hunks: patched.hunks,
+                    created,
+                })
+            }
+            BindrTool::WriteFile(options) => {
+                let full_path = files::resolve_for_write(&self.workspace, &options.path)?;
+                if full_path.is_dir() {
+                    bail!("{} is a directory", options.path.display());
+                }
+                let created = !full_path.exists();
+                if created && !options.create_if_missing {
+                    bail!("{} does not exist", options.path.display());
+                }
+                if let Some(parent) = full_path.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                write_atomic(&full_path, &options.contents)?;
+                Ok(ToolOutput::Written {
+                    path: self.relative(&full_path),
+                    bytes: options.contents.len(),
+                    created,
+                })
+            }
+            BindrTool::RunCommand(_) => bail!("Commands run through ToolExecutor::run_command"),
+            _ => bail!("Bindr can't {} through the executor", invocation.tool.kind().label()),
+        }
+    }
+
+    /// Run a program (no shell) inside the workspace, capturing its output and killing it after
+    /// [`COMMAND_TIMEOUT`].
+    ///
+    /// Without `allow_network`, well-known networking programs and fetch/publish subcommands are
+    /// refused. That catches obvious cases only; it is not a sandbox.
+    pub async fn run_command(&self, options: &CommandOptions) -> Result<ToolOutput> {
+        let working_dir = files::resolve_in_workspace(&self.workspace, &options.working_dir)?;
+        if !working_dir.is_dir() {
+            bail!("{} is not a directory", options.working_dir.display());
+        }
+        if !options.allow_network && let Some(reason) = network_use(&options.command, &options.args) {
+            bail!("{} needs network access, which this command wasn't granted", reason);
+        }
+
+        let command_line = options.command_line();
+        let child = tokio::process::Command::new(&options.command)
+            .args(&options.args)
+            .current_dir(&working_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start {}", options.command))?;
+
+        // Timing out drops the child, and kill_on_drop ends the process
+        let output = tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("{} was killed after {} seconds", command_line, COMMAND_TIMEOUT.as_secs())
+            })?
+            .with_context(|| format!("Failed to run {}", command_line))?;
+
+        Ok(ToolOutput::Command {
+            command: command_line,
+            exit_code: output.status.code(),
+            stdout: capped_output(&output.stdout),
+            stderr: capped_output(&output.stderr),
+        })
+    }
+
+    /// Display path of a resolved file, relative to the workspace when possible
+    fn relative(&self, full_path: &Path) -> PathBuf {
+        self.workspace
+            .canonicalize()
+            .ok()
+            .and_then(|root| full_path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .map(|relative| if relative.as_os_str().is_empty() { PathBuf::from(".") } else { relative })
+            .unwrap_or_else(|| full_path.to_path_buf())
+    }
+}
+
+/// Runs an approved tool call; [`ToolExecutor`] is the real one, tests swap in a fake
+pub trait ToolRunner: Send + Sync {
+    fn run<'a>(&'a self, invocation: &'a ToolInvocation) -> BoxFuture<'a, Result<ToolOutput>>;
+}
+
+impl ToolRunner for ToolExecutor {
+    fn run<'a>(&'a self, invocation: &'a ToolInvocation) -> BoxFuture<'a, Result<ToolOutput>> {
+        Box::pin(async move {
+            match &invocation.tool {
+                BindrTool::RunCommand(options) => self.run_command(options).await,
+                _ => self.execute(invocation),
+            }
+        })
+    }
+}
+
+/// Append `dir`'s entries in name order, descending into subdirectories right after each one
+/// when listing recursively; returns whether `limit` cut the listing short.
+///
+/// Symlinked directories are listed but not entered, so a link can't lead outside the workspace or loop.
+fn list_dir(
+    dir: &Path,
+    prefix: &Path,
+    options: &ListDirectoryOptions,
+    limit: usize,
+    entries: &mut Vec<DirEntry>,
+) -> Result<bool> {
+    let mut children: Vec<(String, bool)> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            (entry.file_name().to_string_lossy().into_owned(), is_dir)
+        })
+        .filter(|(name, _)| options.include_hidden || !name.starts_with('.'))
+        .collect();
+    children.sort();
+
+    for (name, is_dir) in children {
+        if entries.len() >= limit {
+            return Ok(true);
+        }
+        let path = prefix.join(&name);
+        entries.push(DirEntry {
+            path: path.clone(),
+            is_dir,
+        });
+        if is_dir && options.recursive && list_dir(&dir.join(&name), &path, options, limit, entries)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// What about a command line obviously needs the network, if anything
+fn network_use(command: &str, args: &[String]) -> Option<String> {
+    let program = Path::new(command).file_name()?.to_string_lossy().to_lowercase();
+    if NETWORK_PROGRAMS.contains(&program.as_str()) {
+        return Some(program);
+    }
+    // The subcommand is the first word that isn't an option or the value of one, as in `git -C dir fetch`
+    let mut words = args.iter();
+    let mut subcommand = None;
+    while let Some(arg) = words.next() {
+        if OPTIONS_WITH_VALUES.contains(&arg.as_str()) {
+            words.next();
+        } else if !arg.starts_with('-') {
+            subcommand = Some(arg);
+            break;
+        }
+    }
+    let subcommand = subcommand?;
+    NETWORK_SUBCOMMANDS
+        .iter()
+        .find(|(name, subcommands)| *name == program && subcommands.contains(&subcommand.as_str()))
+        .map(|_| format!("{} {}", program, subcommand))
+}
+
+/// Command output as text, cut to [`MAX_COMMAND_OUTPUT`] bytes
+fn capped_output(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_COMMAND_OUTPUT)]).into_owned();
+    if bytes.len() > MAX_COMMAND_OUTPUT {
+        format!("{}\n[truncated: {} of {} bytes]", text, MAX_COMMAND_OUTPUT, bytes.len())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BindrMode;
+    use crate::tools::{ApplyPatchOptions, CommandOptions, DiffFileOptions, ListDirectoryOptions, ReadFileOptions, WriteFileOptions};
+
+    fn run(workspace: &Path, tool: BindrTool) -> Result<ToolOutput> {
+        ToolExecutor::new(workspace).execute(&ToolInvocation::new(tool, BindrMode::Execute, "test"))
+    }
+
+    fn read_file(path: &str, max_bytes: Option<usize>) -> BindrTool {
+        BindrTool::ReadFile(ReadFileOptions { path: path.into(), max_bytes })
+    }
+
+    #[test]
+    fn binary_file_is_read_as_a_note() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        let output = run(workspace.path(), read_file("logo.png", None)).unwrap();
+        assert_eq!(output.render(), "logo.png: binary file, 16 bytes, not shown");
+        assert_eq!(output.summary(), "Read logo.png (binary)");
+    }
+
+    #[test]
+    fn binary_file_is_not_diffed() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        let output = run(workspace.path(), BindrTool::DiffFile(DiffFileOptions {
+            path: "logo.png".into(),
+            proposed: "text\n".to_string(),
+            context_lines: diff::DEFAULT_CONTEXT_LINES,
+        }))
+        .unwrap();
+        assert_eq!(output.render(), "logo.png is a binary file; it would be replaced with text.");
+    }
+
+    #[test]
+    fn reads_a_file() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::create_dir(workspace.path().join("src")).unwrap();
+        fs::write(workspace.path().join("src/lib.rs"), "pub fn answer() -> u32 { 42 }\n").unwrap();
+
+        let output = run(workspace.path(), read_file("src/lib.rs", None)).unwrap();
+        assert_eq!(output, ToolOutput::File {
+            path: "src/lib.rs".into(),
+            contents: FileContents::Text {
+                content: "pub fn answer() -> u32 { 42 }\n".to_string(),
+                truncated: false,
+                total_bytes: 30,
+            },
+        });
+    }
+
+    #[test]
+    fn read_stops_at_max_bytes() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("notes.txt"), "0123456789").unwrap();
+
+        let output = run(workspace.path(), read_file("notes.txt", Some(4))).unwrap();
+        assert_eq!(output.render(), "0123\n[truncated: showing 4 of 10 bytes of notes.txt]");
+    }
+
+    #[test]
+    fn read_outside_the_workspace_is_refused() {
+        let parent = tempfile::tempdir().unwrap();
+        let workspace = parent.path().join("workspace");
+        fs::create_dir(&workspace).unwrap();
+        fs::write(parent.path().join("secret.txt"), "hunter2").unwrap();
+
+        let error = run(&workspace, read_file("../secret.txt", None)).unwrap_err();
+        assert!(error.to_string().contains("is outside the workspace"), "{}", error);
+        let error = run(&workspace, read_file("missing.txt", None)).unwrap_err();
+        assert!(error.to_string().contains("missing.txt does not exist"), "{}", error);
+    }
+
+    fn list(workspace: &Path, recursive: bool, include_hidden: bool, max_entries: Option<usize>) -> String {
+        let tool = BindrTool::ListDirectory(ListDirectoryOptions {
+            path: ".".into(),
+            recursive,
+            include_hidden,
+            max_entries,
+        });
+        run(workspace, tool).unwrap().render()
+    }
+
+    fn tree() -> tempfile::TempDir {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::create_dir_all(workspace.path().join("src/ui")).unwrap();
+        fs::write(workspace.path().join("src/main.rs"), "").unwrap();
+        fs::write(workspace.path().join("src/ui/mod.rs"), "").unwrap();
+        fs::write(workspace.path().join("Cargo.toml"), "").unwrap();
+        fs::write(workspace.path().join(".env"), "").unwrap();
+        workspace
+    }
+
+    #[test]
+    fn lists_one_level_or_the_whole_tree() {
+        let workspace = tree();
+        assert_eq!(list(workspace.path(), false, false, None), "Cargo.toml\nsrc/");
+        assert_eq!(list(workspace.path(), true, false, None), "Cargo.toml\nsrc/\nsrc/main.rs\nsrc/ui/\nsrc/ui/mod.rs");
+    }
+
+    #[test]
+    fn hidden_entries_are_listed_on_request() {
+        let workspace = tree();
+        assert_eq!(list(workspace.path(), false, true, None), ".env\nCargo.toml\nsrc/");
+    }
+
+    #[test]
+    fn listing_stops_at_max_entries() {
+        let workspace = tree();
+        assert_eq!(
+            list(workspace.path(), true, false, Some(3)),
+            "Cargo.toml\nsrc/\nsrc/main.rs\n[truncated: showing the first 3 entries of .]"
+        );
+    }
+
+    fn write_file(path: &str, contents: &str, create_if_missing: bool) -> BindrTool {
+        BindrTool::WriteFile(WriteFileOptions {
+            path: path.into(),
+            contents: contents.to_string(),
+            create_if_missing,
+        })
+    }
+
+    #[test]
+    fn write_creates_the_file_and_its_directories() {
+        let workspace = tempfile::tempdir().unwrap();
+
+        let output = run(workspace.path(), write_file("docs/notes.md", "# Notes\n", true)).unwrap();
+        assert_eq!(output, ToolOutput::Written { path: "docs/notes.md".into(), bytes: 8, created: true });
+        assert_eq!(fs::read_to_string(workspace.path().join("docs/notes.md")).unwrap(), "# Notes\n");
+    }
+
+    #[test]
+    fn write_refuses_to_create_without_the_flag() {
+        let workspace = tempfile::tempdir().unwrap();
+
+        let error = run(workspace.path(), write_file("notes.md", "text", false)).unwrap_err();
+        assert_eq!(error.to_string(), "notes.md does not exist");
+        assert!(!workspace.path().join("notes.md").exists());
+    }
+
+    #[test]
+    fn write_overwrites_an_existing_file() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("notes.md"), "old text that is longer").unwrap();
+
+        let output = run(workspace.path(), write_file("notes.md", "new", false)).unwrap();
+        assert_eq!(output.render(), "Wrote notes.md (3 bytes)");
+        assert_eq!(fs::read_to_string(workspace.path().join("notes.md")).unwrap(), "new");
+    }
+
+    #[test]
+    fn write_outside_the_workspace_is_refused() {
+        let parent = tempfile::tempdir().unwrap();
+        let workspace = parent.path().join("workspace");
+        fs::create_dir(&workspace).unwrap();
+
+        assert!(run(&workspace, write_file("../escape.txt", "x", true)).is_err());
+        assert!(!parent.path().join("escape.txt").exists());
+    }
+
+    fn apply_patch(path: &str, patch: &str) -> BindrTool {
+        BindrTool::ApplyPatch(ApplyPatchOptions { path: path.into(), patch: patch.to_string() })
+    }
+
+    #[test]
+    fn patch_is_applied_to_the_file() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("list.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let output = run(workspace.path(), apply_patch("list.txt", "@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n")).unwrap();
+        assert_eq!(output.render(), "Patched list.txt (1 hunk)");
+        assert_eq!(fs::read_to_string(workspace.path().join("list.txt")).unwrap(), "one\nTWO\nthree\n");
+    }
+
+    #[test]
+    fn failed_hunk_leaves_the_file_untouched() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::write(workspace.path().join("list.txt"), "one\ntwo\nthree\nfour\nfive\nsix\n").unwrap();
+        // The first hunk matches, the second doesn't
+        let patch = "@@ -1,2 +1,2 @@\n-one\n+ONE\n two\n@@ -5,2 +5,2 @@\n five\n-seven\n+SEVEN\n";
+
+        let error = run(workspace.path(), apply_patch("list.txt", patch)).unwrap_err();
+        assert!(format!("{:#}", error).contains("Hunk 2 of 2"), "{:#}", error);
+        assert_eq!(fs::read_to_string(workspace.path().join("list.txt")).unwrap(), "one\ntwo\nthree\nfour\nfive\nsix\n");
+    }
+
+    fn command(command: &str, args: &[&str], allow_network: bool) -> CommandOptions {
+        CommandOptions {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            working_dir: ".".into(),
+            allow_network,
+        }
+    }
+
+    #[tokio::test]
+    async fn command_output_and_exit_code_are_captured() {
+        let workspace = tempfile::tempdir().unwrap();
+        let executor = ToolExecutor::new(workspace.path());
+
+        let output = executor.run_command(&command("echo", &["hello", "bindr"], false)).await.unwrap();
+        assert_eq!(output, ToolOutput::Command {
+            command: "echo hello bindr".to_string(),
+            exit_code: Some(0),
+            stdout: "hello bindr\n".to_string(),
+            stderr: String::new(),
+        });
+
+        let output = executor.run_command(&command("sh", &["-c", "echo oops >&2; exit 3"], false)).await.unwrap();
+        assert_eq!(output.render(), "$ sh -c echo oops >&2; exit 3\n[exit code 3]\nstderr:\noops");
+    }
+
+    #[tokio::test]
+    async fn command_runs_in_the_working_dir() {
+        let workspace = tempfile::tempdir().unwrap();
+        fs::create_dir(workspace.path().join("sub")).unwrap();
+        fs::write(workspace.path().join("sub/marker.txt"), "").unwrap();
+        let mut options = command("ls", &[], false);
+        options.working_dir = "sub".into();
+
+        let output = ToolExecutor::new(workspace.path()).run_command(&options).await.unwrap();
+        assert!(matches!(output, ToolOutput::Command { stdout, .. } if stdout == "marker.txt\n"));
+
+        options.working_dir = "..".into();
+        assert!(ToolExecutor::new(workspace.path()).run_command(&options).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn network_commands_need_permission() {
+        let workspace = tempfile::tempdir().unwrap();
+        let executor = ToolExecutor::new(workspace.path());
+
+        let error = executor.run_command(&command("git", &["-C", "repo", "fetch"], false)).await.unwrap_err();
+        assert_eq!(error.to_string(), "git fetch needs network access, which this command wasn't granted");
+        assert!(executor.run_command(&command("/usr/bin/curl", &["example.com"], false)).await.is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Hard cap on bytes read from any file, regardless of the requested `max_bytes`
+pub const MAX_READ_BYTES: usize = 256 * 1024;
+
+/// Leading bytes checked for NUL when deciding whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// What a file tool found on disk, ready to be shown to the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileContents {
+    Text {
+        content: String,
+        /// Whether the file was cut short by the size limit
+        truncated: bool,
+        total_bytes: u64,
+    },
+    /// NUL bytes or invalid UTF-8; never decoded into the prompt
+    Binary { total_bytes: u64 },
+}
+
+impl FileContents {
+    /// Tool-result text for the model
+    pub fn render(&self, path: &Path) -> String {
+        match self {
+            FileContents::Text { content, truncated: false, .. } => content.clone(),
+            FileContents::Text { content, truncated: true, total_bytes } => format!(
+                "{}\n[truncated: showing {} of {} bytes of {}]",
+                content,
+                content.len(),
+                total_bytes,
+                path.display()
+            ),
+            FileContents::Binary { total_bytes } => {
+                format!("{}: binary file, {} bytes, not shown", path.display(), total_bytes)
+            }
+        }
+    }
+}
+
+/// Resolve a path against the workspace root, refusing anything that lands outside it
+/// (including through `..` or symlinks)
+pub fn resolve_in_workspace(workspace: &Path, path: &Path) -> Result<PathBuf> {
+    let root = workspace
+        .canonicalize()
+        .with_context(|| format!("Workspace {} is not accessible", workspace.display()))?;
+    let full_path = root
+        .join(path)
+        .canonicalize()
+        .with_context(|| format!("{} does not exist", path.display()))?;
+    if !full_path.starts_with(&root) {
+        bail!("{} is outside the workspace {}", path.display(), root.display());
+    }
+    Ok(full_path)
+}
+
+/// Resolve a path that may not exist yet against the workspace root, refusing anything that
+/// would land outside it. The nearest existing ancestor is canonicalized so symlinks are followed.
+pub fn resolve_for_write(workspace: &Path, path: &Path) -> Result<PathBuf> {
+    let root = workspace
+        .canonicalize()
+        .with_context(|| format!("Workspace {} is not accessible", workspace.display()))?;
+
+    let mut normalized = PathBuf::new();
+    for component in root.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    let mut existing = normalized.as_path();
+    while !existing.exists() {
+        existing = existing
+            .parent()
+            .with_context(|| format!("{} has no existing parent directory", path.display()))?;
+    }
+    let mut full_path = existing
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", existing.display()))?;
+    // Joining an empty remainder would add a trailing slash
+    if let Ok(remainder) = normalized.strip_prefix(existing)
+        && !remainder.as_os_str().is_empty()
+    {
+        full_path.push(remainder);
+    }
+    if !full_path.starts_with(&root) {
+        bail!("{} is outside the workspace {}", path.display(), root.display());
+    }
+    Ok(full_path)
+}
+
+/// Read a file as UTF-8 text, stopping at `max_bytes` (capped by [`MAX_READ_BYTES`]).
+///
+/// Binary and non-UTF-8 files are reported as [`FileContents::Binary`] instead of
+/// being lossily decoded.
+pub fn read_text(path: &Path, max_bytes: Option<usize>) -> Result<FileContents> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let total_bytes = file
+        .metadata()
+        .with_context(|| format!("Failed to read metadata for {}", path.display()))?
+        .len();
+    let limit = max_bytes.unwrap_or(MAX_READ_BYTES).min(MAX_READ_BYTES);
+
+    let mut bytes = Vec::with_capacity(limit.min(total_bytes as usize));
+    file.take(limit as u64)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|&byte| byte == 0) {
+        return Ok(FileContents::Binary { total_bytes });
+    }
+
+    let truncated = (bytes.len() as u64) < total_bytes;
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => {
+            let utf8_error = e.utf8_error();
+            // The limit may split a multi-byte character; anything else is genuinely not UTF-8
+            if !truncated || utf8_error.error_len().is_some() {
+                return Ok(FileContents::Binary { total_bytes });
+            }
+            let mut bytes = e.into_bytes();
+            bytes.truncate(utf8_error.valid_up_to());
+            String::from_utf8(bytes).expect("prefix up to valid_up_to is valid UTF-8")
+        }
+    };
+
+    Ok(FileContents::Text {
+        content,
+        truncated,
+        total_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nul_bytes_mark_a_file_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logo.png");
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        assert_eq!(read_text(&path, None).unwrap(), FileContents::Binary { total_bytes: 16 });
+    }
+
+    #[test]
+    fn invalid_utf8_is_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latin1.txt");
+        std::fs::write(&path, b"caf\xe9 au lait").unwrap();
+
+        assert_eq!(read_text(&path, None).unwrap(), FileContents::Binary { total_bytes: 12 });
+    }
+
+    #[test]
+    fn limit_inside_a_character_stays_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "naïve").unwrap();
+
+        // "ï" takes bytes 2 and 3, so a 3-byte limit splits it
+        assert_eq!(
+            read_text(&path, Some(3)).unwrap(),
+            FileContents::Text { content: "na".to_string(), truncated: true, total_bytes: 6 }
+        );
+    }
+
+    #[test]
+    fn requested_size_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.txt");
+        std::fs::write(&path, "a".repeat(MAX_READ_BYTES + 10)).unwrap();
+
+        match read_text(&path, Some(usize::MAX)).unwrap() {
+            FileContents::Text { content, truncated, .. } => {
+                assert_eq!(content.len(), MAX_READ_BYTES);
+                assert!(truncated);
+            }
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+}
@@ -1,12 +1,18 @@
+pub mod calls;
 pub mod capabilities;
+pub mod diff;
 pub mod dispatcher;
+pub mod executor;
+pub mod files;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 use crate::events::BindrMode;
+pub use calls::parse_tool_call;
 pub use capabilities::ToolKind;
-pub use dispatcher::ToolDispatcher;
+pub use dispatcher::{ToolDispatcher, ToolNotPermitted};
+pub use executor::{ToolExecutor, ToolOutput, ToolRunner};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolInvocation {
@@ -44,6 +50,22 @@ pub enum BindrTool {
 }
 
 impl BindrTool {
+    /// What the tool acts on, for approval prompts and summaries
+    pub fn target(&self) -> String {
+        match self {
+            BindrTool::ReadFile(options) => options.path.display().to_string(),
+            BindrTool::WriteFile(options) => options.path.display().to_string(),
+            BindrTool::ListDirectory(options) => options.path.display().to_string(),
+            BindrTool::DiffFile(options) => options.path.display().to_string(),
+            BindrTool::ApplyPatch(options) => options.path.display().to_string(),
+            BindrTool::RunCommand(options) => {
+                format!("{} (in {})", options.command_line(), options.working_dir.display())
+            }
+            BindrTool::ListModels => String::new(),
+            BindrTool::SelectModel(selection) => format!("{}/{}", selection.provider_id, selection.model_id),
+        }
+    }
+
     pub fn kind(&self) -> ToolKind {
         match self {
             BindrTool::ReadFile(_) => ToolKind::ReadFile,
@@ -82,6 +104,8 @@ pub struct ListDirectoryOptions {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffFileOptions {
     pub path: PathBuf,
+    /// Contents to compare the file on disk against
+    pub proposed: String,
     pub context_lines: usize,
 }
 
@@ -99,6 +123,16 @@ pub struct CommandOptions {
     pub allow_network: bool,
 }
 
+impl CommandOptions {
+    /// The program and its arguments as one line
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelSelection {
     pub provider_id: String,
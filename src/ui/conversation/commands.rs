@@ -1,6 +1,14 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
 use crate::events::BindrMode;
+use crate::persist::write_atomic;
 
 use strum::{IntoEnumIterator, AsRefStr, EnumIter, EnumString, IntoStaticStr};
 
@@ -14,10 +22,47 @@ pub enum SlashCommand {
     Mode,
     /// Switch to a different model
     Model,
+    /// Browse every configured provider and model
+    Catalog,
     /// Return to home screen
     Home,
     /// Exit the application
     Bye,
+    /// Show what the current mode can and can't do
+    Capabilities,
+    /// Review file changes queued by the assistant
+    Diff,
+    /// Let Execute mode work through the checklist step by step
+    Auto,
+    /// Open the last response in $EDITOR or $PAGER
+    Open,
+    /// Explain the most recent provider error
+    Explain,
+    /// Show turns, tokens, and time spent per mode
+    Stats,
+    /// Change the workspace directory used by file and command tools
+    Cd,
+    /// Set the language the assistant answers in
+    Lang,
+    /// Send a workspace file with the next message
+    Attach,
+    /// Switch provider without leaving the conversation
+    Provider,
+    /// Override the sampling temperature for this session
+    Temperature,
+    /// Override the reply length limit for this session
+    #[strum(serialize = "maxtokens")]
+    MaxTokens,
+    /// Flag a message for later review
+    Flag,
+    /// List flagged messages
+    Flags,
+    /// Clear the conversation scrollback
+    Clear,
+    /// Save the conversation with the project
+    Save,
+    /// Write the conversation to a Markdown file
+    Export,
     /// Show help
     Help,
 }
@@ -45,6 +90,74 @@ pub struct CommandEntry {
     pub description: &'static str,
 }
 
+const COMMAND_USAGE_FILE: &str = "command_usage.json";
+
+/// Per-command usage statistics used to rank the command palette, persisted across restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandUsage {
+    #[serde(default)]
+    pub counts: HashMap<String, u32>,
+    #[serde(default)]
+    pub last_used: HashMap<String, DateTime<Utc>>,
+    #[serde(default)]
+    pub favorites: Vec<String>,
+}
+
+impl CommandUsage {
+    /// Load usage statistics from the Bindr home directory, falling back to empty stats.
+    pub fn load(bindr_home: &Path) -> Self {
+        fs::read_to_string(bindr_home.join(COMMAND_USAGE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist usage statistics to the Bindr home directory.
+    pub fn save(&self, bindr_home: &Path) -> Result<()> {
+        fs::create_dir_all(bindr_home)
+            .context("Failed to create Bindr home directory")?;
+        let content = serde_json::to_string_pretty(self)
+            .context("Failed to serialize command usage")?;
+        write_atomic(&bindr_home.join(COMMAND_USAGE_FILE), content)
+            .context("Failed to write command usage")?;
+        Ok(())
+    }
+
+    /// Record that a command was just invoked.
+    pub fn record(&mut self, command: SlashCommand) {
+        let keyword = command.command().to_string();
+        *self.counts.entry(keyword.clone()).or_insert(0) += 1;
+        self.last_used.insert(keyword, Utc::now());
+    }
+
+    pub fn is_favorite(&self, command: SlashCommand) -> bool {
+        self.favorites.iter().any(|keyword| keyword == command.command())
+    }
+
+    /// Pin or unpin a command at the top of the palette.
+    pub fn toggle_favorite(&mut self, command: SlashCommand) {
+        let keyword = command.command();
+        if self.is_favorite(command) {
+            self.favorites.retain(|existing| existing != keyword);
+        } else {
+            self.favorites.push(keyword.to_string());
+        }
+    }
+
+    /// Order palette entries: favorites first, then by frequency, recency, and name.
+    pub fn rank(&self, entries: &mut [CommandEntry]) {
+        entries.sort_by(|a, b| {
+            let count = |entry: &CommandEntry| self.counts.get(entry.keyword).copied().unwrap_or(0);
+            let last = |entry: &CommandEntry| self.last_used.get(entry.keyword).copied();
+            self.is_favorite(b.command)
+                .cmp(&self.is_favorite(a.command))
+                .then_with(|| count(b).cmp(&count(a)))
+                .then_with(|| last(b).cmp(&last(a)))
+                .then_with(|| a.keyword.cmp(b.keyword))
+        });
+    }
+}
+
 impl ParsedCommand {
     pub fn argument(&self) -> Option<&str> {
         self.argument.as_deref()
@@ -55,14 +168,7 @@ impl ParsedCommand {
             return None;
         }
 
-        let arg = self.argument()?.trim().to_lowercase();
-        match arg.as_str() {
-            "b" | "brainstorm" => Some(BindrMode::Brainstorm),
-            "p" | "plan" => Some(BindrMode::Plan),
-            "e" | "execute" | "build" => Some(BindrMode::Execute),
-            "d" | "doc" | "document" => Some(BindrMode::Document),
-            _ => None,
-        }
+        BindrMode::from_name(self.argument()?)
     }
 }
 
@@ -71,9 +177,27 @@ impl SlashCommand {
     pub fn description(self) -> &'static str {
         match self {
             SlashCommand::Mode => "switch to a different mode (brainstorm, plan, execute, document)",
-            SlashCommand::Model => "switch to a different model",
+            SlashCommand::Model => "switch model by id (e.g. /model gpt-4o-mini), or pick one from a list",
+            SlashCommand::Catalog => "browse and search every configured provider and model",
             SlashCommand::Home => "return to the home screen",
             SlashCommand::Bye => "exit the application",
+            SlashCommand::Capabilities => "show what the current mode can and can't do",
+            SlashCommand::Diff => "review the pending file changes before approving them",
+            SlashCommand::Auto => "toggle working through the Execute checklist step by step",
+            SlashCommand::Open => "open the last response in $EDITOR or $PAGER",
+            SlashCommand::Explain => "explain the most recent error and how to fix it",
+            SlashCommand::Stats => "show turns, tokens, and time spent per mode",
+            SlashCommand::Cd => "change the workspace directory used by file and command tools",
+            SlashCommand::Lang => "set the language the assistant answers in (e.g. /lang de)",
+            SlashCommand::Attach => "attach a workspace file to your next message (/attach clear removes them)",
+            SlashCommand::Provider => "switch provider (e.g. /provider anthropic), pick one from a list, or /provider add|remove <id> a custom one",
+            SlashCommand::Temperature => "show or override the sampling temperature (e.g. /temperature 0.3)",
+            SlashCommand::MaxTokens => "show or override the reply length limit in tokens (e.g. /maxtokens 4000)",
+            SlashCommand::Flag => "flag the selected message (or the latest reply) for later review",
+            SlashCommand::Flags => "list flagged messages; /flags <n> jumps to one",
+            SlashCommand::Clear => "clear the conversation scrollback",
+            SlashCommand::Save => "save the conversation with the project",
+            SlashCommand::Export => "write the conversation to a Markdown file; /export <path> picks where",
             SlashCommand::Help => "show available commands",
         }
     }
@@ -86,7 +210,28 @@ impl SlashCommand {
     /// Whether this command can be run while streaming is active.
     pub fn available_during_streaming(self) -> bool {
         match self {
-            SlashCommand::Mode | SlashCommand::Model | SlashCommand::Home | SlashCommand::Bye | SlashCommand::Help => true,
+            SlashCommand::Mode
+            | SlashCommand::Model
+            | SlashCommand::Catalog
+            | SlashCommand::Home
+            | SlashCommand::Bye
+            | SlashCommand::Capabilities
+            | SlashCommand::Diff
+            | SlashCommand::Auto
+            | SlashCommand::Open
+            | SlashCommand::Explain
+            | SlashCommand::Stats
+            | SlashCommand::Cd
+            | SlashCommand::Lang
+            | SlashCommand::Attach
+            | SlashCommand::Provider
+            | SlashCommand::Temperature
+            | SlashCommand::MaxTokens
+            | SlashCommand::Flag
+            | SlashCommand::Flags
+            | SlashCommand::Export
+            | SlashCommand::Help => true,
+            SlashCommand::Clear | SlashCommand::Save => false,
         }
     }
 }
@@ -113,6 +258,11 @@ pub fn parse_slash_command(input: &str) -> Option<ParsedCommand> {
         "h" | "home" => Some(SlashCommand::Home),
         "m" | "switch" => Some(SlashCommand::Mode),
         "models" => Some(SlashCommand::Model),
+        "caps" => Some(SlashCommand::Capabilities),
+        "workspace" => Some(SlashCommand::Cd),
+        "language" | "locale" => Some(SlashCommand::Lang),
+        "temp" => Some(SlashCommand::Temperature),
+        "max-tokens" | "max_tokens" => Some(SlashCommand::MaxTokens),
         _ => None,
     })?;
 
@@ -132,8 +282,13 @@ pub fn get_help_text() -> String {
         help.push_str(&format!("/{} - {}\n", command_str, command.description()));
     }
     
-    help.push_str("\nYou can also use aliases like /q for /bye, /h for /home, /m for /mode, /models for /model");
+    help.push_str("\nYou can also use aliases like /q for /bye, /h for /home, /m for /mode, /models for /model, /caps for /capabilities, /workspace for /cd");
+    help.push_str("\nUse /cd <dir> to point tools at another project tree; /cd alone shows the current one.");
+    help.push_str("\nUse /temperature <0-2> to override the per-mode temperature; /temperature default goes back to it.");
+    help.push_str("\nUse /maxtokens <n> to allow longer or shorter replies; /maxtokens default goes back to the per-mode limit.");
+    help.push_str("\nUse /lang <code> to get answers in another language; /lang default returns to the configured one.");
     help.push_str("\nUse /mode <b|p|e|d> to jump directly to Brainstorm, Plan, Execute, or Document mode.");
+    help.push_str("\nPgUp/PgDn or Ctrl+U/Ctrl+D scroll the history; Ctrl+L switches between the comfortable and compact layouts.");
 
     help
 }
@@ -1,20 +1,25 @@
+use crate::config::SubmitKey;
 use crate::events::BindrMode;
-use crate::ui::conversation::commands::{command_entries, CommandEntry, ParsedCommand};
+use crate::ui::conversation::commands::{command_entries, CommandEntry, CommandUsage, ParsedCommand, SlashCommand};
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Widget},
+    widgets::{block::{Position, Title}, Block, Borders, Widget},
 };
 use std::cell::{Cell, RefCell};
 
+/// Most draft lines the composer grows to show before it scrolls
+const MAX_VISIBLE_LINES: usize = 6;
+
 /// Result returned when the user interacts with the conversation composer
 #[derive(Debug, PartialEq)]
 pub enum ConversationResult {
     Submitted(String),
     Command(ParsedCommand),
+    ToggleFavorite(SlashCommand),
     None,
 }
 
@@ -22,8 +27,9 @@ pub enum ConversationResult {
 #[derive(Debug, Clone)]
 pub struct TextAreaState {
     pub content: String,
+    /// Byte offset of the cursor in `content`, always on a character boundary
     pub cursor_position: usize,
-    #[allow(dead_code)]
+    /// First draft line shown, so the cursor's line stays in view
     pub scroll_offset: usize,
 }
 
@@ -37,6 +43,98 @@ impl Default for TextAreaState {
     }
 }
 
+impl TextAreaState {
+    /// Byte offset where the cursor's line starts
+    fn line_start(&self) -> usize {
+        self.content[..self.cursor_position].rfind('\n').map_or(0, |index| index + 1)
+    }
+
+    /// Byte offset where the cursor's line ends, before its newline
+    fn line_end(&self) -> usize {
+        self.content[self.cursor_position..]
+            .find('\n')
+            .map_or(self.content.len(), |index| self.cursor_position + index)
+    }
+
+    /// Line and column of the cursor, both from zero; the column counts characters
+    pub fn cursor_line_col(&self) -> (usize, usize) {
+        let before = &self.content[..self.cursor_position];
+        let line = before.matches('\n').count();
+        let column = self.content[self.line_start()..self.cursor_position].chars().count();
+        (line, column)
+    }
+
+    /// Put the cursor on `line` at `column`, or at the end of the line when it is shorter
+    fn set_cursor_line_col(&mut self, line: usize, column: usize) {
+        let start = match line.checked_sub(1) {
+            Some(previous) => self.content
+                .match_indices('\n')
+                .nth(previous)
+                .map_or(self.content.len(), |(index, _)| index + 1),
+            None => 0,
+        };
+        let text = self.content[start..].split('\n').next().unwrap_or_default();
+        let offset = text.char_indices().nth(column).map_or(text.len(), |(index, _)| index);
+        self.cursor_position = start + offset;
+    }
+
+    fn line_count(&self) -> usize {
+        self.content.matches('\n').count() + 1
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(c) = self.content[..self.cursor_position].chars().next_back() {
+            self.cursor_position -= c.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.content[self.cursor_position..].chars().next() {
+            self.cursor_position += c.len_utf8();
+        }
+    }
+
+    /// Move to the start of the cursor's line
+    pub fn move_home(&mut self) {
+        self.cursor_position = self.line_start();
+    }
+
+    /// Move to the end of the cursor's line
+    pub fn move_end(&mut self) {
+        self.cursor_position = self.line_end();
+    }
+
+    /// Move to the same column on the line above; false when already on the first line
+    pub fn move_up(&mut self) -> bool {
+        let (line, column) = self.cursor_line_col();
+        if line == 0 {
+            return false;
+        }
+        self.set_cursor_line_col(line - 1, column);
+        true
+    }
+
+    /// Move to the same column on the line below; false when already on the last line
+    pub fn move_down(&mut self) -> bool {
+        let (line, column) = self.cursor_line_col();
+        if line + 1 >= self.line_count() {
+            return false;
+        }
+        self.set_cursor_line_col(line + 1, column);
+        true
+    }
+
+    /// Scroll just enough that the cursor's line is among `rows` visible lines
+    fn scroll_to_cursor(&mut self, rows: usize) {
+        let (line, _) = self.cursor_line_col();
+        if line < self.scroll_offset {
+            self.scroll_offset = line;
+        } else if rows > 0 && line >= self.scroll_offset + rows {
+            self.scroll_offset = line + 1 - rows;
+        }
+    }
+}
+
 /// Conversation composer for user input
 #[derive(Clone)]
 pub struct ConversationComposer {
@@ -48,6 +146,12 @@ pub struct ConversationComposer {
     filtered_commands: RefCell<Vec<CommandEntry>>,
     show_command_palette: Cell<bool>,
     selected_command: Cell<Option<usize>>,
+    command_usage: RefCell<CommandUsage>,
+    /// Estimated size and cost of sending the current draft
+    cost_hint: Option<String>,
+    submit_key: SubmitKey,
+    /// Files queued with /attach, shown as chips on the top border
+    attachments: Vec<String>,
 }
 
 impl ConversationComposer {
@@ -61,6 +165,40 @@ impl ConversationComposer {
             filtered_commands: RefCell::new(Vec::new()),
             show_command_palette: Cell::new(false),
             selected_command: Cell::new(None),
+            command_usage: RefCell::new(CommandUsage::default()),
+            cost_hint: None,
+            submit_key: SubmitKey::default(),
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Choose which key sends the draft; Enter inserts a newline unless it is the submit key
+    pub fn set_submit_key(&mut self, submit_key: SubmitKey) {
+        self.submit_key = submit_key;
+    }
+
+    /// Whether `key` sends the draft rather than editing it.
+    ///
+    /// Single-line slash commands always go out on a plain Enter.
+    fn is_submit(&self, key: &KeyEvent, content: &str) -> bool {
+        let plain_enter = key.code == KeyCode::Enter
+            && !key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if plain_enter && content.starts_with('/') && !content.contains('\n') {
+            return true;
+        }
+        match self.submit_key {
+            SubmitKey::Enter => key.code == KeyCode::Enter && !key.modifiers.contains(KeyModifiers::SHIFT),
+            SubmitKey::CtrlEnter => key.code == KeyCode::Enter && key.modifiers.contains(KeyModifiers::CONTROL),
+            SubmitKey::AltEnter => key.code == KeyCode::Enter && key.modifiers.contains(KeyModifiers::ALT),
+            SubmitKey::CtrlS => key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL),
+        }
+    }
+
+    /// Replace the usage statistics used to rank the command palette
+    pub fn set_command_usage(&self, usage: CommandUsage) {
+        *self.command_usage.borrow_mut() = usage;
+        if self.show_command_palette.get() {
+            self.refresh_command_palette(&self.state.borrow());
         }
     }
 
@@ -71,12 +209,11 @@ impl ConversationComposer {
         }
 
         let mut state = self.state.borrow_mut();
+        let submit = self.is_submit(&key, &state.content);
 
         match key.code {
-            KeyCode::Enter => {
-                if key.modifiers.contains(KeyModifiers::SHIFT) {
-                    self.insert_char(&mut state, '\n');
-                } else if self.show_command_palette.get() {
+            _ if submit => {
+                if self.show_command_palette.get() {
                     if self.apply_selected_command(&mut state) {
                         return ConversationResult::None;
                     }
@@ -84,6 +221,7 @@ impl ConversationComposer {
                     let content = state.content.clone();
                     state.content.clear();
                     state.cursor_position = 0;
+                    state.scroll_offset = 0;
                     self.close_command_palette();
                     drop(state);
                     if let Some(command) = crate::ui::conversation::commands::parse_slash_command(&content) {
@@ -93,17 +231,22 @@ impl ConversationComposer {
                     }
                 }
             }
+            KeyCode::Enter => {
+                self.insert_char(&mut state, '\n');
+            }
             KeyCode::Up => {
                 if self.show_command_palette.get() {
                     self.move_command_selection(-1);
                     return ConversationResult::None;
                 }
+                state.move_up();
             }
             KeyCode::Down => {
                 if self.show_command_palette.get() {
                     self.move_command_selection(1);
                     return ConversationResult::None;
                 }
+                state.move_down();
             }
             KeyCode::Esc => {
                 if self.show_command_palette.get() {
@@ -118,6 +261,14 @@ impl ConversationComposer {
                     }
                 }
             }
+            KeyCode::Char('f')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.show_command_palette.get() =>
+            {
+                let filtered = self.filtered_commands.borrow();
+                if let Some(entry) = self.selected_command.get().and_then(|i| filtered.get(i)) {
+                    return ConversationResult::ToggleFavorite(entry.command);
+                }
+            }
             KeyCode::Char(c) => {
                 if c == '/' && state.content.is_empty() {
                     self.insert_char(&mut state, c);
@@ -163,22 +314,10 @@ impl ConversationComposer {
                     }
                 }
             }
-            KeyCode::Left => {
-                if state.cursor_position > 0 {
-                    state.cursor_position -= 1;
-                }
-            }
-            KeyCode::Right => {
-                if state.cursor_position < state.content.len() {
-                    state.cursor_position += 1;
-                }
-            }
-            KeyCode::Home => {
-                state.cursor_position = 0;
-            }
-            KeyCode::End => {
-                state.cursor_position = state.content.len();
-            }
+            KeyCode::Left => state.move_left(),
+            KeyCode::Right => state.move_right(),
+            KeyCode::Home => state.move_home(),
+            KeyCode::End => state.move_end(),
             _ => {}
         }
 
@@ -188,13 +327,13 @@ impl ConversationComposer {
     /// Insert a character at the cursor position
     fn insert_char(&self, state: &mut TextAreaState, c: char) {
         state.content.insert(state.cursor_position, c);
-        state.cursor_position += 1;
+        state.cursor_position += c.len_utf8();
     }
 
     /// Delete character before cursor
     fn backspace(&self, state: &mut TextAreaState) -> bool {
         if state.cursor_position > 0 {
-            state.cursor_position -= 1;
+            state.move_left();
             state.content.remove(state.cursor_position);
             true
         } else {
@@ -218,6 +357,11 @@ impl ConversationComposer {
         self.selected_command.set(Some(0));
     }
 
+    /// Whether Tab and the arrow keys currently belong to the command palette
+    pub fn is_command_palette_open(&self) -> bool {
+        self.show_command_palette.get()
+    }
+
     fn close_command_palette(&self) {
         self.show_command_palette.set(false);
         self.filtered_commands.borrow_mut().clear();
@@ -234,6 +378,7 @@ impl ConversationComposer {
                 filtered.push(*entry);
             }
         }
+        self.command_usage.borrow().rank(&mut filtered);
 
         if filtered.is_empty() {
             self.selected_command.set(None);
@@ -295,26 +440,49 @@ impl ConversationComposer {
     }
 
     /// Get current content
-    #[allow(dead_code)]
     pub fn get_content(&self) -> String {
         self.state.borrow().content.clone()
     }
 
+    /// Replace the draft, placing the cursor at the end
+    pub fn set_content(&self, content: String) {
+        let mut state = self.state.borrow_mut();
+        state.cursor_position = content.len();
+        state.content = content;
+    }
+
+    /// Rows the composer needs, borders included: one per draft line, up to `MAX_VISIBLE_LINES`
+    pub fn height(&self) -> u16 {
+        let lines = self.state.borrow().line_count().min(MAX_VISIBLE_LINES);
+        lines as u16 + 2
+    }
+
+    /// Show an estimate of what sending the draft would cost
+    pub fn set_cost_hint(&mut self, hint: Option<String>) {
+        self.cost_hint = hint;
+    }
+
+    /// Show which files will be sent with the next message
+    pub fn set_attachments(&mut self, attachments: Vec<String>) {
+        self.attachments = attachments;
+    }
+
     /// Clear content
     #[allow(dead_code)]
     pub fn clear(&self) {
         let mut state = self.state.borrow_mut();
         state.content.clear();
         state.cursor_position = 0;
+        state.scroll_offset = 0;
     }
 }
 
 impl Widget for ConversationComposer {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let state = self.state.borrow();
+        let mut state = self.state.borrow_mut();
         
         // Create the input block
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .title(self.get_mode_title())
             .style(if self.has_focus {
@@ -322,6 +490,20 @@ impl Widget for ConversationComposer {
             } else {
                 Style::default().fg(Color::Gray)
             });
+        if let Some(hint) = &self.cost_hint {
+            block = block.title(
+                Title::from(Span::styled(format!(" {} ", hint), Style::default().fg(Color::DarkGray)))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Right),
+            );
+        }
+        if !self.attachments.is_empty() {
+            let chips: Vec<String> = self.attachments.iter().map(|path| format!("📎 {}", path)).collect();
+            block = block.title(
+                Title::from(Span::styled(format!(" {} ", chips.join("  ")), Style::default().fg(Color::Cyan)))
+                    .alignment(Alignment::Right),
+            );
+        }
 
         let inner_area = block.inner(area);
         block.render(area, buf);
@@ -336,17 +518,30 @@ impl Widget for ConversationComposer {
             ]);
             buf.set_line(inner_area.x, inner_area.y, &placeholder_line, inner_area.width);
         } else {
-            // Render content with cursor indicator
-            let mut content = state.content.clone();
-            if self.has_focus {
-                content.insert(state.cursor_position.min(content.len()), '▌');
-            }
-
-            for (i, line_text) in content.split('\n').enumerate() {
-                if i < inner_area.height as usize {
-                    let line = Line::from(vec![Span::raw(line_text)]);
-                    buf.set_line(inner_area.x, inner_area.y + i as u16, &line, inner_area.width);
-                }
+            // Render the lines in view, with the cursor indicator on the cursor's line
+            let rows = inner_area.height as usize;
+            state.scroll_to_cursor(rows);
+            let (cursor_line, cursor_column) = state.cursor_line_col();
+            let width = inner_area.width as usize;
+
+            for (row, (index, line_text)) in state.content
+                .split('\n')
+                .enumerate()
+                .skip(state.scroll_offset)
+                .take(rows)
+                .enumerate()
+            {
+                let text = if index == cursor_line && self.has_focus {
+                    let mut chars: Vec<char> = line_text.chars().collect();
+                    chars.insert(cursor_column, '▌');
+                    // Keep the cursor in view on a line wider than the composer
+                    let skip = (cursor_column + 1).saturating_sub(width);
+                    chars.into_iter().skip(skip).collect()
+                } else {
+                    line_text.to_string()
+                };
+                let line = Line::from(vec![Span::raw(text)]);
+                buf.set_line(inner_area.x, inner_area.y + row as u16, &line, inner_area.width);
             }
         }
 
@@ -363,12 +558,13 @@ impl Widget for ConversationComposer {
 
             let block = Block::default()
                 .borders(Borders::ALL)
-                .title("Commands")
+                .title("Commands (Ctrl+F to pin)")
                 .style(Style::default().fg(Color::Blue));
             let inner = block.inner(palette_area);
             block.render(palette_area, buf);
 
             let selected = self.selected_command.get();
+            let usage = self.command_usage.borrow();
             for (index, entry) in filtered.iter().enumerate() {
                 if index >= inner.height as usize {
                     break;
@@ -381,7 +577,9 @@ impl Widget for ConversationComposer {
                     Style::default().fg(Color::White)
                 };
 
+                let pin = if usage.is_favorite(entry.command) { "★ " } else { "" };
                 let line = Line::from(vec![
+                    Span::styled(pin, Style::default().fg(Color::Yellow)),
                     Span::styled(format!("/{}", entry.keyword), style),
                     Span::styled(" — ", Style::default().fg(Color::DarkGray)),
                     Span::styled(entry.description, Style::default().fg(Color::Gray)),
@@ -405,3 +603,120 @@ impl ConversationComposer {
         .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRAFT: &str = "first line\nab\nthird line";
+
+    fn state_at(cursor_position: usize) -> TextAreaState {
+        TextAreaState { content: DRAFT.to_string(), cursor_position, scroll_offset: 0 }
+    }
+
+    fn press(composer: &ConversationComposer, code: KeyCode) {
+        composer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn up_and_down_keep_the_column_and_clamp_on_a_short_line() {
+        // Column 5 of the first line
+        let mut state = state_at(5);
+        assert_eq!(state.cursor_line_col(), (0, 5));
+
+        assert!(state.move_down());
+        assert_eq!(state.cursor_line_col(), (1, 2));
+        assert!(state.move_down());
+        assert_eq!(state.cursor_line_col(), (2, 2));
+        assert!(!state.move_down());
+        assert_eq!(state.cursor_line_col(), (2, 2));
+
+        assert!(state.move_up());
+        assert!(state.move_up());
+        assert_eq!(state.cursor_line_col(), (0, 2));
+        assert!(!state.move_up());
+        assert_eq!(state.cursor_position, 2);
+    }
+
+    #[test]
+    fn home_and_end_stay_on_the_cursor_line() {
+        // Inside "ab"
+        let mut state = state_at(12);
+        state.move_home();
+        assert_eq!(state.cursor_position, 11);
+        state.move_end();
+        assert_eq!(state.cursor_position, 13);
+        assert_eq!(state.cursor_line_col(), (1, 2));
+    }
+
+    #[test]
+    fn left_and_right_cross_line_breaks() {
+        let mut state = state_at(11);
+        state.move_left();
+        assert_eq!(state.cursor_line_col(), (0, 10));
+        state.move_right();
+        state.move_right();
+        state.move_right();
+        assert_eq!(state.cursor_line_col(), (1, 2));
+        state.move_right();
+        assert_eq!(state.cursor_line_col(), (2, 0));
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let mut state = TextAreaState { content: "héllo\nwörld\nok".to_string(), cursor_position: 3, scroll_offset: 0 };
+        assert_eq!(state.cursor_line_col(), (0, 2));
+        assert!(state.move_down());
+        assert_eq!(state.cursor_line_col(), (1, 2));
+        assert_eq!(&state.content[state.cursor_position..], "rld\nok");
+        assert!(state.move_down());
+        assert_eq!(state.cursor_line_col(), (2, 2));
+    }
+
+    #[test]
+    fn scrolling_follows_the_cursor_line() {
+        let mut state = state_at(DRAFT.len());
+        state.scroll_to_cursor(2);
+        assert_eq!(state.scroll_offset, 1);
+        state.cursor_position = 0;
+        state.scroll_to_cursor(2);
+        assert_eq!(state.scroll_offset, 0);
+    }
+
+    #[test]
+    fn arrow_keys_edit_the_right_line() {
+        let composer = ConversationComposer::new(String::new(), BindrMode::Execute);
+        composer.set_content(DRAFT.to_string());
+
+        press(&composer, KeyCode::Up);
+        press(&composer, KeyCode::Home);
+        press(&composer, KeyCode::Char('>'));
+        press(&composer, KeyCode::Up);
+        press(&composer, KeyCode::End);
+        press(&composer, KeyCode::Char('!'));
+        press(&composer, KeyCode::Down);
+        press(&composer, KeyCode::Down);
+        press(&composer, KeyCode::Left);
+        press(&composer, KeyCode::Char('_'));
+
+        assert_eq!(composer.get_content(), "first line!\n>ab\nth_ird line");
+    }
+
+    #[test]
+    fn cursor_is_drawn_on_its_row_and_column() {
+        let mut composer = ConversationComposer::new(String::new(), BindrMode::Execute);
+        composer.set_focus(true);
+        composer.set_content(DRAFT.to_string());
+        press(&composer, KeyCode::Up);
+        press(&composer, KeyCode::Left);
+
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        composer.render(area, &mut buf);
+
+        let row = |y: u16| (1..area.width - 1).map(|x| buf.get(x, y).symbol().to_string()).collect::<String>();
+        assert_eq!(row(1).trim_end(), "first line");
+        assert_eq!(row(2).trim_end(), "a▌b");
+        assert_eq!(row(3).trim_end(), "third line");
+    }
+}
@@ -0,0 +1,152 @@
+//! Review panel for file changes queued by the assistant
+
+use crate::tools::diff::ChangePreview;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{block::{Position, Title}, Block, Borders, Widget},
+};
+
+/// What the user decided in the diff view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffDecision {
+    /// Keep reviewing
+    None,
+    /// Close the view and leave the queue as it is
+    Close,
+    /// Apply the changes at these queue indices and drop the rest
+    Approve(Vec<usize>),
+    /// Drop every queued change
+    RejectAll,
+}
+
+/// File-by-file view of pending changes with per-file selection
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    files: Vec<ChangePreview>,
+    selected: Vec<bool>,
+    cursor: usize,
+    scroll: usize,
+}
+
+impl DiffView {
+    pub fn new(files: Vec<ChangePreview>) -> Self {
+        let selected = vec![true; files.len()];
+        Self {
+            files,
+            selected,
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> DiffDecision {
+        match key.code {
+            KeyCode::Esc => DiffDecision::Close,
+            KeyCode::Up if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.scroll = 0;
+                DiffDecision::None
+            }
+            KeyCode::Down if self.cursor + 1 < self.files.len() => {
+                self.cursor += 1;
+                self.scroll = 0;
+                DiffDecision::None
+            }
+            KeyCode::PageDown => {
+                self.scroll += 10;
+                DiffDecision::None
+            }
+            KeyCode::PageUp => {
+                self.scroll = self.scroll.saturating_sub(10);
+                DiffDecision::None
+            }
+            KeyCode::Char(' ') => {
+                if let Some(selected) = self.selected.get_mut(self.cursor) {
+                    *selected = !*selected;
+                }
+                DiffDecision::None
+            }
+            KeyCode::Char('a') => DiffDecision::Approve((0..self.files.len()).collect()),
+            KeyCode::Enter => DiffDecision::Approve(
+                self.selected
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, &selected)| selected.then_some(index))
+                    .collect(),
+            ),
+            KeyCode::Char('r') => DiffDecision::RejectAll,
+            _ => DiffDecision::None,
+        }
+    }
+
+    fn diff_line(text: &str) -> Line<'static> {
+        let style = if text.starts_with("+++") || text.starts_with("---") {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else if text.starts_with("@@") {
+            Style::default().fg(Color::Cyan)
+        } else if text.starts_with('+') {
+            Style::default().fg(Color::Green)
+        } else if text.starts_with('-') {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        Line::from(Span::styled(text.to_string(), style))
+    }
+}
+
+impl Widget for DiffView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("📝 Pending changes ({} files)", self.files.len()))
+            .title(
+                Title::from(Span::styled(
+                    " ↑↓ file • PgUp/PgDn scroll • Space select • Enter apply selected • a apply all • r reject all • Esc close ",
+                    Style::default().fg(Color::DarkGray),
+                ))
+                .position(Position::Bottom),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let list_height = (self.files.len() as u16).min(inner.height / 3).max(1);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(list_height), Constraint::Length(1), Constraint::Min(1)])
+            .split(inner);
+
+        let list_start = self.cursor.saturating_sub(list_height as usize - 1);
+        for (row, index) in (list_start..self.files.len()).take(list_height as usize).enumerate() {
+            let marker = if self.selected[index] { "[x]" } else { "[ ]" };
+            let style = if index == self.cursor {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let line = Line::from(Span::styled(
+                format!("{} {}", marker, self.files[index].path.display()),
+                style,
+            ));
+            buf.set_line(chunks[0].x, chunks[0].y + row as u16, &line, chunks[0].width);
+        }
+
+        let separator = Line::from(Span::styled(
+            "─".repeat(chunks[1].width as usize),
+            Style::default().fg(Color::DarkGray),
+        ));
+        buf.set_line(chunks[1].x, chunks[1].y, &separator, chunks[1].width);
+
+        if let Some(file) = self.files.get(self.cursor) {
+            let lines: Vec<&str> = file.diff.lines().collect();
+            let start = self.scroll.min(lines.len().saturating_sub(1));
+            for (row, text) in lines[start..].iter().take(chunks[2].height as usize).enumerate() {
+                buf.set_line(chunks[2].x, chunks[2].y + row as u16, &Self::diff_line(text), chunks[2].width);
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Token coloring for fenced code blocks in a handful of common languages.
+//!
+//! Each line is lexed on its own, so constructs that span lines (block comments,
+//! multi-line strings) only color the line they start on.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+/// Languages with a built-in lexer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    Shell,
+    Json,
+}
+
+impl Language {
+    /// Language named by a fence's info string (```rust, ```py, ...), if it is one Bindr knows
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let name = tag.split_whitespace().next()?.to_ascii_lowercase();
+        match name.as_str() {
+            "rust" | "rs" => Some(Language::Rust),
+            "python" | "py" => Some(Language::Python),
+            "javascript" | "js" | "jsx" | "typescript" | "ts" | "tsx" => Some(Language::JavaScript),
+            "go" | "golang" => Some(Language::Go),
+            "sh" | "bash" | "shell" | "zsh" | "console" => Some(Language::Shell),
+            "json" | "jsonc" => Some(Language::Json),
+            _ => None,
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+                "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+                "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+                "unsafe", "use", "where", "while",
+            ],
+            Language::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+                "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+                "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+                "yield",
+            ],
+            Language::JavaScript => &[
+                "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+                "delete", "do", "else", "export", "extends", "finally", "for", "from", "function", "if",
+                "import", "in", "instanceof", "interface", "let", "new", "of", "return", "switch", "this",
+                "throw", "try", "type", "typeof", "var", "void", "while", "yield",
+            ],
+            Language::Go => &[
+                "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+                "for", "func", "go", "goto", "if", "import", "interface", "map", "package", "range",
+                "return", "select", "struct", "switch", "type", "var",
+            ],
+            Language::Shell => &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+                "in", "local", "return", "then", "until", "while",
+            ],
+            Language::Json => &[],
+        }
+    }
+
+    fn constants(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["true", "false", "None", "Some", "Ok", "Err"],
+            Language::Python => &["True", "False", "None"],
+            Language::JavaScript => &["true", "false", "null", "undefined"],
+            Language::Go => &["true", "false", "nil", "iota"],
+            Language::Shell => &["true", "false"],
+            Language::Json => &["true", "false", "null"],
+        }
+    }
+
+    fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Language::Rust | Language::JavaScript | Language::Go => Some("//"),
+            Language::Python | Language::Shell => Some("#"),
+            Language::Json => None,
+        }
+    }
+
+    /// Rust's `'` also starts lifetimes, so only its double quotes delimit strings
+    fn quotes(self) -> &'static [char] {
+        match self {
+            Language::Rust | Language::Json => &['"'],
+            Language::JavaScript | Language::Go => &['"', '\'', '`'],
+            Language::Python | Language::Shell => &['"', '\''],
+        }
+    }
+}
+
+fn keyword_style() -> Style {
+    Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD)
+}
+
+fn string_style() -> Style {
+    Style::default().fg(Color::LightGreen)
+}
+
+fn comment_style() -> Style {
+    Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC)
+}
+
+fn constant_style() -> Style {
+    Style::default().fg(Color::LightCyan)
+}
+
+/// Split one line of code into spans colored by token, patched over `base`
+pub fn highlight_line(line: &str, language: Language, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        let token_end = if language.line_comment().is_some_and(|marker| rest.starts_with(marker)) {
+            push_token(&mut spans, &mut plain, base, rest, base.patch(comment_style()));
+            break;
+        } else if language.quotes().contains(&c) {
+            let end = string_end(rest, c);
+            push_token(&mut spans, &mut plain, base, &rest[..end], base.patch(string_style()));
+            end
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+                .unwrap_or(rest.len());
+            push_token(&mut spans, &mut plain, base, &rest[..end], base.patch(constant_style()));
+            end
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            if language.keywords().contains(&word) {
+                push_token(&mut spans, &mut plain, base, word, base.patch(keyword_style()));
+            } else if language.constants().contains(&word) {
+                push_token(&mut spans, &mut plain, base, word, base.patch(constant_style()));
+            } else {
+                plain.push_str(word);
+            }
+            end
+        } else {
+            plain.push(c);
+            c.len_utf8()
+        };
+        rest = &rest[token_end..];
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+/// Byte length of the string literal opening `text`, through its closing quote or the end of the line
+fn string_end(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return index + c.len_utf8();
+        }
+    }
+    text.len()
+}
+
+/// Emit pending plain text in `base`, then `token` in `style`
+fn push_token(spans: &mut Vec<Span<'static>>, plain: &mut String, base: Style, token: &str, style: Style) {
+    if !plain.is_empty() {
+        spans.push(Span::styled(std::mem::take(plain), base));
+    }
+    spans.push(Span::styled(token.to_string(), style));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled(spans: &[Span<'static>], token: &str) -> Style {
+        spans.iter().find(|span| span.content == token).unwrap_or_else(|| panic!("no span {:?} in {:?}", token, spans)).style
+    }
+
+    #[test]
+    fn rust_keyword_is_colored() {
+        let base = Style::default().bg(Color::DarkGray);
+        let spans = highlight_line("pub fn answer() -> u32 { 42 } // the answer", Language::Rust, base);
+
+        assert_eq!(styled(&spans, "fn").fg, Some(Color::LightMagenta));
+        assert_eq!(styled(&spans, "pub").fg, Some(Color::LightMagenta));
+        assert_eq!(styled(&spans, "42").fg, Some(Color::LightCyan));
+        assert_eq!(styled(&spans, "// the answer").fg, Some(Color::Gray));
+        // Token colors keep the code block's background
+        assert!(spans.iter().all(|span| span.style.bg == Some(Color::DarkGray)));
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "pub fn answer() -> u32 { 42 } // the answer");
+    }
+
+    #[test]
+    fn keywords_inside_strings_and_names_stay_plain() {
+        let spans = highlight_line(r#"let format = "if \"fn\" else";"#, Language::Rust, Style::default());
+        assert_eq!(styled(&spans, r#""if \"fn\" else""#).fg, Some(Color::LightGreen));
+        assert!(spans.iter().all(|span| span.content != "if" && span.content != "for"));
+    }
+
+    #[test]
+    fn fence_tags_map_to_languages() {
+        assert_eq!(Language::from_tag("rs"), Some(Language::Rust));
+        assert_eq!(Language::from_tag("Python title=\"x\""), Some(Language::Python));
+        assert_eq!(Language::from_tag("haskell"), None);
+        assert_eq!(Language::from_tag(""), None);
+    }
+}
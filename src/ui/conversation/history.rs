@@ -1,12 +1,16 @@
 //! Conversation history display component
 
+use crate::config::{Density, SubmitKey};
 use crate::events::{BindrMode, ConversationRole};
+use crate::pricing::{self, TurnCost};
+use crate::ui::conversation::markdown::render_markdown;
+use crate::ui::conversation::wrap::wrap_text;
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::{Color, Style},
+    layout::{Margin, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget},
+    widgets::{Block, Borders, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget},
 };
 use std::collections::VecDeque;
 
@@ -17,16 +21,45 @@ pub struct ConversationMessage {
     pub content: String,
     pub mode: BindrMode,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Marked with /flag for later review
+    pub flagged: bool,
+    pub flag_note: Option<String>,
+    /// Thinking the model streamed ahead of the reply
+    pub reasoning: Option<String>,
+    /// Show the reasoning in full instead of a one-line summary
+    pub reasoning_expanded: bool,
+    /// Tokens and estimated cost of the turn that produced a reply
+    pub cost: Option<TurnCost>,
+    /// Model a reply was requested from, shown in its header
+    pub model_id: Option<String>,
 }
 
 /// Conversation history display component
 #[derive(Clone)]
 pub struct ConversationHistory {
     messages: VecDeque<ConversationMessage>,
-    #[allow(dead_code)]
     scroll_state: ScrollbarState,
     max_messages: usize,
     streaming_message: Option<String>,
+    streaming_reasoning: String,
+    /// Shown in place of the thinking indicator, e.g. while a busy provider is retried
+    streaming_status: Option<String>,
+    /// Show model reasoning, streaming and collapsed under replies
+    show_reasoning: bool,
+    reduced_motion: bool,
+    /// Color tokens in fenced code blocks of assistant replies
+    syntax_highlighting: bool,
+    density: Density,
+    has_focus: bool,
+    submit_key: SubmitKey,
+    /// Message picked with the arrow keys while the history is focused
+    cursor: Option<usize>,
+    /// Lines scrolled up from the bottom; 0 follows new messages
+    scroll_offset: usize,
+    /// Largest offset the last render allowed
+    max_scroll: usize,
+    /// Rendered line count at the last render, so a scrolled-up view stays put as lines are added
+    rendered_lines: usize,
 }
 
 impl ConversationHistory {
@@ -36,6 +69,18 @@ impl ConversationHistory {
             scroll_state: ScrollbarState::default(),
             max_messages,
             streaming_message: None,
+            streaming_reasoning: String::new(),
+            streaming_status: None,
+            show_reasoning: true,
+            reduced_motion: false,
+            syntax_highlighting: true,
+            density: Density::default(),
+            has_focus: false,
+            submit_key: SubmitKey::default(),
+            cursor: None,
+            scroll_offset: 0,
+            max_scroll: 0,
+            rendered_lines: 0,
         }
     }
 
@@ -46,10 +91,8 @@ impl ConversationHistory {
         // Limit message count
         if self.messages.len() > self.max_messages {
             self.messages.pop_front();
+            self.cursor = self.cursor.and_then(|index| index.checked_sub(1));
         }
-        
-        // Auto-scroll to bottom
-        self.scroll_to_bottom();
     }
 
     /// Add a user message
@@ -59,17 +102,41 @@ impl ConversationHistory {
             content,
             mode,
             timestamp: chrono::Utc::now(),
+            flagged: false,
+            flag_note: None,
+            reasoning: None,
+            reasoning_expanded: false,
+            cost: None,
+            model_id: None,
         };
         self.add_message(message);
     }
 
     /// Add an assistant message
     pub fn add_assistant_message(&mut self, content: String, mode: BindrMode) {
+        self.add_assistant_reply(content, None, None, None, mode);
+    }
+
+    /// Add an assistant message along with the reasoning streamed before it, what the turn cost, and the model that wrote it
+    pub fn add_assistant_reply(
+        &mut self,
+        content: String,
+        reasoning: Option<String>,
+        cost: Option<TurnCost>,
+        model_id: Option<String>,
+        mode: BindrMode,
+    ) {
         let message = ConversationMessage {
             role: ConversationRole::Assistant,
             content,
             mode,
             timestamp: chrono::Utc::now(),
+            flagged: false,
+            flag_note: None,
+            reasoning: reasoning.filter(|reasoning| !reasoning.trim().is_empty()),
+            reasoning_expanded: false,
+            cost,
+            model_id,
         };
         self.add_message(message);
     }
@@ -81,32 +148,76 @@ impl ConversationHistory {
             content,
             mode,
             timestamp: chrono::Utc::now(),
+            flagged: false,
+            flag_note: None,
+            reasoning: None,
+            reasoning_expanded: false,
+            cost: None,
+            model_id: None,
         };
         self.add_message(message);
     }
 
-    /// Scroll up
-    #[allow(dead_code)]
-    pub fn scroll_up(&mut self) {
-        // TODO: Implement proper scrolling
+    /// Scroll up by `lines`, stopping at the first line
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset = (self.scroll_offset + lines).min(self.max_scroll);
     }
 
-    /// Scroll down
-    #[allow(dead_code)]
-    pub fn scroll_down(&mut self) {
-        // TODO: Implement proper scrolling
+    /// Scroll down by `lines`; reaching the bottom follows new messages again
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
     }
 
     /// Scroll to bottom
     pub fn scroll_to_bottom(&mut self) {
-        // TODO: Implement proper scrolling
+        self.scroll_offset = 0;
+    }
+
+    /// Messages in the history, oldest first
+    pub fn messages(&self) -> impl Iterator<Item = &ConversationMessage> {
+        self.messages.iter()
+    }
+
+    /// Transcript of the conversation as Markdown, one section per message
+    pub fn to_markdown(&self, title: &str) -> String {
+        let mut output = format!(
+            "# {}\n\nExported {}\n",
+            title,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        for message in &self.messages {
+            let role = match message.role {
+                ConversationRole::User => "User",
+                ConversationRole::Assistant => "Assistant",
+                ConversationRole::System => "System",
+            };
+            output.push_str(&format!(
+                "\n## {} {} · {} {} · {}\n",
+                role_icon(&message.role),
+                role,
+                mode_icon(message.mode),
+                message.mode.display_name(),
+                message.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+            ));
+            if message.flagged {
+                match &message.flag_note {
+                    Some(note) => output.push_str(&format!("\n> 🚩 {}\n", note)),
+                    None => output.push_str("\n> 🚩 Flagged\n"),
+                }
+            }
+            output.push('\n');
+            output.push_str(message.content.trim_end());
+            output.push('\n');
+        }
+        output
     }
 
     /// Clear all messages
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.messages.clear();
+        self.scroll_offset = 0;
         self.scroll_state = ScrollbarState::default();
+        self.cursor = None;
     }
 
     /// Get message count
@@ -115,22 +226,160 @@ impl ConversationHistory {
         self.messages.len()
     }
 
+    /// Content of the most recent assistant message, if any
+    pub fn last_assistant_message(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|message| matches!(message.role, ConversationRole::Assistant))
+            .map(|message| message.content.as_str())
+    }
+
+    /// Index of the most recent assistant message, if any
+    pub fn last_assistant_index(&self) -> Option<usize> {
+        self.messages
+            .iter()
+            .rposition(|message| matches!(message.role, ConversationRole::Assistant))
+    }
+
+    /// Move the message cursor towards older messages, starting from the newest
+    pub fn cursor_up(&mut self) {
+        self.cursor = match self.cursor {
+            Some(index) => Some(index.saturating_sub(1)),
+            None => self.messages.len().checked_sub(1),
+        };
+    }
+
+    /// Move the message cursor towards newer messages
+    pub fn cursor_down(&mut self) {
+        if let Some(index) = self.cursor
+            && index + 1 < self.messages.len()
+        {
+            self.cursor = Some(index + 1);
+        }
+    }
+
+    pub fn set_cursor(&mut self, cursor: Option<usize>) {
+        self.cursor = cursor.filter(|&index| index < self.messages.len());
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Index of the message sent at this exact time, if it is still shown
+    pub fn find_message(&self, timestamp: chrono::DateTime<chrono::Utc>) -> Option<usize> {
+        self.messages.iter().position(|message| message.timestamp == timestamp)
+    }
+
+    /// Flag a message, update the note on a flagged one, or clear the flag when no note is given
+    pub fn toggle_flag(&mut self, index: usize, note: Option<String>) -> Option<ConversationMessage> {
+        let message = self.messages.get_mut(index)?;
+        match (message.flagged, note) {
+            (true, None) => {
+                message.flagged = false;
+                message.flag_note = None;
+            }
+            (_, note) => {
+                message.flagged = true;
+                message.flag_note = note;
+            }
+        }
+        Some(message.clone())
+    }
+
+    /// Expand or collapse a message's reasoning
+    pub fn toggle_reasoning(&mut self, index: usize) {
+        if let Some(message) = self.messages.get_mut(index) {
+            message.reasoning_expanded = !message.reasoning_expanded;
+        }
+    }
+
+    pub fn set_show_reasoning(&mut self, show_reasoning: bool) {
+        self.show_reasoning = show_reasoning;
+    }
+
+    /// Render the in-progress message without a moving cursor
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+    }
+
+    /// Choose between the decorated and compact message layouts
+    pub fn set_syntax_highlighting(&mut self, syntax_highlighting: bool) {
+        self.syntax_highlighting = syntax_highlighting;
+    }
+
+    /// Change how many messages are kept, dropping the oldest beyond the new limit
+    pub fn set_max_messages(&mut self, max_messages: usize) {
+        self.max_messages = max_messages.max(1);
+        while self.messages.len() > self.max_messages {
+            self.messages.pop_front();
+            self.cursor = self.cursor.and_then(|index| index.checked_sub(1));
+        }
+    }
+
+    pub fn set_density(&mut self, density: Density) {
+        self.density = density;
+    }
+
+    pub fn density(&self) -> Density {
+        self.density
+    }
+
+    /// Keep the welcome hint in line with the composer's submit key
+    pub fn set_submit_key(&mut self, submit_key: SubmitKey) {
+        self.submit_key = submit_key;
+    }
+
+    /// Highlight the border while arrow and page keys scroll the history
+    pub fn set_focus(&mut self, has_focus: bool) {
+        self.has_focus = has_focus;
+    }
+
     /// Set the current streaming message
     pub fn set_streaming_message(&mut self, message: String) {
         self.streaming_message = Some(message);
     }
 
+    /// Set the reasoning shown above the in-progress reply
+    pub fn set_streaming_reasoning(&mut self, reasoning: String) {
+        self.streaming_reasoning = reasoning;
+    }
+
+    /// Set the note shown while no reply text has arrived yet
+    pub fn set_streaming_status(&mut self, status: Option<String>) {
+        self.streaming_status = status;
+    }
+
     /// Clear the streaming message
     pub fn clear_streaming_message(&mut self) {
         self.streaming_message = None;
+        self.streaming_reasoning.clear();
+        self.streaming_status = None;
     }
 }
 
 impl Widget for ConversationHistory {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        (&mut self).render(area, buf);
+    }
+}
+
+/// Rendering by reference records how far the view can scroll
+impl Widget for &mut ConversationHistory {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let block = Block::default()
             .borders(Borders::ALL)
-            .title("💬 Conversation History");
+            .title(if self.scroll_offset > 0 {
+                "💬 Conversation History (scrolled up, PgDn for newer)"
+            } else {
+                "💬 Conversation History"
+            })
+            .border_style(if self.has_focus {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::Gray)
+            });
 
         let inner_area = block.inner(area);
         block.render(area, buf);
@@ -142,7 +391,7 @@ impl Widget for ConversationHistory {
                 Line::from(vec![Span::raw("")]),
                 Line::from(vec![Span::styled("Start by sharing your ideas below.", Style::default().fg(Color::Gray))]),
                 Line::from(vec![Span::raw("")]),
-                Line::from(vec![Span::styled("Press Enter to send, Shift+Enter for new line.", Style::default().fg(Color::DarkGray))]),
+                Line::from(vec![Span::styled(self.submit_key.hint(), Style::default().fg(Color::DarkGray))]),
             ];
 
             for (i, line) in welcome_lines.iter().enumerate() {
@@ -153,11 +402,22 @@ impl Widget for ConversationHistory {
         } else {
             // Collect all lines for messages (including streaming if any)
             let mut all_lines: Vec<Line> = Vec::new();
-            for message in self.messages.iter() {
+            let mut cursor_start = None;
+            for (index, message) in self.messages.iter().enumerate() {
                 let mut lines = self.render_message(message, inner_area.width);
+                if self.cursor == Some(index) {
+                    cursor_start = Some(all_lines.len());
+                    if let Some(first) = lines.first_mut() {
+                        for span in first.spans.iter_mut() {
+                            span.style = span.style.add_modifier(Modifier::REVERSED);
+                        }
+                    }
+                }
                 all_lines.append(&mut lines);
                 // spacing between messages
-                all_lines.push(Line::from(vec![Span::raw("")]))
+                if self.density == Density::Comfortable {
+                    all_lines.push(Line::from(vec![Span::raw("")]))
+                }
             }
 
             if let Some(ref streaming_text) = self.streaming_message {
@@ -165,96 +425,163 @@ impl Widget for ConversationHistory {
                 all_lines.append(&mut streaming_lines);
             }
 
-            // Determine the range of lines to display from the bottom
             let height = inner_area.height as usize;
             let total = all_lines.len();
-            let start = total.saturating_sub(height);
-            let visible = &all_lines[start..];
+            let mut offset = self.scroll_offset;
+            if offset > 0 {
+                // Keep the same lines in view while a reply streams in below them
+                offset += total.saturating_sub(self.rendered_lines);
+            }
+            let mut start = visible_start(total, height, offset);
+            // Keep the message under the cursor on screen
+            if let Some(cursor_start) = cursor_start {
+                start = start.min(cursor_start).max((cursor_start + 1).saturating_sub(height));
+            }
 
-            for (i, line) in visible.iter().enumerate() {
+            for (i, line) in all_lines[start..].iter().take(height).enumerate() {
                 buf.set_line(inner_area.x, inner_area.y + i as u16, line, inner_area.width);
             }
+
+            drop(all_lines);
+            self.max_scroll = total.saturating_sub(height);
+            self.scroll_offset = self.max_scroll - start.min(self.max_scroll);
+            self.rendered_lines = total;
+
+            // Drawn over the block's right border, and only when there is something to scroll
+            if self.max_scroll > 0 {
+                self.scroll_state = ScrollbarState::new(self.max_scroll + 1)
+                    .viewport_content_length(height)
+                    .position(start);
+                Scrollbar::default()
+                    .orientation(ScrollbarOrientation::VerticalRight)
+                    .begin_symbol(Some("↑"))
+                    .end_symbol(Some("↓"))
+                    .render(area.inner(&Margin { vertical: 1, horizontal: 0 }), buf, &mut self.scroll_state);
+            }
         }
+    }
+}
 
-        // Render scrollbar placeholder
-        let _scrollbar = Scrollbar::default()
-            .orientation(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("↑"))
-            .end_symbol(Some("↓"));
+/// First of the `height` lines shown when the view is `offset` lines up from the bottom of `total`
+fn visible_start(total: usize, height: usize, offset: usize) -> usize {
+    let bottom = total.saturating_sub(height);
+    bottom - offset.min(bottom)
+}
+
+fn role_icon(role: &ConversationRole) -> &'static str {
+    match role {
+        ConversationRole::User => "👤",
+        ConversationRole::Assistant => "🤖",
+        ConversationRole::System => "⚙️",
     }
 }
 
+fn mode_icon(mode: BindrMode) -> &'static str {
+    match mode {
+        BindrMode::Brainstorm => "💡",
+        BindrMode::Plan => "📋",
+        BindrMode::Execute => "⚡",
+        BindrMode::Document => "📝",
+    }
+}
+
+/// Reasoning text wrapped under a 💭 marker, dimmed so it reads as an aside
+fn reasoning_lines(reasoning: &str, width: u16) -> Vec<Line<'static>> {
+    let style = Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC);
+    let prefix = "  💭 ";
+    let indent = " ".repeat(prefix.chars().count());
+    let content_lines = wrap_text(reasoning.trim(), (width as usize).saturating_sub(indent.len() + 1))
+        .into_iter()
+        .map(|line| Line::from(Span::styled(line, style)))
+        .collect();
+    with_prefix(prefix, &indent, content_lines)
+}
+
+/// Put `prefix` before the first line and `indent` before the rest
+fn with_prefix(prefix: &str, indent: &str, lines: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut line)| {
+            let lead = if i == 0 { prefix } else { indent };
+            line.spans.insert(0, Span::styled(lead.to_string(), Style::default().fg(Color::DarkGray)));
+            line
+        })
+        .collect()
+}
+
 impl ConversationHistory {
     /// Render a single message into lines
     fn render_message(&self, message: &ConversationMessage, width: u16) -> Vec<Line> {
         let mut lines = Vec::new();
         
         // Message header with role and timestamp
-        let role_icon = match message.role {
-            ConversationRole::User => "👤",
-            ConversationRole::Assistant => "🤖",
-            ConversationRole::System => "⚙️",
-        };
+        let role_icon = role_icon(&message.role);
+        let mode_text = mode_icon(message.mode);
         
-        let mode_text = match message.mode {
-            BindrMode::Brainstorm => "💡",
-            BindrMode::Plan => "📋",
-            BindrMode::Execute => "⚡",
-            BindrMode::Document => "📝",
-        };
+        if self.density == Density::Compact {
+            let flag = if message.flagged { "🚩" } else { "" };
+            let prefix = format!("{}{} {} ", flag, role_icon, message.timestamp.format("%H:%M"));
+            let mut lines = self.message_reasoning(message, width);
+            lines.extend(self.render_compact(&prefix, &message.role, &message.content, width));
+            return lines;
+        }
         
         let timestamp = message.timestamp.format("%H:%M:%S").to_string();
-        let header = format!("{} {} {} {}", role_icon, mode_text, timestamp, "─".repeat(20));
+        let mut header = format!("{} {} {}", role_icon, mode_text, timestamp);
+        if let Some(model_id) = &message.model_id {
+            header.push_str(&format!(" · {}", model_id));
+        }
+        header.push_str(&format!(" {}", "─".repeat(20)));
+        if let Some(cost) = &message.cost {
+            header.push_str(&format!(
+                " {}{}→{} tokens",
+                if cost.exact { "" } else { "~" },
+                pricing::format_tokens(cost.usage.input_tokens),
+                pricing::format_tokens(cost.usage.output_tokens)
+            ));
+            if let Some(usd) = cost.cost {
+                header.push_str(&format!(" {}", pricing::format_cost(usd)));
+            }
+        }
+        if message.flagged {
+            header.push_str(" 🚩");
+            if let Some(note) = &message.flag_note {
+                header.push_str(&format!(" {}", note));
+            }
+        }
         
         lines.push(Line::from(vec![
             Span::styled(header, Style::default().fg(Color::DarkGray)),
         ]));
+        lines.extend(self.message_reasoning(message, width));
         
         // Message content
-        let content_lines = self.wrap_text(&message.content, width.saturating_sub(2) as usize);
-        for content_line in content_lines {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(content_line, self.get_content_style(&message.role)),
-            ]));
+        for mut content_line in self.content_lines(&message.role, &message.content, width.saturating_sub(2) as usize) {
+            content_line.spans.insert(0, Span::raw("  "));
+            lines.push(content_line);
         }
         
         lines
     }
 
-    /// Wrap text to fit within the given width
-    fn wrap_text(&self, text: &str, width: usize) -> Vec<String> {
-        if width == 0 {
-            return vec![text.to_string()];
-        }
-        
-        let mut lines = Vec::new();
-        let mut current_line = String::new();
-        
-        for word in text.split_whitespace() {
-            if current_line.len() + word.len() + 1 <= width {
-                if !current_line.is_empty() {
-                    current_line.push(' ');
-                }
-                current_line.push_str(word);
-            } else {
-                if !current_line.is_empty() {
-                    lines.push(current_line);
-                    current_line = String::new();
-                }
-                current_line.push_str(word);
-            }
-        }
-        
-        if !current_line.is_empty() {
-            lines.push(current_line);
-        }
-        
-        if lines.is_empty() {
-            lines.push(String::new());
+    /// Message text wrapped to `width`, with Markdown styling for assistant replies
+    fn content_lines(&self, role: &ConversationRole, text: &str, width: usize) -> Vec<Line<'static>> {
+        let style = self.get_content_style(role);
+        match role {
+            ConversationRole::Assistant => render_markdown(text, width, style, self.syntax_highlighting),
+            _ => wrap_text(text, width)
+                .into_iter()
+                .map(|line| Line::from(Span::styled(line, style)))
+                .collect(),
         }
-        
-        lines
+    }
+
+    /// Render content with an inline prefix on its first line and no header
+    fn render_compact(&self, prefix: &str, role: &ConversationRole, text: &str, width: u16) -> Vec<Line<'static>> {
+        let indent = " ".repeat(prefix.chars().count());
+        let content_lines = self.content_lines(role, text, (width as usize).saturating_sub(indent.len() + 1));
+        with_prefix(prefix, &indent, content_lines)
     }
 
     /// Get content style based on role
@@ -270,6 +597,20 @@ impl ConversationHistory {
     fn render_streaming_message(&self, text: &str, width: u16) -> Vec<Line> {
         let mut lines = Vec::new();
         
+        if self.density == Density::Compact {
+            let prefix = format!("🤖 {} ", chrono::Utc::now().format("%H:%M"));
+            let mut lines = self.render_streaming_reasoning(width);
+            if text.is_empty() {
+                lines.push(self.thinking_indicator(&prefix));
+                return lines;
+            }
+            lines.extend(self.render_compact(&prefix, &ConversationRole::Assistant, text, width));
+            if !self.reduced_motion && let Some(last) = lines.last_mut() {
+                last.spans.push(Span::styled("▋", Style::default().fg(Color::Yellow)));
+            }
+            return lines;
+        }
+        
         // Streaming message header
         let timestamp = chrono::Utc::now().format("%H:%M:%S").to_string();
         let header = format!("🤖 💡 {} {}", timestamp, "─".repeat(20));
@@ -277,20 +618,238 @@ impl ConversationHistory {
         lines.push(Line::from(vec![
             Span::styled(header, Style::default().fg(Color::DarkGray)),
         ]));
+        lines.extend(self.render_streaming_reasoning(width));
+        
+        if text.is_empty() {
+            lines.push(self.thinking_indicator("  "));
+            return lines;
+        }
         
         // Streaming content with cursor
-        let content_lines = self.wrap_text(text, width.saturating_sub(2) as usize);
-        for (i, content_line) in content_lines.iter().enumerate() {
-            let is_last_line = i == content_lines.len() - 1;
-            let cursor = if is_last_line { "▋" } else { "" };
-            
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(content_line.clone(), Style::default().fg(Color::Green)),
-                Span::styled(cursor, Style::default().fg(Color::Yellow)),
-            ]));
+        for mut content_line in self.content_lines(&ConversationRole::Assistant, text, width.saturating_sub(2) as usize) {
+            content_line.spans.insert(0, Span::raw("  "));
+            lines.push(content_line);
+        }
+        if !self.reduced_motion && let Some(last) = lines.last_mut() {
+            last.spans.push(Span::styled("▋", Style::default().fg(Color::Yellow)));
         }
         
         lines
     }
+
+    /// Reasoning streamed ahead of the reply, dimmed so it reads as an aside
+    fn render_streaming_reasoning(&self, width: u16) -> Vec<Line<'static>> {
+        if !self.show_reasoning || self.streaming_reasoning.trim().is_empty() {
+            return Vec::new();
+        }
+        reasoning_lines(&self.streaming_reasoning, width)
+    }
+
+    /// A finished reply's reasoning: one summary line until expanded with `r`
+    fn message_reasoning(&self, message: &ConversationMessage, width: u16) -> Vec<Line<'static>> {
+        let Some(reasoning) = message.reasoning.as_deref().filter(|_| self.show_reasoning) else {
+            return Vec::new();
+        };
+        if message.reasoning_expanded {
+            return reasoning_lines(reasoning, width);
+        }
+        let summary = format!(
+            "  💭 Reasoning ({} lines) · select and press r to expand",
+            reasoning.trim().lines().count()
+        );
+        vec![Line::from(Span::styled(
+            summary,
+            Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        ))]
+    }
+
+    /// Shown until the first reply text arrives
+    fn thinking_indicator(&self, prefix: &str) -> Line<'static> {
+        let dots = if self.reduced_motion {
+            "..."
+        } else {
+            match (std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() / 300) % 4 {
+                0 => ".",
+                1 => "..",
+                2 => "...",
+                _ => "",
+            }
+        };
+        Line::from(vec![
+            Span::styled(prefix.to_string(), Style::default().fg(Color::DarkGray)),
+            match &self.streaming_status {
+                Some(status) => Span::styled(status.clone(), Style::default().fg(Color::Yellow)),
+                None => Span::styled("Bindr is thinking", Style::default().fg(Color::Green)),
+            },
+            Span::styled(dots, Style::default().fg(Color::Yellow)),
+            Span::styled("  Esc to stop", Style::default().fg(Color::DarkGray)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compact history of one-line messages "message 0" to "message {count - 1}"
+    fn history(count: usize) -> ConversationHistory {
+        let mut history = ConversationHistory::new(100);
+        history.set_density(Density::Compact);
+        for n in 0..count {
+            history.add_user_message(format!("message {}", n), BindrMode::Brainstorm);
+        }
+        history
+    }
+
+    /// Render into a 40-column area with `height` rows inside the border
+    fn render(history: &mut ConversationHistory, height: u16) -> Buffer {
+        let area = Rect::new(0, 0, 40, height + 2);
+        let mut buf = Buffer::empty(area);
+        history.render(area, &mut buf);
+        buf
+    }
+
+    fn visible_messages(buf: &Buffer) -> Vec<usize> {
+        let area = buf.area;
+        (1..area.height - 1)
+            .filter_map(|y| {
+                let row: String = (1..area.width - 1).map(|x| buf.get(x, y).symbol()).collect();
+                row.split("message ").nth(1)?.split_whitespace().next()?.parse().ok()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn visible_slice_follows_the_offset() {
+        let mut history = history(10);
+        let buf = render(&mut history, 4);
+        assert_eq!(visible_messages(&buf), [6, 7, 8, 9]);
+
+        history.scroll_up(2);
+        assert_eq!(visible_messages(&render(&mut history, 4)), [4, 5, 6, 7]);
+
+        // Scrolling stops at the first line
+        history.scroll_up(100);
+        assert_eq!(visible_messages(&render(&mut history, 4)), [0, 1, 2, 3]);
+
+        history.scroll_down(3);
+        assert_eq!(visible_messages(&render(&mut history, 4)), [3, 4, 5, 6]);
+
+        history.scroll_to_bottom();
+        assert_eq!(visible_messages(&render(&mut history, 4)), [6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn scrolled_up_view_stays_put_as_messages_arrive() {
+        let mut history = history(10);
+        render(&mut history, 4);
+        history.scroll_up(2);
+        render(&mut history, 4);
+
+        history.add_user_message("message 10".to_string(), BindrMode::Brainstorm);
+        assert_eq!(visible_messages(&render(&mut history, 4)), [4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn visible_start_clamps() {
+        assert_eq!(visible_start(10, 4, 0), 6);
+        assert_eq!(visible_start(10, 4, 2), 4);
+        assert_eq!(visible_start(10, 4, 50), 0);
+        assert_eq!(visible_start(3, 4, 1), 0);
+    }
+
+    /// Rows of the right gutter, top to bottom, between the corners
+    fn gutter(buf: &Buffer) -> Vec<String> {
+        let x = buf.area.width - 1;
+        (1..buf.area.height - 1).map(|y| buf.get(x, y).symbol().to_string()).collect()
+    }
+
+    fn thumb_rows(buf: &Buffer) -> Vec<usize> {
+        gutter(buf).iter().enumerate().filter(|(_, symbol)| *symbol == "█").map(|(row, _)| row).collect()
+    }
+
+    #[test]
+    fn scrollbar_thumb_tracks_the_offset() {
+        let mut history = history(30);
+        let bottom = render(&mut history, 8);
+        let gutter = gutter(&bottom);
+        assert_eq!((gutter[0].as_str(), gutter[7].as_str()), ("↑", "↓"));
+        let at_bottom = thumb_rows(&bottom);
+        assert_eq!(at_bottom.last(), Some(&6));
+
+        history.scroll_up(100);
+        let at_top = thumb_rows(&render(&mut history, 8));
+        assert_eq!(at_top.first(), Some(&1));
+        assert_eq!(at_top.len(), at_bottom.len());
+
+        history.scroll_down(11);
+        let middle = thumb_rows(&render(&mut history, 8));
+        assert!(middle[0] > at_top[0] && middle[0] < at_bottom[0], "{:?}", middle);
+    }
+
+    #[test]
+    fn scrollbar_is_hidden_when_everything_fits() {
+        let mut history = history(3);
+        let buf = render(&mut history, 8);
+        assert!(gutter(&buf).iter().all(|symbol| symbol == "│"));
+    }
+
+    fn text(buf: &Buffer) -> String {
+        let area = buf.area;
+        (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf.get(x, y).symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn reasoning_is_collapsed_until_expanded_and_hidden_when_turned_off() {
+        let mut history = ConversationHistory::new(100);
+        history.set_density(Density::Compact);
+        let reasoning = "Light scatters.\nShort waves most.".to_string();
+        history.add_assistant_reply("Blue.".to_string(), Some(reasoning), None, None, BindrMode::Brainstorm);
+
+        let collapsed = text(&render(&mut history, 8));
+        assert!(collapsed.contains("Reasoning (2 lines)"));
+        assert!(!collapsed.contains("Short waves most."));
+
+        history.toggle_reasoning(0);
+        let expanded = text(&render(&mut history, 8));
+        assert!(expanded.contains("Light scatters."));
+        assert!(expanded.contains("Short waves most."));
+
+        history.set_show_reasoning(false);
+        let hidden = text(&render(&mut history, 8));
+        assert!(!hidden.contains("Reasoning") && !hidden.contains("Light scatters."));
+        assert!(hidden.contains("Blue."));
+    }
+
+    fn contents(history: &ConversationHistory) -> Vec<String> {
+        history.messages().map(|message| message.content.clone()).collect()
+    }
+
+    #[test]
+    fn messages_beyond_the_cap_drop_the_oldest() {
+        let mut history = ConversationHistory::new(10);
+        for n in 0..15 {
+            history.add_user_message(format!("message {}", n), BindrMode::Brainstorm);
+        }
+
+        let expected: Vec<String> = (5..15).map(|n| format!("message {}", n)).collect();
+        assert_eq!(contents(&history), expected);
+    }
+
+    #[test]
+    fn lowering_the_cap_trims_the_oldest() {
+        let mut history = history(10);
+        history.set_max_messages(3);
+        assert_eq!(contents(&history), ["message 7", "message 8", "message 9"]);
+
+        // A cap of zero still keeps the latest message
+        history.set_max_messages(0);
+        assert_eq!(contents(&history), ["message 9"]);
+    }
 }
@@ -1,16 +1,32 @@
 use crate::agent::AgentManager;
-use crate::config::Config;
-use crate::events::BindrMode;
-use crate::llm::LlmClient;
-use crate::ui::conversation::{ConversationComposer, ConversationHistory, StreamingResponse, SlashCommand, ParsedCommand, get_help_text};
-use anyhow::Result;
+use crate::config::{Config, MAX_TOKENS_LIMIT};
+use crate::events::{BindrMode, ConversationEntry, ConversationRole, FlaggedMessage};
+use crate::llm::{LlmClient, LlmEvent};
+use crate::loop_guard;
+use crate::persist::write_atomic;
+use crate::pricing;
+use crate::prompts;
+use crate::session::{AutoSave, LastSession};
+use crate::stats::ProjectStats;
+use crate::tools::{self, diff};
+use crate::tools::files::{self, FileContents};
+use crate::tools::{BindrTool, ReadFileOptions, ToolDispatcher, ToolExecutor, ToolInvocation, ToolNotPermitted, ToolOutput, ToolRequestOutcome, ToolRunner};
+use crate::ui::conversation::{ConversationComposer, ConversationHistory, DiffDecision, DiffView, SlashCommand, ParsedCommand, get_help_text};
+use crate::ui::conversation::commands::CommandUsage;
+use anyhow::{bail, Context, Result};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect, Direction},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::Widget,
 };
+use std::path::PathBuf;
 use tokio::sync::mpsc;
 
+/// Lines the history scrolls for PageUp/PageDown and Ctrl+U/Ctrl+D
+const HISTORY_PAGE_LINES: usize = 10;
+
 /// Actions that can be requested by the conversation manager
 #[derive(Debug, Clone)]
 pub enum ConversationAction {
@@ -18,47 +34,180 @@ pub enum ConversationAction {
     GoHome,
     Exit,
     ShowModelSelection,
+    /// Pick a provider, then a model, and come back to the conversation
+    ShowProviderSelection,
+    /// Make this provider (which has a key) the selected one
+    SwitchProvider(String),
+    /// Select this model of this provider (which has a key)
+    SwitchModel { provider_id: String, model_id: String },
+    /// Open the form for adding an OpenAI-compatible provider
+    ShowAddProvider,
+    /// Delete this custom provider from the config
+    RemoveProvider(String),
+    /// Browse every configured provider and model
+    ShowModelCatalog,
+    /// Jump to the add-key flow for a provider whose key was rejected
+    UpdateApiKey(String),
+    /// Suspend the TUI and open a file in an external program
+    OpenExternal { program: String, path: PathBuf },
+    /// The workspace root used by tools changed
+    WorkspaceChanged(PathBuf),
+}
+
+/// Progress of an /auto run through an Execute checklist
+#[derive(Debug, Clone, Copy)]
+struct AutoContinue {
+    steps: usize,
+    /// The last step finished cleanly and the next one can start
+    due: bool,
+}
+
+/// File read with /attach, waiting to go out with the next message
+#[derive(Debug, Clone)]
+struct Attachment {
+    /// Workspace-relative path shown on the chip
+    path: String,
+    contents: String,
+}
+
+/// Pane that receives arrow and page keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusTarget {
+    Composer,
+    History,
 }
 
 /// Manages the conversation flow and UI components
 pub struct ConversationManager {
     history: ConversationHistory,
     composer: ConversationComposer,
-    streaming: StreamingResponse,
     agent_manager: AgentManager,
     #[allow(dead_code)]
     llm_client: LlmClient,
+    config: Config,
+    command_usage: CommandUsage,
     current_mode: BindrMode,
     is_active: bool,
-    stream_receiver: Option<mpsc::UnboundedReceiver<String>>,
+    stream_receiver: Option<mpsc::UnboundedReceiver<LlmEvent>>,
     current_streaming_message: String,
+    /// Reasoning streamed alongside the reply; shown while streaming, never recorded
+    current_reasoning: String,
+    /// Whether the in-flight response ended in an error rather than a reply
+    stream_failed: bool,
+    /// Most recent provider error, offered to /explain
+    last_error: Option<String>,
+    /// Estimated input tokens of the in-flight turn
+    turn_input_estimate: usize,
+    /// Token counts the provider reported for the in-flight turn
+    reported_usage: pricing::TokenUsage,
+    /// Tokens and cost of every reply this run, shown in the status bar
+    session_cost: pricing::SessionCost,
+    /// Expensive draft the user has been warned about; sending it again confirms
+    pending_cost_confirmation: Option<String>,
+    /// Provider whose add-key screen Ctrl+K opens: one that rejected its key, or lacks one
+    key_prompt_provider: Option<String>,
+    /// File changes from the assistant awaiting review with /diff
+    pending_changes: Vec<ToolInvocation>,
+    /// Open review of `pending_changes`, shown in place of the history
+    diff_view: Option<DiffView>,
+    /// Tool call waiting for the user to press y or n before it runs
+    pending_tool: Option<ToolRequestOutcome>,
+    /// Tool call found in the last reply, reviewed on the next main-loop pass
+    requested_tool: Option<ToolInvocation>,
+    /// Runs approved tools in the workspace
+    tool_runner: Box<dyn ToolRunner>,
+    /// Tool results sent back to the model without the user stepping in since their last message
+    tool_round_trips: usize,
+    focus: FocusTarget,
+    auto_save: AutoSave,
+    /// When auto-save last wrote the project, shown in the status bar
+    last_auto_save: Option<chrono::DateTime<chrono::Local>>,
+    auto_continue: Option<AutoContinue>,
+    attachments: Vec<Attachment>,
 }
 
 impl ConversationManager {
-    pub fn new(agent_manager: AgentManager, llm_client: LlmClient, mode: BindrMode) -> Self {
+    pub fn new(config: Config, agent_manager: AgentManager, llm_client: LlmClient, mode: BindrMode) -> Self {
         let placeholder = Self::get_mode_placeholder(mode);
+        let command_usage = CommandUsage::load(&config.bindr_home);
+        let mut composer = ConversationComposer::new(placeholder, mode);
+        composer.set_command_usage(command_usage.clone());
+        composer.set_submit_key(config.ui.submit_key);
+        let mut history = ConversationHistory::new(config.ui.max_history_lines);
+        history.set_reduced_motion(config.ui.reduced_motion);
+        history.set_syntax_highlighting(config.ui.syntax_highlighting);
+        history.set_show_reasoning(config.ui.show_reasoning);
+        history.set_density(config.ui.density);
+        history.set_submit_key(config.ui.submit_key);
+        let auto_save = AutoSave::new(config.ui.auto_save_interval, std::time::Instant::now());
+        let tool_runner = Box::new(ToolExecutor::new(&config.cwd));
         
         Self {
-            history: ConversationHistory::new(100),
-            composer: ConversationComposer::new(placeholder, mode),
-            streaming: StreamingResponse::new(mode),
+            history,
+            composer,
             agent_manager,
             llm_client,
+            config,
+            command_usage,
             current_mode: mode,
             is_active: false,
             stream_receiver: None,
             current_streaming_message: String::new(),
+            current_reasoning: String::new(),
+            stream_failed: false,
+            last_error: None,
+            turn_input_estimate: 0,
+            reported_usage: pricing::TokenUsage::default(),
+            session_cost: pricing::SessionCost::default(),
+            pending_cost_confirmation: None,
+            key_prompt_provider: None,
+            pending_changes: Vec::new(),
+            diff_view: None,
+            pending_tool: None,
+            requested_tool: None,
+            tool_runner,
+            tool_round_trips: 0,
+            focus: FocusTarget::Composer,
+            auto_save,
+            last_auto_save: None,
+            auto_continue: None,
+            attachments: Vec::new(),
         }
     }
 
     /// Start a new conversation
     pub fn start_conversation(&mut self) {
         self.is_active = true;
-        self.composer.set_focus(true);
+        self.set_focus(true);
         self.history.add_system_message(
-            format!("Started {} mode", self.current_mode.display_name()),
+            format!(
+                "Started {} mode. {}",
+                self.current_mode.display_name(),
+                self.config.mode_greeting(self.current_mode)
+            ),
             self.current_mode,
         );
+        self.announce_capabilities();
+    }
+
+    /// Record the active mode so the next launch can resume it
+    fn remember_mode(&mut self) {
+        if !self.config.ui.restore_last_session {
+            return;
+        }
+        if let Err(e) = LastSession::new(self.current_mode).save(&self.config.bindr_home) {
+            self.history.add_system_message(
+                format!("Failed to save last session: {}", e),
+                self.current_mode,
+            );
+        }
+    }
+
+    /// Add a system message describing what the current mode is allowed to do
+    fn announce_capabilities(&mut self) {
+        let message = ToolDispatcher::describe(self.current_mode)
+            .unwrap_or_else(|e| e.to_string());
+        self.history.add_system_message(message, self.current_mode);
     }
 
     /// Handle user input and start streaming response
@@ -67,17 +216,62 @@ impl ConversationManager {
             return Ok(());
         }
 
-        // Add user message to history
-        self.history.add_user_message(input.clone(), self.current_mode);
+        if self.is_streaming() {
+            // Keep the draft so it can be sent once the current reply is done
+            self.composer.set_content(input);
+            self.refresh_cost_preview();
+            self.history.add_system_message(
+                "Wait for the current response to finish before sending another message.".to_string(),
+                self.current_mode,
+            );
+            return Ok(());
+        }
+
+        self.tool_round_trips = 0;
+        if self.attachments.is_empty() {
+            self.history.add_user_message(input.clone(), self.current_mode);
+            return self.start_stream(input).await;
+        }
+
+        let names: Vec<&str> = self.attachments.iter().map(|attachment| attachment.path.as_str()).collect();
+        self.history.add_user_message(format!("{}  📎 {}", input, names.join(", ")), self.current_mode);
+        self.start_stream(self.with_attachments(&input)).await?;
+        self.attachments.clear();
+        self.composer.set_attachments(Vec::new());
+        Ok(())
+    }
+
+    /// Message as it will be sent, with any attached files folded in ahead of it
+    fn with_attachments(&self, input: &str) -> String {
+        if self.attachments.is_empty() {
+            return input.to_string();
+        }
+        prompts::with_attachments(
+            input,
+            self.attachments
+                .iter()
+                .map(|attachment| (attachment.path.as_str(), attachment.contents.as_str())),
+        )
+    }
+
+    /// Send a message to the agent and stream its reply into the history
+    async fn start_stream(&mut self, message: String) -> Result<()> {
+        // A second receiver would replace the first and drop its reply
+        if self.is_streaming() {
+            bail!("A response is already streaming");
+        }
 
-        // Start streaming response
-        self.streaming.start_streaming();
         self.current_streaming_message.clear();
+        self.current_reasoning.clear();
+        self.history.set_streaming_message(String::new());
+        self.stream_failed = false;
+        self.turn_input_estimate = self.agent_manager.orchestrator().estimate_input_tokens(&message);
+        self.reported_usage = pricing::TokenUsage::default();
 
         // Get streaming response from agent and store the receiver
         let stream_rx = self.agent_manager
             .orchestrator_mut()
-            .continue_conversation(input)
+            .continue_conversation(message)
             .await?;
 
         // Store the stream receiver for processing in the main loop
@@ -86,43 +280,411 @@ impl ConversationManager {
         Ok(())
     }
 
+    /// Check a tool call from the model against the current mode and run, stage, or queue it.
+    ///
+    /// File changes that need approval wait for /diff, other tools that need it wait for y/n,
+    /// and auto-approved tools run straight away. A tool the mode forbids doesn't surface as
+    /// a raw error: the user gets a short note and the model is told the tool is unavailable
+    /// so it can carry on.
+    pub async fn handle_tool_request(&mut self, invocation: ToolInvocation) -> Result<()> {
+        match self.agent_manager.review_tool_invocation(invocation) {
+            Ok(outcome) if outcome.requires_approval && diff::is_file_change(&outcome.invocation) => {
+                self.queue_change(outcome.invocation);
+                Ok(())
+            }
+            Ok(outcome) if outcome.requires_approval => {
+                self.stage_tool(outcome);
+                Ok(())
+            }
+            Ok(outcome) => self.run_tool(outcome.invocation).await,
+            Err(e) => match e.downcast::<ToolNotPermitted>() {
+                Ok(denied) => {
+                    self.history.add_system_message(denied.user_note(), self.current_mode);
+                    self.send_tool_result(denied.tool_result()).await
+                }
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    /// Hold a tool call until the user approves or rejects it
+    fn stage_tool(&mut self, outcome: ToolRequestOutcome) {
+        let tool = &outcome.invocation.tool;
+        self.history.add_system_message(
+            format!(
+                "The assistant wants to {}: {}. Press y to allow or n to refuse.",
+                tool.kind().label(),
+                tool.target()
+            ),
+            self.current_mode,
+        );
+        self.pending_tool = Some(outcome);
+    }
+
+    /// Act on y/n for the staged tool call; other keys are ignored until it is resolved
+    async fn resolve_pending_tool(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        match key.code {
+            crossterm::event::KeyCode::Char('y' | 'Y') => {
+                if let Some(outcome) = self.pending_tool.take() {
+                    // The user stepped in, so the automatic round-trips start over
+                    self.tool_round_trips = 0;
+                    self.run_tool(outcome.invocation).await?;
+                }
+            }
+            crossterm::event::KeyCode::Char('n' | 'N') | crossterm::event::KeyCode::Esc => {
+                if let Some(outcome) = self.pending_tool.take() {
+                    let tool = &outcome.invocation.tool;
+                    self.history.add_system_message(
+                        format!("Refused to {}: {}.", tool.kind().label(), tool.target()),
+                        self.current_mode,
+                    );
+                    self.stop_auto_continue("a tool call was refused");
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Run an approved tool, note the outcome, and hand the result back to the model
+    async fn run_tool(&mut self, invocation: ToolInvocation) -> Result<()> {
+        let output = self.tool_runner.run(&invocation).await;
+        let label = invocation.tool.kind().label();
+        let result = match output {
+            Ok(output) => {
+                self.history.add_system_message(format!("🔧 {}", output.summary()), self.current_mode);
+                format!("[tool result: {}]\n{}", label, output.render())
+            }
+            Err(e) => {
+                self.history.add_system_message(format!("❌ {:#}", e), self.current_mode);
+                format!("[tool result: {}] Failed: {:#}", label, e)
+            }
+        };
+        self.send_tool_result(result).await
+    }
+
+    /// Hand a tool result back to the model, unless it has already had `[loop_guard].max_tool_round_trips`
+    /// of them this turn; a model that keeps calling tools would otherwise spend tokens without end
+    async fn send_tool_result(&mut self, result: String) -> Result<()> {
+        let limit = self.config.loop_guard.max_tool_round_trips;
+        if self.tool_round_trips >= limit {
+            self.history.add_system_message(
+                format!(
+                    "⏸ Stopped after {} tool calls in a row without your input ([loop_guard].max_tool_round_trips). Send a message to let the assistant carry on.",
+                    limit
+                ),
+                self.current_mode,
+            );
+            self.stop_auto_continue("the tool call limit was reached");
+            return Ok(());
+        }
+        self.tool_round_trips += 1;
+        self.start_stream(result).await
+    }
+
+    /// Hold a file change for review instead of applying it straight away
+    fn queue_change(&mut self, invocation: ToolInvocation) {
+        let path = diff::preview(&invocation, &self.config.cwd)
+            .map(|preview| preview.path.display().to_string())
+            .unwrap_or_default();
+        self.pending_changes.push(invocation);
+        self.history.add_system_message(
+            format!(
+                "Queued a change to {} ({} pending). Run /diff to review.",
+                path,
+                self.pending_changes.len()
+            ),
+            self.current_mode,
+        );
+    }
+
+    /// Open the combined diff of every pending file change
+    fn open_diff_view(&mut self) {
+        if self.pending_changes.is_empty() {
+            self.history.add_system_message(
+                "There are no pending file changes to review.".to_string(),
+                self.current_mode,
+            );
+            return;
+        }
+        let previews = self.pending_changes
+            .iter()
+            .filter_map(|invocation| diff::preview(invocation, &self.config.cwd))
+            .collect();
+        self.diff_view = Some(DiffView::new(previews));
+    }
+
+    /// Act on a decision made in the diff view
+    fn resolve_diff(&mut self, decision: DiffDecision) {
+        match decision {
+            DiffDecision::None => return,
+            DiffDecision::Close => {}
+            DiffDecision::RejectAll => {
+                let count = self.pending_changes.len();
+                self.pending_changes.clear();
+                self.history.add_system_message(
+                    format!("Rejected {} pending change(s).", count),
+                    self.current_mode,
+                );
+                self.stop_auto_continue("changes were rejected");
+            }
+            DiffDecision::Approve(indices) => {
+                if indices.is_empty() {
+                    self.history.add_system_message(
+                        "No changes were selected; all pending changes were discarded.".to_string(),
+                        self.current_mode,
+                    );
+                }
+                for (index, invocation) in std::mem::take(&mut self.pending_changes).into_iter().enumerate() {
+                    if !indices.contains(&index) {
+                        continue;
+                    }
+                    // Changes queued before a /mode switch must still be allowed in the mode approving them
+                    let result = match self.agent_manager.review_tool_invocation(invocation) {
+                        Ok(outcome) => match diff::apply(&outcome.invocation, &self.config.cwd) {
+                            Ok(summary) => format!("✅ {}", summary),
+                            Err(e) => format!("❌ {:#}", e),
+                        },
+                        Err(e) => match e.downcast::<ToolNotPermitted>() {
+                            Ok(denied) => format!("❌ {}", denied.user_note()),
+                            Err(e) => format!("❌ {:#}", e),
+                        },
+                    };
+                    self.history.add_system_message(result, self.current_mode);
+                }
+            }
+        }
+        self.diff_view = None;
+    }
+
     /// Process streaming chunks (called from main loop)
     pub fn process_streaming_chunks(&mut self) {
-        if let Some(ref mut stream_rx) = self.stream_receiver {
-            loop {
-                match stream_rx.try_recv() {
-                    Ok(chunk) => {
-                        self.current_streaming_message.push_str(&chunk);
-                        // Update the streaming message in history as it grows
-                        self.history.set_streaming_message(self.current_streaming_message.clone());
-                    }
-                    Err(tokio::sync::mpsc::error::TryRecvError::Empty) => {
-                        // No more chunks right now
-                        break;
-                    }
-                    Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
-                        // Drain any remaining buffered chunks before finalizing
-                        while let Ok(chunk) = stream_rx.try_recv() {
-                            self.current_streaming_message.push_str(&chunk);
-                        }
-                        // Stream complete - finalize message
-                        if !self.current_streaming_message.is_empty() {
-                            self.history.add_assistant_message(
-                                self.current_streaming_message.clone(),
-                                self.current_mode,
-                            );
-                        }
-                        self.history.clear_streaming_message();
-                        self.current_streaming_message.clear();
-                        self.stream_receiver = None;
-                        self.streaming.clear();
+        let Some(mut stream_rx) = self.stream_receiver.take() else {
+            return;
+        };
+
+        loop {
+            match stream_rx.try_recv() {
+                Ok(event) => {
+                    self.apply_stream_event(event);
+                    if self.cut_repetition_loop() {
+                        // Dropping the receiver cancels the rest of the reply
+                        self.stop_auto_continue("the reply got stuck repeating itself");
+                        self.finish_stream();
+                        self.history.add_system_message(
+                            "⚠ Detected a repetition loop, stopped the response. Set enabled = false under [loop_guard] if the repetition was intended.".to_string(),
+                            self.current_mode,
+                        );
                         break;
                     }
+                    // Update the streaming message in history as it grows
+                    let preview = self.agent_manager
+                        .orchestrator()
+                        .redact(&self.current_streaming_message)
+                        .into_owned();
+                    self.history.set_streaming_message(preview);
+                    self.history.set_streaming_reasoning(self.current_reasoning.clone());
                 }
+                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => {
+                    // No more chunks right now
+                    self.stream_receiver = Some(stream_rx);
+                    break;
+                }
+                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
+                    // All buffered chunks have been received; finalize the message
+                    self.finish_stream();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Stop the reply in progress, keeping whatever text already arrived
+    fn interrupt_stream(&mut self) {
+        // Dropping the receiver and the relay task cancels the provider request
+        self.stream_receiver = None;
+        self.agent_manager.orchestrator_mut().cancel_stream();
+        self.stop_auto_continue("the reply was interrupted");
+        if self.current_streaming_message.is_empty() {
+            self.history.add_system_message(
+                "Interrupted before the assistant replied.".to_string(),
+                self.current_mode,
+            );
+        } else {
+            self.current_streaming_message.push_str("\n\n_(interrupted)_");
+        }
+        self.finish_stream();
+        // A tool call in a cut-off reply wasn't meant to run
+        self.requested_tool = None;
+    }
+
+    /// Trim a reply that has started repeating itself to one copy of the repeated text
+    fn cut_repetition_loop(&mut self) -> bool {
+        let guard = &self.config.loop_guard;
+        if !guard.enabled {
+            return false;
+        }
+        let Some(cut) = loop_guard::loop_cut(&self.current_streaming_message, guard.min_repeats) else {
+            return false;
+        };
+        self.current_streaming_message.truncate(cut);
+        true
+    }
+
+    /// Apply a single streamed event to the in-progress assistant message
+    fn apply_stream_event(&mut self, event: LlmEvent) {
+        match event {
+            LlmEvent::TextDelta(chunk) => self.current_streaming_message.push_str(&chunk),
+            LlmEvent::Error(error) => {
+                self.stream_failed = true;
+                self.last_error = Some(error);
             }
+            LlmEvent::ReasoningDelta(chunk) => self.current_reasoning.push_str(&chunk),
+            LlmEvent::KeyRejected(provider_id) => self.key_prompt_provider = Some(provider_id),
+            LlmEvent::FallbackUsed { note, model_id } => {
+                self.history.set_streaming_status(None);
+                self.history.add_system_message(note, self.current_mode);
+                self.agent_manager.orchestrator_mut().reply_fell_back_to(model_id);
+            }
+            LlmEvent::Retrying(note) => self.history.set_streaming_status(Some(note)),
+            LlmEvent::Usage(usage) => self.reported_usage.merge(usage),
+            LlmEvent::ResponseComplete(_) | LlmEvent::StreamComplete => {}
         }
     }
 
+    /// Finalize the streamed assistant message once the channel closes.
+    ///
+    /// The reply is assembled from deltas alone, so it is recorded exactly once
+    /// in both the visible history and the agent's context.
+    fn finish_stream(&mut self) {
+        if !self.current_streaming_message.is_empty() {
+            let orchestrator = self.agent_manager.orchestrator_mut();
+            let content = if self.stream_failed {
+                orchestrator.redact(&self.current_streaming_message).into_owned()
+            } else {
+                orchestrator.process_complete_response(self.current_streaming_message.clone())
+            };
+            // Counts the provider didn't report fall back to estimates
+            let mut usage = pricing::TokenUsage {
+                input_tokens: self.turn_input_estimate,
+                output_tokens: pricing::estimate_tokens(&self.current_streaming_message)
+                    + pricing::estimate_tokens(&self.current_reasoning),
+            };
+            usage.merge(self.reported_usage);
+            let exact = self.reported_usage.input_tokens > 0 && self.reported_usage.output_tokens > 0;
+            // Priced as the model that answered, which a fallback may have changed
+            let orchestrator = self.agent_manager.orchestrator();
+            let model_id = orchestrator.reply_model().map(str::to_string);
+            let rates = self.config.model_rates(model_id.as_deref().unwrap_or(orchestrator.current_model()));
+            let cost = self.session_cost.add_turn(usage, exact, rates);
+            let reasoning = Some(self.current_reasoning.clone());
+            self.history.add_assistant_reply(content, reasoning, Some(cost), model_id, self.current_mode);
+            if !self.stream_failed {
+                self.requested_tool = tools::parse_tool_call(&self.current_streaming_message, self.current_mode);
+                if let Err(e) = self.config.record_usage() {
+                    self.history.add_system_message(format!("Couldn't update today's usage: {:#}", e), self.current_mode);
+                }
+            }
+        }
+        if self.stream_failed && let Some(error) = &self.last_error {
+            self.history.add_system_message(
+                format!("❌ Error: {} (run /explain or press Ctrl+E for help)", error),
+                self.current_mode,
+            );
+        }
+        if let Some(auto) = self.auto_continue.as_mut() {
+            if self.stream_failed {
+                self.stop_auto_continue("the last step failed");
+            } else if self.current_streaming_message.contains(prompts::AUTO_CONTINUE_DONE) {
+                self.stop_auto_continue("every step is complete");
+            } else {
+                auto.due = true;
+            }
+        }
+        self.history.clear_streaming_message();
+        self.current_streaming_message.clear();
+        self.current_reasoning.clear();
+    }
+
+    /// Turn /auto on or off
+    fn toggle_auto_continue(&mut self) {
+        if self.auto_continue.is_some() {
+            self.stop_auto_continue("turned off");
+            return;
+        }
+        if self.current_mode != BindrMode::Execute {
+            self.history.add_system_message(
+                "/auto works through an approved checklist, so it only runs in Execute mode.".to_string(),
+                self.current_mode,
+            );
+            return;
+        }
+
+        self.auto_continue = Some(AutoContinue {
+            steps: 0,
+            due: !self.is_streaming(),
+        });
+        self.history.add_system_message(
+            format!(
+                "Auto-continue on: working through the checklist for up to {} steps. File changes still wait for your approval in /diff; run /auto again to stop.",
+                self.config.ui.auto_continue_max_steps
+            ),
+            self.current_mode,
+        );
+    }
+
+    fn stop_auto_continue(&mut self, reason: &str) {
+        if let Some(auto) = self.auto_continue.take() {
+            self.history.add_system_message(
+                format!("Auto-continue stopped after {} step(s): {}.", auto.steps, reason),
+                self.current_mode,
+            );
+        }
+    }
+
+    /// Review the tool call from the last reply, if it made one (called from main loop)
+    pub async fn run_requested_tool(&mut self) -> Result<()> {
+        match self.requested_tool.take() {
+            Some(invocation) => self.handle_tool_request(invocation).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Start the next /auto step once the previous reply is in and its changes are reviewed (called from main loop)
+    pub async fn advance_auto_continue(&mut self) -> Result<()> {
+        let Some(auto) = self.auto_continue else {
+            return Ok(());
+        };
+        if !auto.due
+            || self.is_streaming()
+            || !self.pending_changes.is_empty()
+            || self.diff_view.is_some()
+            || self.pending_tool.is_some()
+        {
+            return Ok(());
+        }
+
+        let max_steps = self.config.ui.auto_continue_max_steps;
+        if auto.steps >= max_steps {
+            self.stop_auto_continue("reached the step limit ([ui].auto_continue_max_steps)");
+            return Ok(());
+        }
+
+        let step = auto.steps + 1;
+        self.auto_continue = Some(AutoContinue { steps: step, due: false });
+        // Each step is a turn of its own, capped by the step limit
+        self.tool_round_trips = 0;
+        self.history.add_system_message(
+            format!("Auto-continue: step {} of {}", step, max_steps),
+            self.current_mode,
+        );
+        if let Err(e) = self.start_stream(prompts::auto_continue_prompt(step, max_steps)).await {
+            self.stop_auto_continue("the next step could not start");
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Switch to a different mode
     pub async fn switch_mode(&mut self, new_mode: BindrMode) -> Result<()> {
         if new_mode == self.current_mode {
@@ -131,41 +693,243 @@ impl ConversationManager {
 
         // Switch agent mode
         self.agent_manager.orchestrator_mut().switch_mode(new_mode).await?;
+        self.stop_auto_continue("left Execute mode");
 
         // Update UI components
         self.current_mode = new_mode;
+        self.remember_mode();
         let placeholder = Self::get_mode_placeholder(new_mode);
         self.composer = ConversationComposer::new(placeholder, new_mode);
-        self.streaming.update_mode(new_mode);
+        self.composer.set_command_usage(self.command_usage.clone());
+        self.composer.set_submit_key(self.config.ui.submit_key);
 
         // Add mode switch message
         self.history.add_system_message(
-            format!("Switched to {} mode", new_mode.display_name()),
+            format!(
+                "Switched to {} mode. {}",
+                new_mode.display_name(),
+                self.config.mode_greeting(new_mode)
+            ),
             new_mode,
         );
+        self.announce_capabilities();
 
         Ok(())
     }
 
     /// Handle key input
     pub async fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> Result<ConversationAction> {
-        match self.composer.handle_key(key) {
+        if let Some(diff_view) = self.diff_view.as_mut() {
+            let decision = diff_view.handle_key(key);
+            self.resolve_diff(decision);
+            return Ok(ConversationAction::None);
+        }
+
+        if self.pending_tool.is_some() {
+            self.resolve_pending_tool(key).await?;
+            return Ok(ConversationAction::None);
+        }
+
+        if key.code == crossterm::event::KeyCode::Char('k')
+            && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
+            && let Some(provider_id) = self.key_prompt_provider.take()
+        {
+            return Ok(ConversationAction::UpdateApiKey(provider_id));
+        }
+
+        if key.code == crossterm::event::KeyCode::Char('e')
+            && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
+            && self.last_error.is_some()
+        {
+            self.explain_last_error().await?;
+            return Ok(ConversationAction::None);
+        }
+
+        if self.is_streaming()
+            && ((key.code == crossterm::event::KeyCode::Esc && !self.composer.is_command_palette_open())
+                || (key.code == crossterm::event::KeyCode::Char('c')
+                    && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)))
+        {
+            self.interrupt_stream();
+            return Ok(ConversationAction::None);
+        }
+
+        if key.code == crossterm::event::KeyCode::Char('l')
+            && key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
+        {
+            // Runtime only; `ui.density` sets the starting layout. Ctrl+D scrolls, so density lives on Ctrl+L
+            self.history.set_density(self.history.density().toggled());
+            return Ok(ConversationAction::None);
+        }
+
+        let control = key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
+        match key.code {
+            crossterm::event::KeyCode::PageUp => {
+                self.history.scroll_up(HISTORY_PAGE_LINES);
+                return Ok(ConversationAction::None);
+            }
+            crossterm::event::KeyCode::PageDown => {
+                self.history.scroll_down(HISTORY_PAGE_LINES);
+                return Ok(ConversationAction::None);
+            }
+            crossterm::event::KeyCode::Char('u') if control => {
+                self.history.scroll_up(HISTORY_PAGE_LINES);
+                return Ok(ConversationAction::None);
+            }
+            crossterm::event::KeyCode::Char('d') if control => {
+                self.history.scroll_down(HISTORY_PAGE_LINES);
+                return Ok(ConversationAction::None);
+            }
+            _ => {}
+        }
+
+        if key.code == crossterm::event::KeyCode::Tab
+            && (self.focus == FocusTarget::History || !self.composer.is_command_palette_open())
+        {
+            self.focus = match self.focus {
+                FocusTarget::Composer => FocusTarget::History,
+                FocusTarget::History => FocusTarget::Composer,
+            };
+            self.set_focus(true);
+            return Ok(ConversationAction::None);
+        }
+
+        if self.focus == FocusTarget::History {
+            match key.code {
+                crossterm::event::KeyCode::Up => self.history.cursor_up(),
+                crossterm::event::KeyCode::Down => self.history.cursor_down(),
+                crossterm::event::KeyCode::Char('f') if key.modifiers.is_empty() => self.toggle_flag(None),
+                crossterm::event::KeyCode::Char('r') if key.modifiers.is_empty() => {
+                    if let Some(index) = self.history.cursor() {
+                        self.history.toggle_reasoning(index);
+                    }
+                }
+                crossterm::event::KeyCode::End => self.history.scroll_to_bottom(),
+                crossterm::event::KeyCode::Esc => {
+                    self.history.set_cursor(None);
+                    self.focus = FocusTarget::Composer;
+                    self.set_focus(true);
+                }
+                _ => {
+                    // Anything else is typing; hand it to the composer
+                    self.focus = FocusTarget::Composer;
+                    self.set_focus(true);
+                    return self.handle_composer_key(key).await;
+                }
+            }
+            return Ok(ConversationAction::None);
+        }
+
+        self.handle_composer_key(key).await
+    }
+
+    /// Route a key to the composer and act on what it produced
+    async fn handle_composer_key(&mut self, key: crossterm::event::KeyEvent) -> Result<ConversationAction> {
+        let result = self.composer.handle_key(key);
+        self.refresh_cost_preview();
+
+        match result {
             crate::ui::conversation::composer::ConversationResult::Submitted(input) => {
+                if self.needs_cost_confirmation(&input) {
+                    return Ok(ConversationAction::None);
+                }
                 self.handle_input(input).await?;
                 Ok(ConversationAction::None)
             }
             crate::ui::conversation::composer::ConversationResult::Command(command) => {
+                self.command_usage.record(command.command);
+                self.persist_command_usage();
                 self.handle_slash_command(command).await
             }
+            crate::ui::conversation::composer::ConversationResult::ToggleFavorite(command) => {
+                self.command_usage.toggle_favorite(command);
+                self.persist_command_usage();
+                Ok(ConversationAction::None)
+            }
             crate::ui::conversation::composer::ConversationResult::None => {
                 Ok(ConversationAction::None)
             }
         }
     }
 
+    /// Update the composer's estimate of what sending the current draft would cost
+    fn refresh_cost_preview(&mut self) {
+        let draft = self.composer.get_content();
+        let hint = if draft.trim().is_empty() || draft.starts_with('/') {
+            None
+        } else {
+            let tokens = self.agent_manager.orchestrator().estimate_input_tokens(&self.with_attachments(&draft));
+            Some(match self.config.model_rates(self.agent_manager.orchestrator().current_model()) {
+                Some(rates) => format!("~{} tokens • {} for this turn", tokens, pricing::format_cost(rates.input_cost(tokens))),
+                None => format!("~{} tokens", tokens),
+            })
+        };
+        self.composer.set_cost_hint(hint);
+    }
+
+    /// Hold back a turn whose estimated cost exceeds `[ui].confirm_cost_above` until it is sent twice
+    fn needs_cost_confirmation(&mut self, input: &str) -> bool {
+        let Some(threshold) = self.config.ui.confirm_cost_above else {
+            return false;
+        };
+        if self.pending_cost_confirmation.take().as_deref() == Some(input) {
+            return false;
+        }
+        let tokens = self.agent_manager.orchestrator().estimate_input_tokens(&self.with_attachments(input));
+        let Some(cost) = self.config.model_rates(self.agent_manager.orchestrator().current_model()).map(|rates| rates.input_cost(tokens)) else {
+            return false;
+        };
+        if cost <= threshold {
+            return false;
+        }
+
+        self.history.add_system_message(
+            format!(
+                "This turn is estimated at {} (~{} tokens), above your ${:.2} limit. Press Enter again to send it anyway.",
+                pricing::format_cost(cost),
+                tokens,
+                threshold
+            ),
+            self.current_mode,
+        );
+        self.composer.set_content(input.to_string());
+        self.pending_cost_confirmation = Some(input.to_string());
+        self.refresh_cost_preview();
+        true
+    }
+
+    /// Save command usage statistics and refresh the palette ranking
+    fn persist_command_usage(&mut self) {
+        self.composer.set_command_usage(self.command_usage.clone());
+        if let Err(e) = self.command_usage.save(&self.config.bindr_home) {
+            self.history.add_system_message(
+                format!("Failed to save command usage: {}", e),
+                self.current_mode,
+            );
+        }
+    }
+
     /// Set focus state
     pub fn set_focus(&mut self, has_focus: bool) {
-        self.composer.set_focus(has_focus);
+        self.composer.set_focus(has_focus && self.focus == FocusTarget::Composer);
+        self.history.set_focus(has_focus && self.focus == FocusTarget::History);
+    }
+
+    /// Name shown for the project: the open session's, else the workspace directory's
+    pub fn project_name(&self) -> String {
+        if let Some(name) = self.agent_manager.orchestrator().project_name() {
+            return name.to_string();
+        }
+        self.config
+            .cwd
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.config.cwd.display().to_string())
+    }
+
+    /// Whether a reply is still streaming in; only one may run at a time
+    pub fn is_streaming(&self) -> bool {
+        self.stream_receiver.is_some()
     }
 
     /// Check if conversation is active
@@ -175,23 +939,59 @@ impl ConversationManager {
     }
 
     /// Get current mode
-    #[allow(dead_code)]
     pub fn current_mode(&self) -> BindrMode {
         self.current_mode
     }
 
+    /// Model the current mode sends its turns to
+    pub fn current_model(&self) -> &str {
+        self.agent_manager.orchestrator().current_model()
+    }
+
     /// Clear conversation
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.history.clear();
         self.composer.clear();
-        self.streaming.clear();
     }
 
     /// Refresh configuration for agent and client
     pub fn update_config(&mut self, config: Config) {
         self.agent_manager.update_config(config.clone());
-        self.llm_client = LlmClient::new(config);
+        self.llm_client = LlmClient::new(config.clone());
+        self.history.set_reduced_motion(config.ui.reduced_motion);
+        self.history.set_syntax_highlighting(config.ui.syntax_highlighting);
+        self.history.set_show_reasoning(config.ui.show_reasoning);
+        self.history.set_density(config.ui.density);
+        self.history.set_submit_key(config.ui.submit_key);
+        self.history.set_max_messages(config.ui.max_history_lines);
+        self.composer.set_submit_key(config.ui.submit_key);
+        if config.ui.auto_save_interval != self.config.ui.auto_save_interval {
+            self.auto_save = AutoSave::new(config.ui.auto_save_interval, std::time::Instant::now());
+        }
+        self.config = config;
+    }
+
+    /// Save the open project once `ui.auto_save_interval` has passed, if it has unsaved changes
+    pub fn auto_save(&mut self) {
+        if !self.auto_save.due(std::time::Instant::now()) {
+            return;
+        }
+        match self.agent_manager.orchestrator_mut().save_if_dirty() {
+            Ok(true) => self.last_auto_save = Some(chrono::Local::now()),
+            Ok(false) => {}
+            Err(e) => self.notify(format!("Auto-save failed: {:#}", e)),
+        }
+    }
+
+    /// When auto-save last wrote the project this run
+    pub fn last_auto_save(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.last_auto_save
+    }
+
+    /// Tokens and estimated cost of the replies so far this run
+    pub fn session_cost(&self) -> pricing::SessionCost {
+        self.session_cost
     }
 
     /// Handle slash commands
@@ -223,10 +1023,590 @@ impl ConversationManager {
                 self.history.add_system_message(help_text, self.current_mode);
                 Ok(ConversationAction::None)
             }
-            SlashCommand::Model => {
-                Ok(ConversationAction::ShowModelSelection)
+            SlashCommand::Model => match command.argument {
+                Some(model_id) => Ok(self.select_model(model_id.trim())),
+                None => Ok(ConversationAction::ShowModelSelection),
+            },
+            SlashCommand::Provider => match command.argument.as_deref().map(str::trim) {
+                None => Ok(ConversationAction::ShowProviderSelection),
+                Some("add") => Ok(ConversationAction::ShowAddProvider),
+                Some(argument) => match argument.strip_prefix("remove ") {
+                    Some(provider_id) => Ok(self.remove_provider(provider_id.trim())),
+                    None => Ok(self.switch_provider(argument)),
+                },
+            },
+            SlashCommand::Capabilities => {
+                self.announce_capabilities();
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Diff => {
+                self.open_diff_view();
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Auto => {
+                self.toggle_auto_continue();
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Open => self.open_last_response(),
+            SlashCommand::Cd => self.change_workspace(command.argument),
+            SlashCommand::Lang => {
+                self.change_response_locale(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Attach => {
+                self.attach_file(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::MaxTokens => {
+                self.change_max_tokens(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Temperature => {
+                self.change_temperature(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Flag => {
+                self.toggle_flag(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Flags => {
+                self.show_flags(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Clear => {
+                if self.is_streaming() {
+                    self.history.add_system_message(
+                        "Wait for the current response to finish before clearing the conversation.".to_string(),
+                        self.current_mode,
+                    );
+                } else {
+                    self.history.clear();
+                    self.current_streaming_message.clear();
+                    self.current_reasoning.clear();
+                    self.history.add_system_message("Conversation cleared.".to_string(), self.current_mode);
+                }
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Save => {
+                self.save_conversation();
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Export => {
+                self.export_conversation(command.argument);
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Catalog => Ok(ConversationAction::ShowModelCatalog),
+            SlashCommand::Explain => {
+                self.explain_last_error().await?;
+                Ok(ConversationAction::None)
+            }
+            SlashCommand::Stats => {
+                let state = self.agent_manager.orchestrator().get_project_state();
+                let report = ProjectStats::from_history("this conversation", &state.conversation_history)
+                    .render()
+                    .join("\n");
+                self.history.add_system_message(report, self.current_mode);
+                Ok(ConversationAction::None)
+            }
+        }
+    }
+
+    /// Explain the most recent provider error, asking the model only when no built-in answer fits
+    async fn explain_last_error(&mut self) -> Result<()> {
+        let Some(error) = self.last_error.take() else {
+            self.history.add_system_message(
+                "There is no recent error to explain.".to_string(),
+                self.current_mode,
+            );
+            return Ok(());
+        };
+
+        match prompts::explain_error(&error) {
+            Some(explanation) => {
+                self.history.add_system_message(explanation.to_string(), self.current_mode);
+                Ok(())
+            }
+            None => self.handle_input(prompts::explain_error_prompt(&error)).await,
+        }
+    }
+
+    /// Set the response language for this project, or report the current one
+    fn change_response_locale(&mut self, locale: Option<String>) {
+        let orchestrator = self.agent_manager.orchestrator_mut();
+        let message = match locale.as_deref().map(str::trim) {
+            None => match orchestrator.response_locale() {
+                Some(current) => format!("Responses are in \"{}\". Use /lang <code> to change it.", current),
+                None => "Responses follow the model's default language. Use /lang <code> to pick one.".to_string(),
+            },
+            Some(code) if !is_locale_code(code) => {
+                format!("\"{}\" doesn't look like a language code; try something like de, fr, or pt-BR.", code)
+            }
+            Some(code) => {
+                let reset = matches!(code, "default" | "reset" | "off");
+                let saved = orchestrator.set_response_locale((!reset).then(|| code.to_string()));
+                let mut message = match orchestrator.response_locale() {
+                    Some(current) => format!("Responses will be in \"{}\".", current),
+                    None => "Responses will follow the model's default language.".to_string(),
+                };
+                if let Err(e) = saved {
+                    message.push_str(&format!(" (Couldn't save it with the project: {})", e));
+                }
+                message
+            }
+        };
+        self.history.add_system_message(message, self.current_mode);
+    }
+
+    /// Queue a workspace file for the next message, or list or clear what is queued
+    fn attach_file(&mut self, argument: Option<String>) {
+        let message = match argument.as_deref().map(str::trim) {
+            None if self.attachments.is_empty() => {
+                "Nothing attached. Use /attach <path> to send a file with your next message.".to_string()
+            }
+            None => {
+                let names: Vec<&str> = self.attachments.iter().map(|attachment| attachment.path.as_str()).collect();
+                format!("Attached to your next message: {}. /attach clear removes them.", names.join(", "))
+            }
+            Some("clear") => {
+                self.attachments.clear();
+                "Removed all attachments.".to_string()
+            }
+            Some(path) => self.read_attachment(path),
+        };
+        let names = self.attachments.iter().map(|attachment| attachment.path.clone()).collect();
+        self.composer.set_attachments(names);
+        self.refresh_cost_preview();
+        self.history.add_system_message(message, self.current_mode);
+    }
+
+    /// Read a file through the ReadFile tool's rules and queue it; returns what to tell the user
+    fn read_attachment(&mut self, path: &str) -> String {
+        let invocation = ToolInvocation::new(
+            BindrTool::ReadFile(ReadFileOptions {
+                path: PathBuf::from(path),
+                max_bytes: None,
+            }),
+            self.current_mode,
+            format!("Attach {}", path),
+        );
+        let outcome = match self.agent_manager.review_tool_invocation(invocation) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                return match e.downcast::<ToolNotPermitted>() {
+                    Ok(denied) => denied.user_note(),
+                    Err(e) => format!("{:#}", e),
+                };
+            }
+        };
+
+        let output = match ToolExecutor::new(&self.config.cwd).execute(&outcome.invocation) {
+            Ok(output) => output,
+            Err(e) => return format!("{:#}", e),
+        };
+        let ToolOutput::File { path: relative, contents } = &output else {
+            return format!("{} is not a file", path);
+        };
+        let shown = relative.display().to_string();
+
+        match contents {
+            FileContents::Binary { total_bytes } => {
+                format!("{} is a binary file ({} bytes); only text files can be attached.", shown, total_bytes)
+            }
+            FileContents::Text { truncated, .. } => {
+                let truncated = *truncated;
+                self.attachments.retain(|attachment| attachment.path != shown);
+                self.attachments.push(Attachment {
+                    contents: output.render(),
+                    path: shown.clone(),
+                });
+                if truncated {
+                    format!("📎 Attached the first {} KB of {}; it will go with your next message.", files::MAX_READ_BYTES / 1024, shown)
+                } else {
+                    format!("📎 Attached {}; it will go with your next message.", shown)
+                }
+            }
+        }
+    }
+
+    /// Override the sampling temperature for this session, or report the one in use
+    fn change_temperature(&mut self, value: Option<String>) {
+        let orchestrator = self.agent_manager.orchestrator_mut();
+        let message = match value.as_deref().map(str::trim) {
+            None => match orchestrator.temperature_override() {
+                Some(temperature) => format!(
+                    "Temperature is {} for this session. /temperature default returns to the per-mode values.",
+                    temperature
+                ),
+                None => format!(
+                    "Temperature is {} ({} default). Use /temperature <0-2> to override it.",
+                    orchestrator.temperature(),
+                    self.current_mode.display_name()
+                ),
+            },
+            Some("default" | "reset" | "off") => {
+                orchestrator.set_temperature_override(None);
+                format!(
+                    "Temperature follows the mode again ({} for {}).",
+                    orchestrator.temperature(),
+                    self.current_mode.display_name()
+                )
+            }
+            Some(value) => match value.parse::<f32>() {
+                Ok(temperature) if (0.0..=2.0).contains(&temperature) => {
+                    orchestrator.set_temperature_override(Some(temperature));
+                    format!(
+                        "Temperature set to {} for this session. Providers with a narrower range clamp it.",
+                        temperature
+                    )
+                }
+                _ => format!("\"{}\" isn't a temperature; pick a number between 0 and 2.", value),
+            },
+        };
+        self.history.add_system_message(message, self.current_mode);
+    }
+
+    /// Switch to the provider with this id or display name, if it is configured and has a key
+    fn switch_provider(&mut self, name: &str) -> ConversationAction {
+        let name = name.trim();
+        let found = self.config.model_providers
+            .iter()
+            .find(|(id, provider)| id.eq_ignore_ascii_case(name) || provider.name.eq_ignore_ascii_case(name));
+        let Some((provider_id, provider)) = found else {
+            let ids: Vec<&str> = self.config.get_providers().into_iter().map(|(id, _)| id.as_str()).collect();
+            self.history.add_system_message(
+                format!("There's no provider called \"{}\". Try one of: {}.", name, ids.join(", ")),
+                self.current_mode,
+            );
+            return ConversationAction::None;
+        };
+        if !self.config.has_api_key_for(provider_id) {
+            self.history.add_system_message(
+                format!("{} has no API key yet. Press Ctrl+K to add one and switch to it.", provider.name),
+                self.current_mode,
+            );
+            self.key_prompt_provider = Some(provider_id.clone());
+            return ConversationAction::None;
+        }
+        ConversationAction::SwitchProvider(provider_id.clone())
+    }
+
+    /// Switch to the model with this id, preferring the current provider's; open the list when it can't be told apart
+    fn select_model(&mut self, model_id: &str) -> ConversationAction {
+        let selected = &self.config.selected_provider;
+        let mut matches: Vec<(&String, &str)> = self.config.model_providers
+            .iter()
+            .flat_map(|(provider_id, provider)| provider.models.iter().map(move |model| (provider_id, model.id.as_str())))
+            .filter(|(_, id)| *id != "custom-model" && id.eq_ignore_ascii_case(model_id))
+            .collect();
+        if let Some(current) = matches.iter().find(|(provider_id, _)| *provider_id == selected) {
+            matches = vec![*current];
+        }
+        let (provider_id, found) = match matches.as_slice() {
+            [single] => *single,
+            [] => {
+                self.history.add_system_message(
+                    format!("No configured provider offers a model called \"{}\". Pick one from the list.", model_id),
+                    self.current_mode,
+                );
+                return ConversationAction::ShowModelSelection;
+            }
+            several => {
+                let mut providers: Vec<&str> = several.iter().map(|(provider_id, _)| provider_id.as_str()).collect();
+                providers.sort_unstable();
+                self.history.add_system_message(
+                    format!("{} is offered by {}. Pick the one you want from the list.", model_id, providers.join(", ")),
+                    self.current_mode,
+                );
+                return ConversationAction::ShowModelSelection;
+            }
+        };
+        if !self.config.has_api_key_for(provider_id) {
+            self.history.add_system_message(
+                format!("{} needs a {} API key. Press Ctrl+K to add one, then run /model again.", found, provider_id),
+                self.current_mode,
+            );
+            self.key_prompt_provider = Some(provider_id.clone());
+            return ConversationAction::None;
+        }
+        ConversationAction::SwitchModel { provider_id: provider_id.clone(), model_id: found.to_string() }
+    }
+
+    /// Ask for a custom provider to be removed, explaining why when it can't be
+    fn remove_provider(&mut self, provider_id: &str) -> ConversationAction {
+        let problem = if Config::is_builtin_provider(provider_id) {
+            Some(format!("{} is built in and can't be removed.", provider_id))
+        } else if !self.config.model_providers.contains_key(provider_id) {
+            Some(format!("There's no custom provider called \"{}\".", provider_id))
+        } else {
+            None
+        };
+        match problem {
+            Some(problem) => {
+                self.history.add_system_message(problem, self.current_mode);
+                ConversationAction::None
+            }
+            None => ConversationAction::RemoveProvider(provider_id.to_string()),
+        }
+    }
+
+    /// Override the reply length limit for this session, or report the one in use
+    fn change_max_tokens(&mut self, value: Option<String>) {
+        let orchestrator = self.agent_manager.orchestrator_mut();
+        let message = match value.as_deref().map(str::trim) {
+            None => match orchestrator.max_tokens_override() {
+                Some(max_tokens) => format!(
+                    "Replies are limited to {} tokens this session. /maxtokens default returns to the per-mode limits.",
+                    max_tokens
+                ),
+                None => format!(
+                    "Replies are limited to {} tokens ({} default). Use /maxtokens <n> to override it.",
+                    orchestrator.max_tokens(),
+                    self.current_mode.display_name()
+                ),
+            },
+            Some("default" | "reset" | "off") => {
+                orchestrator.set_max_tokens_override(None);
+                format!(
+                    "Reply limit follows the mode again ({} tokens for {}).",
+                    orchestrator.max_tokens(),
+                    self.current_mode.display_name()
+                )
+            }
+            Some(value) => match value.parse::<u32>() {
+                Ok(max_tokens) if (1..=MAX_TOKENS_LIMIT).contains(&max_tokens) => {
+                    orchestrator.set_max_tokens_override(Some(max_tokens));
+                    format!(
+                        "Replies limited to {} tokens for this session. Models with a lower ceiling may reject it.",
+                        max_tokens
+                    )
+                }
+                _ => format!(
+                    "\"{}\" isn't a token limit; pick a whole number between 1 and {}.",
+                    value, MAX_TOKENS_LIMIT
+                ),
+            },
+        };
+        self.history.add_system_message(message, self.current_mode);
+    }
+
+    /// Flag the message under the history cursor, or the latest reply when there is no cursor
+    fn toggle_flag(&mut self, note: Option<String>) {
+        let Some(index) = self.history.cursor().or_else(|| self.history.last_assistant_index()) else {
+            self.history.add_system_message(
+                "Nothing to flag yet. Press Tab and use the arrow keys to pick a message.".to_string(),
+                self.current_mode,
+            );
+            return;
+        };
+        let Some(message) = self.history.toggle_flag(index, note) else {
+            return;
+        };
+
+        let orchestrator = self.agent_manager.orchestrator_mut();
+        let mut flagged = orchestrator.flagged_messages().to_vec();
+        flagged.retain(|flag| flag.timestamp != message.timestamp);
+        if message.flagged {
+            flagged.push(FlaggedMessage {
+                timestamp: message.timestamp,
+                role: message.role.clone(),
+                excerpt: flag_excerpt(&message.content),
+                note: message.flag_note.clone(),
+            });
+            flagged.sort_by_key(|flag| flag.timestamp);
+        }
+        let saved = orchestrator.set_flagged_messages(flagged);
+
+        let mut notice = if message.flagged {
+            format!("🚩 Flagged \"{}\". /flags lists your flags.", flag_excerpt(&message.content))
+        } else {
+            format!("Removed the flag from \"{}\".", flag_excerpt(&message.content))
+        };
+        if let Err(e) = saved {
+            notice.push_str(&format!(" (Couldn't save it with the project: {})", e));
+        }
+        self.history.add_system_message(notice, self.current_mode);
+    }
+
+    /// Persist the visible conversation, minus Bindr's own notices, to the open project
+    fn save_conversation(&mut self) {
+        if self.is_streaming() {
+            self.history.add_system_message(
+                "Wait for the current response to finish before saving the conversation.".to_string(),
+                self.current_mode,
+            );
+            return;
+        }
+        let entries: Vec<ConversationEntry> = self.history
+            .messages()
+            .filter(|message| !matches!(message.role, ConversationRole::System))
+            .map(|message| ConversationEntry {
+                mode: message.mode,
+                role: message.role.clone(),
+                content: message.content.clone(),
+                timestamp: message.timestamp,
+                model_id: message.model_id.clone(),
+            })
+            .collect();
+        let count = entries.len();
+        let notice = match self.agent_manager.orchestrator_mut().save_conversation(entries) {
+            Ok(project) => format!(
+                "💾 Saved {} message{} to project '{}'.",
+                count,
+                if count == 1 { "" } else { "s" },
+                project
+            ),
+            Err(e) => format!("❌ Failed to save the conversation: {:#}", e),
+        };
+        self.history.add_system_message(notice, self.current_mode);
+    }
+
+    /// Write the conversation as Markdown to `path` (relative to the workspace),
+    /// or to a timestamped file under `<bindr_home>/exports`
+    fn export_conversation(&mut self, path: Option<String>) {
+        let path = match path {
+            Some(path) => self.config.cwd.join(path),
+            None => self.config
+                .bindr_home
+                .join("exports")
+                .join(format!("{}.md", chrono::Local::now().format("%Y%m%d-%H%M%S"))),
+        };
+        let title = format!("Bindr conversation: {}", self.project_name());
+        let markdown = self.history.to_markdown(&title);
+
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| write_atomic(&path, markdown))
+            .with_context(|| format!("Failed to write {}", path.display()));
+        let notice = match written {
+            Ok(()) => format!("📄 Exported the conversation to {}", path.display()),
+            Err(e) => format!("❌ {:#}", e),
+        };
+        self.history.add_system_message(notice, self.current_mode);
+    }
+
+    /// List flagged messages, or jump to one with `/flags <n>`
+    fn show_flags(&mut self, argument: Option<String>) {
+        let flagged = self.agent_manager.orchestrator().flagged_messages().to_vec();
+        if flagged.is_empty() {
+            self.history.add_system_message(
+                "No flagged messages. Use /flag [note] or press f on a focused message to add one.".to_string(),
+                self.current_mode,
+            );
+            return;
+        }
+
+        if let Some(argument) = argument {
+            let message = match argument.trim().parse::<usize>().ok().and_then(|n| flagged.get(n.checked_sub(1)?)) {
+                None => format!("Pick a flag between 1 and {}.", flagged.len()),
+                Some(flag) => match self.history.find_message(flag.timestamp) {
+                    Some(index) => {
+                        self.history.set_cursor(Some(index));
+                        self.focus = FocusTarget::History;
+                        self.set_focus(true);
+                        return;
+                    }
+                    None => format!("\"{}\" is from an earlier session and isn't shown here.", flag.excerpt),
+                },
+            };
+            self.history.add_system_message(message, self.current_mode);
+            return;
+        }
+
+        for (number, flag) in flagged.iter().enumerate() {
+            let role_icon = match flag.role {
+                ConversationRole::User => "👤",
+                ConversationRole::Assistant => "🤖",
+                ConversationRole::System => "⚙️",
+            };
+            let mut line = format!(
+                "🚩 {}. {} {} \"{}\"",
+                number + 1,
+                role_icon,
+                flag.timestamp.with_timezone(&chrono::Local).format("%b %d %H:%M"),
+                flag.excerpt
+            );
+            if let Some(note) = &flag.note {
+                line.push_str(&format!(" — {}", note));
             }
+            self.history.add_system_message(line, self.current_mode);
+        }
+        self.history.add_system_message(
+            "Use /flags <n> to jump to a flag.".to_string(),
+            self.current_mode,
+        );
+    }
+
+    /// Point tools at a new workspace root, or report the current one
+    fn change_workspace(&mut self, dir: Option<String>) -> Result<ConversationAction> {
+        let Some(dir) = dir else {
+            self.history.add_system_message(
+                format!("Workspace: {}", self.config.cwd.display()),
+                self.current_mode,
+            );
+            return Ok(ConversationAction::None);
+        };
+
+        let mut config = self.config.clone();
+        if let Err(e) = config.set_workspace(std::path::Path::new(&dir)) {
+            self.history.add_system_message(format!("{:#}", e), self.current_mode);
+            return Ok(ConversationAction::None);
         }
+
+        let workspace = config.cwd.clone();
+        self.update_config(config);
+        self.history.add_system_message(
+            format!("Workspace set to {}", workspace.display()),
+            self.current_mode,
+        );
+        Ok(ConversationAction::WorkspaceChanged(workspace))
+    }
+
+    /// Write the last assistant message to a temp file and hand it to $EDITOR/$PAGER
+    fn open_last_response(&mut self) -> Result<ConversationAction> {
+        let Some(content) = self.history.last_assistant_message() else {
+            self.history.add_system_message(
+                "There is no assistant response to open yet.".to_string(),
+                self.current_mode,
+            );
+            return Ok(ConversationAction::None);
+        };
+
+        let path = std::env::temp_dir().join(format!("bindr-response-{}.md", uuid::Uuid::new_v4()));
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let program = ["EDITOR", "PAGER"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.trim().is_empty());
+
+        match program {
+            Some(program) => Ok(ConversationAction::OpenExternal { program, path }),
+            None => {
+                self.history.add_system_message(
+                    format!("Neither $EDITOR nor $PAGER is set; saved the last response to {}", path.display()),
+                    self.current_mode,
+                );
+                Ok(ConversationAction::None)
+            }
+        }
+    }
+
+    /// Show a system notice in the conversation history
+    pub fn notify(&mut self, message: String) {
+        self.history.add_system_message(message, self.current_mode);
+    }
+
+    /// Show an error that stopped a key or message from being handled, offering /explain like a failed reply
+    pub fn report_error(&mut self, error: &anyhow::Error) {
+        let error = format!("{:#}", error);
+        self.history.add_system_message(
+            format!("❌ Error: {} (run /explain or press Ctrl+E for help)", error),
+            self.current_mode,
+        );
+        self.last_error = Some(error);
     }
 
     /// Get mode-specific placeholder text
@@ -251,7 +1631,7 @@ impl Widget for ConversationManager {
             .direction(ratatui::layout::Direction::Vertical)
             .constraints([
                 Constraint::Min(10), // History
-                Constraint::Length(3), // Composer
+                Constraint::Length(self.composer.height()), // Composer
             ])
             .split(area);
 
@@ -260,18 +1640,6 @@ impl Widget for ConversationManager {
 
         // Render composer
         self.composer.render(chunks[1], buf);
-
-        // Render streaming response if active
-        if self.streaming.is_streaming() {
-            // Create a small area for streaming indicator
-            let streaming_area = Rect {
-                x: chunks[1].x,
-                y: chunks[1].y - 1,
-                width: chunks[1].width,
-                height: 1,
-            };
-            self.streaming.render(streaming_area, buf);
-        }
     }
 }
 
@@ -279,19 +1647,747 @@ impl ConversationManager {
     /// Render the conversation UI components
     pub fn render_conversation_ui(&mut self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
         // Create layout for conversation UI
+        let prompt_height = if self.pending_tool.is_some() { 1 } else { 0 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(10), // History area
-                Constraint::Length(3), // Composer area
+                Constraint::Length(prompt_height), // Tool approval prompt
+                Constraint::Length(self.composer.height()), // Composer area
             ])
             .split(area);
 
-        // Render history (includes streaming message if active)
-        self.history.clone().render(chunks[0], buf);
+        // Render pending changes under review, otherwise the history (including any streaming message)
+        match &self.diff_view {
+            Some(diff_view) => diff_view.clone().render(chunks[0], buf),
+            None => (&mut self.history).render(chunks[0], buf),
+        }
+
+        if let Some(outcome) = &self.pending_tool {
+            let tool = &outcome.invocation.tool;
+            let prompt = Line::from(vec![
+                Span::styled(
+                    format!(" ⚠ Allow the assistant to {}: {}? ", tool.kind().label(), tool.target()),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled("y allow • n refuse", Style::default().fg(Color::DarkGray)),
+            ]);
+            buf.set_line(chunks[1].x, chunks[1].y, &prompt, chunks[1].width);
+        }
 
         // Render composer
-        self.composer.clone().render(chunks[1], buf);
+        self.composer.clone().render(chunks[2], buf);
+    }
+
+}
+
+/// Loose check for a BCP 47 style tag such as `de`, `pt-BR`, or `zh_Hant`
+fn is_locale_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.len() <= 35
+        && code.split(['-', '_']).all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// First words of a message, kept short enough for one line
+fn flag_excerpt(content: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_CHARS {
+        return text;
+    }
+    let mut excerpt: String = text.chars().take(MAX_CHARS).collect();
+    excerpt.push('…');
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ConversationRole;
+    use crate::session::SessionManager;
+    use crate::tools::CommandOptions;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use crate::mock_provider::{openai_reply, MockProvider};
+    use crate::config::{Density, FallbackTarget};
+    use futures::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    /// Config on a throwaway Bindr home, also its workspace, so usage counters and saves stay out of the real one
+    fn config_in(home: &tempfile::TempDir) -> Config {
+        let mut config = Config::default();
+        config.bindr_home = home.path().to_path_buf();
+        config.projects_dir = home.path().join("projects");
+        config.cwd = home.path().to_path_buf();
+        // A key in the environment would send test turns to the real provider
+        for provider in config.model_providers.values_mut() {
+            provider.api_key_env = None;
+        }
+        config
+    }
+
+    async fn manager_in(home: &tempfile::TempDir) -> ConversationManager {
+        manager_with(config_in(home)).await
+    }
+
+    /// Manager whose OpenAI provider is `server`, talking to gpt-4o
+    async fn manager_against(home: &tempfile::TempDir, server: &MockProvider) -> ConversationManager {
+        let mut config = config_in(home);
+        config.selected_provider = "openai".to_string();
+        config.default_model = "gpt-4o".to_string();
+        config.model_providers.get_mut("openai").unwrap().base_url = server.base_url.clone();
+        config.set_api_key("openai".to_string(), "test-key".to_string());
+        manager_with(config).await
     }
 
+    async fn manager_with(config: Config) -> ConversationManager {
+        let mut agent_manager = AgentManager::new(config.clone(), SessionManager::new(config.clone()));
+        agent_manager.orchestrator_mut().switch_mode(BindrMode::Execute).await.unwrap();
+        let llm_client = LlmClient::new(config.clone());
+        ConversationManager::new(config, agent_manager, llm_client, BindrMode::Execute)
+    }
+
+    fn last_system_message(manager: &ConversationManager) -> String {
+        manager.history
+            .messages()
+            .filter(|message| matches!(message.role, ConversationRole::System))
+            .last()
+            .map(|message| message.content.clone())
+            .unwrap_or_default()
+    }
+
+    /// Feed `events` to the manager as a finished stream
+    fn stream(manager: &mut ConversationManager, events: Vec<LlmEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for event in events {
+            tx.send(event).unwrap();
+        }
+        drop(tx);
+        manager.stream_receiver = Some(rx);
+        manager.process_streaming_chunks();
+    }
+
+    fn assistant_messages(manager: &ConversationManager) -> Vec<String> {
+        manager.history
+            .messages()
+            .filter(|message| matches!(message.role, ConversationRole::Assistant))
+            .map(|message| message.content.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn streamed_reply_is_recorded_once() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        stream(&mut manager, vec![
+            LlmEvent::TextDelta("Hel".to_string()),
+            LlmEvent::TextDelta("lo there".to_string()),
+            LlmEvent::ResponseComplete("Hello there".to_string()),
+            LlmEvent::StreamComplete,
+        ]);
+
+        assert_eq!(assistant_messages(&manager), ["Hello there"]);
+        let replies: Vec<&str> = manager.agent_manager
+            .orchestrator()
+            .conversation_history()
+            .iter()
+            .filter(|entry| matches!(entry.role, ConversationRole::Assistant))
+            .map(|entry| entry.content.as_str())
+            .collect();
+        assert_eq!(replies, ["Hello there"]);
+        assert!(!manager.is_streaming());
+    }
+
+    #[tokio::test]
+    async fn second_submit_while_streaming_is_rejected() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        let (tx, rx) = mpsc::unbounded_channel();
+        manager.stream_receiver = Some(rx);
+        tx.send(LlmEvent::TextDelta("First ".to_string())).unwrap();
+
+        manager.handle_input("second question".to_string()).await.unwrap();
+
+        assert!(manager.is_streaming());
+        assert!(last_system_message(&manager).starts_with("Wait for the current response to finish"));
+        assert_eq!(manager.composer.get_content(), "second question");
+        assert!(!manager.history.messages().any(|message| message.content == "second question"));
+
+        // The first reply still arrives whole
+        tx.send(LlmEvent::TextDelta("answer".to_string())).unwrap();
+        drop(tx);
+        manager.process_streaming_chunks();
+        assert_eq!(assistant_messages(&manager), ["First answer"]);
+    }
+
+    /// Send `input` and process the reply until the stream ends
+    async fn send(manager: &mut ConversationManager, input: &str) {
+        manager.handle_input(input.to_string()).await.unwrap();
+        finish(manager).await;
+    }
+
+    /// Drain the reply in flight
+    async fn finish(manager: &mut ConversationManager) {
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while manager.is_streaming() {
+                manager.process_streaming_chunks();
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    async fn command(manager: &mut ConversationManager, input: &str) {
+        let parsed = crate::ui::conversation::commands::parse_slash_command(input).unwrap();
+        manager.handle_slash_command(parsed).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn clear_empties_the_history() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        for n in 0..3 {
+            manager.history.add_user_message(format!("question {}", n), BindrMode::Execute);
+            manager.history.add_assistant_message(format!("answer {}", n), BindrMode::Execute);
+        }
+
+        command(&mut manager, "/clear").await;
+
+        assert_eq!(manager.history.message_count(), 1);
+        assert_eq!(last_system_message(&manager), "Conversation cleared.");
+    }
+
+    #[tokio::test]
+    async fn saved_conversation_reloads_the_same() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.history.add_user_message("What is Bindr?".to_string(), BindrMode::Execute);
+        manager.history.add_assistant_message("A mode-based assistant.".to_string(), BindrMode::Execute);
+        manager.history.add_system_message("Not saved.".to_string(), BindrMode::Execute);
+
+        command(&mut manager, "/save").await;
+
+        let project = manager.agent_manager.orchestrator().project_name().unwrap().to_string();
+        assert_eq!(last_system_message(&manager), format!("💾 Saved 2 messages to project '{}'.", project));
+        let mut sessions = SessionManager::new(manager.config.clone());
+        sessions.load_sessions().unwrap();
+        sessions.open_project(&project).unwrap();
+        let saved: Vec<String> = sessions
+            .current_session()
+            .unwrap()
+            .project_state
+            .conversation_history
+            .iter()
+            .map(|entry| format!("{:?} in {:?}: {}", entry.role, entry.mode, entry.content))
+            .collect();
+        assert_eq!(saved, ["User in Execute: What is Bindr?", "Assistant in Execute: A mode-based assistant."]);
+    }
+
+    #[tokio::test]
+    async fn export_writes_a_section_per_message() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.history.add_user_message("How do I build it?".to_string(), BindrMode::Execute);
+        manager.history.add_assistant_message("Run `cargo build`.".to_string(), BindrMode::Execute);
+
+        command(&mut manager, "/export notes/chat.md").await;
+
+        let path = home.path().join("notes/chat.md");
+        assert_eq!(last_system_message(&manager), format!("📄 Exported the conversation to {}", path.display()));
+        let markdown = std::fs::read_to_string(path).unwrap();
+        assert!(markdown.starts_with("# Bindr conversation: "), "{}", markdown);
+        let user = markdown.find("\n## 👤 User · ⚡ Execute · ").unwrap();
+        let assistant = markdown.find("\n## 🤖 Assistant · ⚡ Execute · ").unwrap();
+        assert!(user < assistant);
+        assert!(markdown[user..assistant].contains("\nHow do I build it?\n"));
+        assert!(markdown[assistant..].contains("\nRun `cargo build`.\n"));
+    }
+
+    fn echo(text: &str) -> ToolInvocation {
+        let options = CommandOptions {
+            command: "echo".to_string(),
+            args: vec![text.to_string()],
+            working_dir: ".".into(),
+            allow_network: false,
+        };
+        ToolInvocation::new(BindrTool::RunCommand(options), BindrMode::Execute, "test")
+    }
+
+    /// Stands in for the workspace: answers every tool call with `output` and records its target
+    struct FakeRunner {
+        output: ToolOutput,
+        ran: Arc<Mutex<Vec<String>>>,
+    }
+
+    /// Put a `FakeRunner` in the manager, returning the list of targets it ran
+    fn fake_runner(manager: &mut ConversationManager, output: ToolOutput) -> Arc<Mutex<Vec<String>>> {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        manager.tool_runner = Box::new(FakeRunner { output, ran: ran.clone() });
+        ran
+    }
+
+    impl ToolRunner for FakeRunner {
+        fn run<'a>(&'a self, invocation: &'a ToolInvocation) -> BoxFuture<'a, Result<ToolOutput>> {
+            self.ran.lock().unwrap().push(invocation.tool.target());
+            Box::pin(async move { Ok(self.output.clone()) })
+        }
+    }
+
+    /// Content of the last message in the request the provider received
+    fn last_sent_message(server: &MockProvider) -> String {
+        let requests = server.requests();
+        let messages = requests.last().unwrap().body["messages"].as_array().unwrap().clone();
+        messages.last().unwrap()["content"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn auto_approved_tool_runs_straight_away() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Noted"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+        let contents = FileContents::Text { content: "remember the milk".to_string(), truncated: false, total_bytes: 17 };
+        let ran = fake_runner(&mut manager, ToolOutput::File { path: "notes.txt".into(), contents });
+        let read = BindrTool::ReadFile(ReadFileOptions { path: "notes.txt".into(), max_bytes: None });
+
+        manager.handle_tool_request(ToolInvocation::new(read, BindrMode::Execute, "test")).await.unwrap();
+        assert!(manager.pending_tool.is_none());
+        assert_eq!(*ran.lock().unwrap(), vec!["notes.txt".to_string()]);
+        assert_eq!(last_system_message(&manager), "🔧 Read notes.txt (17 bytes)");
+
+        // The result goes straight back to the model
+        finish(&mut manager).await;
+        assert_eq!(server.requests().len(), 1);
+        let sent = last_sent_message(&server);
+        assert!(sent.starts_with("[tool result: read files]"), "{}", sent);
+        assert!(sent.contains("remember the milk"));
+    }
+
+    #[tokio::test]
+    async fn tool_needing_approval_waits_for_y() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Done"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+        let output = ToolOutput::Command {
+            command: "echo approved".to_string(),
+            exit_code: Some(0),
+            stdout: "approved\n".to_string(),
+            stderr: String::new(),
+        };
+        let ran = fake_runner(&mut manager, output);
+
+        manager.handle_tool_request(echo("approved")).await.unwrap();
+        assert!(manager.pending_tool.is_some());
+        assert!(last_system_message(&manager).starts_with("The assistant wants to run commands: echo approved"));
+
+        // Other keys leave it waiting, with nothing run or sent
+        manager.resolve_pending_tool(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE)).await.unwrap();
+        assert!(manager.pending_tool.is_some());
+        assert!(ran.lock().unwrap().is_empty());
+        assert!(server.requests().is_empty());
+
+        manager.resolve_pending_tool(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE)).await.unwrap();
+        assert!(manager.pending_tool.is_none());
+        assert_eq!(ran.lock().unwrap().len(), 1);
+        assert_eq!(last_system_message(&manager), "🔧 Ran echo approved (exit code 0)");
+
+        finish(&mut manager).await;
+        assert_eq!(server.requests().len(), 1);
+        assert!(last_sent_message(&server).contains("$ echo approved\n[exit code 0]\nstdout:\napproved"));
+    }
+
+    #[tokio::test]
+    async fn refused_tool_is_not_run() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        manager.handle_tool_request(echo("refused")).await.unwrap();
+        manager.resolve_pending_tool(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE)).await.unwrap();
+
+        assert!(manager.pending_tool.is_none());
+        assert!(last_system_message(&manager).starts_with("Refused to run commands: echo refused"));
+        assert!(!manager.history.messages().any(|message| message.content.starts_with("🔧")));
+        assert!(!manager.is_streaming());
+    }
+
+    #[tokio::test]
+    async fn tool_round_trips_stop_at_the_limit() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Carrying on"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+        manager.config.loop_guard.max_tool_round_trips = 2;
+        for _ in 0..2 {
+            manager.send_tool_result("[tool result: read file]\nok".to_string()).await.unwrap();
+            assert!(manager.is_streaming());
+            finish(&mut manager).await;
+        }
+
+        manager.send_tool_result("[tool result: read file]\nok".to_string()).await.unwrap();
+        assert!(!manager.is_streaming());
+        assert_eq!(server.requests().len(), 2);
+        assert!(last_system_message(&manager).contains("Stopped after 2 tool calls"));
+    }
+
+    #[tokio::test]
+    async fn ctrl_l_toggles_density_and_ctrl_d_scrolls() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.handle_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)).await.unwrap();
+        assert_eq!(manager.history.density(), Density::Comfortable);
+
+        manager.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL)).await.unwrap();
+        assert_eq!(manager.history.density(), Density::Compact);
+        manager.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL)).await.unwrap();
+        assert_eq!(manager.history.density(), Density::Comfortable);
+    }
+
+    #[tokio::test]
+    async fn a_user_message_resets_the_tool_round_trips() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Carrying on"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+        manager.config.loop_guard.max_tool_round_trips = 1;
+        manager.send_tool_result("[tool result: read file]\nok".to_string()).await.unwrap();
+        finish(&mut manager).await;
+
+        send(&mut manager, "keep going").await;
+        manager.send_tool_result("[tool result: read file]\nok".to_string()).await.unwrap();
+        assert!(manager.is_streaming());
+        finish(&mut manager).await;
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(last_sent_message(&server), "[tool result: read file]\nok");
+    }
+
+    #[tokio::test]
+    async fn sampling_commands_change_the_next_request() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Hello"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+
+        command(&mut manager, "/temperature 1.5").await;
+        command(&mut manager, "/maxtokens 500").await;
+        let orchestrator = manager.agent_manager.orchestrator();
+        assert_eq!(orchestrator.temperature(), 1.5);
+        assert_eq!(orchestrator.max_tokens(), 500);
+
+        send(&mut manager, "Hi").await;
+        let body = &server.requests()[0].body;
+        assert_eq!(body["temperature"], 1.5);
+        assert_eq!(body["max_tokens"], 500);
+    }
+
+    #[tokio::test]
+    async fn out_of_range_sampling_values_are_refused() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        let temperature = manager.agent_manager.orchestrator().temperature();
+        let max_tokens = manager.agent_manager.orchestrator().max_tokens();
+
+        command(&mut manager, "/temperature 3").await;
+        assert!(last_system_message(&manager).contains("isn't a temperature"));
+        command(&mut manager, "/maxtokens 0").await;
+        assert!(last_system_message(&manager).contains("isn't a token limit"));
+
+        assert_eq!(manager.agent_manager.orchestrator().temperature(), temperature);
+        assert_eq!(manager.agent_manager.orchestrator().max_tokens(), max_tokens);
+    }
+
+    #[tokio::test]
+    async fn default_returns_to_the_mode_values() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        let temperature = manager.agent_manager.orchestrator().temperature();
+
+        command(&mut manager, "/temperature 1.5").await;
+        command(&mut manager, "/temperature default").await;
+
+        assert_eq!(manager.agent_manager.orchestrator().temperature_override(), None);
+        assert_eq!(manager.agent_manager.orchestrator().temperature(), temperature);
+    }
+
+    async fn command_action(manager: &mut ConversationManager, input: &str) -> ConversationAction {
+        let parsed = crate::ui::conversation::commands::parse_slash_command(input).unwrap();
+        manager.handle_slash_command(parsed).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn provider_with_a_key_is_switched_to() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.config.set_api_key("anthropic".to_string(), "sk-ant-test".to_string());
+
+        let action = command_action(&mut manager, "/provider Anthropic").await;
+
+        assert!(matches!(action, ConversationAction::SwitchProvider(ref id) if id == "anthropic"), "{:?}", action);
+        assert_eq!(manager.key_prompt_provider, None);
+    }
+
+    #[tokio::test]
+    async fn provider_without_a_key_asks_for_one() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        // A key in the environment would count as configured
+        manager.config.model_providers.get_mut("anthropic").unwrap().api_key_env = None;
+
+        let action = command_action(&mut manager, "/provider anthropic").await;
+
+        assert!(matches!(action, ConversationAction::None), "{:?}", action);
+        assert!(last_system_message(&manager).contains("has no API key yet"));
+        assert_eq!(manager.key_prompt_provider.as_deref(), Some("anthropic"));
+    }
+
+    #[tokio::test]
+    async fn unknown_provider_lists_the_choices() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        let action = command_action(&mut manager, "/provider nowhere").await;
+
+        assert!(matches!(action, ConversationAction::None));
+        assert!(last_system_message(&manager).starts_with("There's no provider called \"nowhere\""));
+    }
+
+    #[tokio::test]
+    async fn provider_without_an_argument_opens_the_list() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        let action = command_action(&mut manager, "/provider").await;
+        assert!(matches!(action, ConversationAction::ShowProviderSelection));
+    }
+
+    #[tokio::test]
+    async fn history_cap_comes_from_the_config() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = config_in(&home);
+        config.ui.max_history_lines = 12;
+        let mut manager = manager_with(config).await;
+
+        for n in 0..20 {
+            manager.history.add_user_message(format!("message {}", n), BindrMode::Execute);
+        }
+
+        let kept: Vec<String> = manager.history.messages().map(|message| message.content.clone()).collect();
+        assert_eq!(kept.len(), 12);
+        assert_eq!(kept[0], "message 8");
+    }
+
+    #[tokio::test]
+    async fn model_with_a_known_id_is_switched_to() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.config.switch_provider("openai");
+        manager.config.set_api_key("openai".to_string(), "sk-test".to_string());
+
+        let action = command_action(&mut manager, "/model GPT-5").await;
+
+        assert!(
+            matches!(action, ConversationAction::SwitchModel { ref provider_id, ref model_id } if provider_id == "openai" && model_id == "gpt-5"),
+            "{:?}",
+            action
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_or_ambiguous_model_opens_the_list() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        let action = command_action(&mut manager, "/model no-such-model").await;
+        assert!(matches!(action, ConversationAction::ShowModelSelection));
+        assert!(last_system_message(&manager).starts_with("No configured provider offers a model called \"no-such-model\""));
+
+        // Offered by two providers, neither of them the selected one
+        manager.config.add_custom_provider("Mirror", "https://example.com", None, "gpt-5").unwrap();
+        manager.config.switch_provider("anthropic");
+        let action = command_action(&mut manager, "/model gpt-5").await;
+        assert!(matches!(action, ConversationAction::ShowModelSelection));
+        assert_eq!(last_system_message(&manager), "gpt-5 is offered by mirror, openai. Pick the one you want from the list.");
+    }
+
+    #[tokio::test]
+    async fn model_without_an_argument_opens_the_list() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        let action = command_action(&mut manager, "/model").await;
+        assert!(matches!(action, ConversationAction::ShowModelSelection));
+    }
+
+    fn write_notes() -> ToolInvocation {
+        let options = crate::tools::WriteFileOptions {
+            path: "notes.md".into(),
+            contents: "hello".to_string(),
+            create_if_missing: true,
+        };
+        ToolInvocation::new(BindrTool::WriteFile(options), BindrMode::Brainstorm, "test")
+    }
+
+    #[tokio::test]
+    async fn write_in_brainstorm_is_refused_with_a_note() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("Understood"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+        manager.switch_mode(BindrMode::Brainstorm).await.unwrap();
+
+        manager.handle_tool_request(write_notes()).await.unwrap();
+
+        assert!(manager.history.messages().any(|message| message.content
+            .starts_with("The assistant tried to write files, but Brainstorm mode can't do that. Switch with /mode")));
+        assert!(manager.pending_changes.is_empty());
+        assert!(!home.path().join("notes.md").exists());
+        // The model hears why, so it can carry on
+        send(&mut manager, "go on").await;
+        let told = server.requests()[0].body["messages"].to_string();
+        assert!(told.contains("Tool unavailable in Brainstorm mode"));
+    }
+
+    #[tokio::test]
+    async fn write_in_document_waits_for_review() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        manager.switch_mode(BindrMode::Document).await.unwrap();
+
+        manager.handle_tool_request(write_notes()).await.unwrap();
+
+        assert_eq!(manager.pending_changes.len(), 1);
+        assert!(!home.path().join("notes.md").exists());
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_keeps_the_partial_reply() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+
+        // The provider goes away before the reply completes
+        stream(&mut manager, vec![LlmEvent::TextDelta("Half an ".to_string()), LlmEvent::TextDelta("answer".to_string())]);
+
+        assert_eq!(assistant_messages(&manager), ["Half an answer"]);
+        assert!(!manager.is_streaming());
+        assert!(manager.current_streaming_message.is_empty());
+    }
+
+    #[tokio::test]
+    async fn esc_interrupts_and_marks_the_reply() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        let (tx, rx) = mpsc::unbounded_channel();
+        manager.stream_receiver = Some(rx);
+        tx.send(LlmEvent::TextDelta("Partial".to_string())).unwrap();
+        manager.process_streaming_chunks();
+
+        manager.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).await.unwrap();
+
+        assert_eq!(assistant_messages(&manager), ["Partial\n\n_(interrupted)_"]);
+        assert!(!manager.is_streaming());
+        // Later chunks go nowhere
+        assert!(tx.send(LlmEvent::TextDelta(" more".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn ctrl_c_before_any_text_leaves_a_note() {
+        let home = tempfile::tempdir().unwrap();
+        let mut manager = manager_in(&home).await;
+        let (_tx, rx) = mpsc::unbounded_channel();
+        manager.stream_receiver = Some(rx);
+
+        manager.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)).await.unwrap();
+
+        assert!(assistant_messages(&manager).is_empty());
+        assert_eq!(last_system_message(&manager), "Interrupted before the assistant replied.");
+        assert!(!manager.is_streaming());
+    }
+
+    #[tokio::test]
+    async fn failed_input_becomes_a_visible_error() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = config_in(&home);
+        config.selected_provider = "nowhere".to_string();
+        let mut manager = manager_with(config).await;
+
+        let error = manager.handle_input("Hi".to_string()).await.unwrap_err();
+        manager.report_error(&error);
+
+        assert_eq!(
+            last_system_message(&manager),
+            "❌ Error: No provider configured for id nowhere (run /explain or press Ctrl+E for help)"
+        );
+        assert_eq!(manager.last_error.as_deref(), Some("No provider configured for id nowhere"));
+        assert!(!manager.is_streaming());
+    }
+
+    #[tokio::test]
+    async fn missing_key_is_a_system_error_not_a_reply() {
+        let home = tempfile::tempdir().unwrap();
+        let mut config = config_in(&home);
+        config.switch_provider("openai");
+        config.model_providers.get_mut("openai").unwrap().api_key_env = None;
+        let mut manager = manager_with(config).await;
+
+        send(&mut manager, "Hi").await;
+
+        assert!(assistant_messages(&manager).is_empty());
+        assert!(last_system_message(&manager).starts_with("❌ Error: No API key configured for OpenAI. Press Ctrl+K to add one."));
+        assert_eq!(manager.key_prompt_provider.as_deref(), Some("openai"));
+    }
+
+    #[tokio::test]
+    async fn each_reply_records_the_model_that_wrote_it() {
+        let home = tempfile::tempdir().unwrap();
+        let server = MockProvider::start(vec![(200, openai_reply("First")), (200, openai_reply("Second"))]).await;
+        let mut manager = manager_against(&home, &server).await;
+
+        send(&mut manager, "Hi").await;
+        let mut config = manager.config.clone();
+        config.set_default_model("gpt-4o-mini").unwrap();
+        manager.update_config(config);
+        send(&mut manager, "Again").await;
+
+        let models: Vec<serde_json::Value> = server.requests().into_iter().map(|request| request.body["model"].clone()).collect();
+        assert_eq!(models, ["gpt-4o", "gpt-4o-mini"]);
+        let shown: Vec<(String, Option<String>)> = manager.history
+            .messages()
+            .filter(|message| matches!(message.role, ConversationRole::Assistant))
+            .map(|message| (message.content.clone(), message.model_id.clone()))
+            .collect();
+        assert_eq!(shown, [
+            ("First".to_string(), Some("gpt-4o".to_string())),
+            ("Second".to_string(), Some("gpt-4o-mini".to_string())),
+        ]);
+        let recorded: Vec<Option<&str>> = manager.agent_manager
+            .orchestrator()
+            .conversation_history()
+            .iter()
+            .filter(|entry| matches!(entry.role, ConversationRole::Assistant))
+            .map(|entry| entry.model_id.as_deref())
+            .collect();
+        assert_eq!(recorded, [Some("gpt-4o"), Some("gpt-4o-mini")]);
+    }
+
+    #[tokio::test]
+    async fn fallback_reply_is_priced_as_the_fallback_model() {
+        let home = tempfile::tempdir().unwrap();
+        let usage = serde_json::json!({ "choices": [], "usage": { "prompt_tokens": 1000, "completion_tokens": 100, "total_tokens": 1100 } });
+        let reply = openai_reply("Hello").replace("data: [DONE]", &format!("data: {}\n\ndata: [DONE]", usage));
+        let server = MockProvider::start(vec![(503, String::new()), (200, reply)]).await;
+        let mut config = config_in(&home);
+        config.selected_provider = "openai".to_string();
+        config.default_model = "gpt-4o".to_string();
+        config.model_providers.get_mut("openai").unwrap().base_url = server.base_url.clone();
+        config.set_api_key("openai".to_string(), "test-key".to_string());
+        config.ui.request_retries = 0;
+        config.fallback.providers = vec![FallbackTarget { provider: "openai".to_string(), model: "gpt-4o-mini".to_string() }];
+        let mut manager = manager_with(config).await;
+
+        send(&mut manager, "Hi").await;
+
+        let requests = server.requests();
+        assert_eq!(requests[0].body["model"], "gpt-4o");
+        assert_eq!(requests[1].body["model"], "gpt-4o-mini");
+        let usage = pricing::TokenUsage { input_tokens: 1000, output_tokens: 100 };
+        let expected = manager.config.model_rates("gpt-4o-mini").unwrap().cost(usage);
+        assert_eq!(manager.session_cost.cost, expected);
+        assert!(manager.history.messages().any(|message| message.model_id.as_deref() == Some("gpt-4o-mini")));
+    }
 }
@@ -0,0 +1,308 @@
+//! Minimal Markdown rendering for assistant replies: headings, lists, quotes,
+//! emphasis, inline code, and fenced code blocks
+
+use crate::ui::conversation::highlight::{highlight_line, Language};
+use crate::ui::conversation::wrap::wrap_spans;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Background and text colors of fenced code blocks
+fn code_block_style() -> Style {
+    Style::default().fg(Color::White).bg(Color::DarkGray)
+}
+
+fn inline_code_style() -> Style {
+    Style::default().fg(Color::Yellow)
+}
+
+/// Render `text` as styled lines no wider than `width`, with `base` as the body style.
+///
+/// With `highlight`, code blocks tagged with a known language get token colors.
+pub fn render_markdown(text: &str, width: usize, base: Style, highlight: bool) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let mut language = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            // The opening fence shows its language, if any; the closing fence leaves no trace
+            let tag = trimmed.trim_start_matches('`').trim();
+            language = Language::from_tag(tag).filter(|_| highlight);
+            if in_code && !tag.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    tag.to_string(),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                )));
+            }
+            continue;
+        }
+
+        if in_code {
+            let spans = match language {
+                Some(language) => highlight_line(line, language, code_block_style()),
+                None => vec![Span::styled(line.to_string(), code_block_style())],
+            };
+            for mut code_line in wrap_spans(&spans, width) {
+                // Pad to the full width so the block reads as one shaded panel
+                let padding = width.saturating_sub(code_line.width());
+                code_line.spans.push(Span::styled(" ".repeat(padding), code_block_style()));
+                lines.push(code_line);
+            }
+            continue;
+        }
+
+        lines.extend(render_block_line(line, width, base));
+    }
+
+    if lines.is_empty() {
+        lines.push(Line::default());
+    }
+    lines
+}
+
+/// One line outside a code block: a heading, list item, quote, rule, or paragraph text
+fn render_block_line(line: &str, width: usize, base: Style) -> Vec<Line<'static>> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    if let Some((level, heading)) = heading(trimmed) {
+        let mut style = base.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return wrap_spans(&inline_spans(heading, style), width);
+    }
+
+    if is_rule(trimmed) {
+        return vec![Line::from(Span::styled(
+            "─".repeat(width.min(40)),
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let style = base.fg(Color::Gray).add_modifier(Modifier::ITALIC);
+        return hanging(
+            Span::styled("│ ", Style::default().fg(Color::DarkGray)),
+            &inline_spans(quote.trim_start(), style),
+            width,
+        );
+    }
+
+    if let Some((marker, item)) = list_item(trimmed) {
+        let marker = format!("{}{} ", " ".repeat(indent), marker);
+        return hanging(
+            Span::styled(marker, base.add_modifier(Modifier::BOLD)),
+            &inline_spans(item, base),
+            width,
+        );
+    }
+
+    wrap_spans(&inline_spans(line, base), width)
+}
+
+/// Heading level and text of an ATX heading (`# Title` through `###### Title`)
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if rest.is_empty() {
+        return Some((level, ""));
+    }
+    rest.starts_with(' ').then(|| (level, rest.trim()))
+}
+
+/// `---`, `***`, or `___`, possibly spaced out
+fn is_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_'].iter().any(|&mark| marks.chars().all(|c| c == mark))
+}
+
+/// Marker to show and text of a bullet (`-`, `*`, `+`) or numbered (`1.`, `1)`) list item
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), item));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let rest = &line[digits..];
+    let item = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))?;
+    Some((line[..digits + 1].to_string(), item))
+}
+
+/// Wrap `spans` after `lead`, indenting continuation lines to line up under the first
+fn hanging(lead: Span<'static>, spans: &[Span<'static>], width: usize) -> Vec<Line<'static>> {
+    let lead_width = lead.content.chars().count();
+    let indent = " ".repeat(lead_width);
+    wrap_spans(spans, width.saturating_sub(lead_width).max(1))
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut line)| {
+            let prefix = if i == 0 { lead.clone() } else { Span::raw(indent.clone()) };
+            line.spans.insert(0, prefix);
+            line
+        })
+        .collect()
+}
+
+/// Split a line into spans for `**bold**`, `*italic*` / `_italic_`, and `` `code` ``.
+///
+/// A delimiter only counts when a matching one follows, so stray `*` and snake_case stay literal.
+fn inline_spans(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let style = |bold: bool, italic: bool| {
+        let mut style = base;
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        style
+    };
+
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+
+        if c == '`'
+            && let Some(end) = after.find('`')
+        {
+            flush(&mut spans, &mut current, style(bold, italic));
+            spans.push(Span::styled(after[..end].to_string(), inline_code_style()));
+            rest = &after[end + 1..];
+            previous = Some('`');
+            continue;
+        }
+
+        if let Some(strong) = ["**", "__"].into_iter().find(|delimiter| rest.starts_with(delimiter))
+            && (bold || rest[2..].contains(strong))
+        {
+            flush(&mut spans, &mut current, style(bold, italic));
+            bold = !bold;
+            rest = &rest[2..];
+            previous = Some(c);
+            continue;
+        }
+
+        if c == '*' || c == '_' {
+            // `_` only counts at word boundaries, so snake_case stays intact
+            let word_before = previous.is_some_and(char::is_alphanumeric);
+            let word_after = after.starts_with(char::is_alphanumeric);
+            let toggles = if italic {
+                c == '*' || !word_after
+            } else {
+                !after.starts_with(char::is_whitespace)
+                    && after.contains(c)
+                    && (c == '*' || !word_before)
+            };
+            if toggles {
+                flush(&mut spans, &mut current, style(bold, italic));
+                italic = !italic;
+                rest = after;
+                previous = Some(c);
+                continue;
+            }
+        }
+
+        current.push(c);
+        rest = after;
+        previous = Some(c);
+    }
+    flush(&mut spans, &mut current, style(bold, italic));
+    spans
+}
+
+fn flush(spans: &mut Vec<Span<'static>>, current: &mut String, style: Style) {
+    if !current.is_empty() {
+        spans.push(Span::styled(std::mem::take(current), style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn heading_is_bold_without_its_hashes() {
+        let lines = render_markdown("# Plan\n## Steps", 40, Style::default(), false);
+
+        assert_eq!(text(&lines[0]), "Plan");
+        let style = lines[0].spans[0].style;
+        assert_eq!(style.fg, Some(Color::Cyan));
+        assert!(style.add_modifier.contains(Modifier::BOLD | Modifier::UNDERLINED));
+        assert_eq!(text(&lines[1]), "Steps");
+        assert!(!lines[1].spans[0].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn code_block_is_shaded_to_the_full_width() {
+        let lines = render_markdown("Run:\n```sh\ncargo test\n```\nDone.", 20, Style::default(), false);
+
+        let rendered: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(rendered, ["Run:", "sh", "cargo test          ", "Done."]);
+        assert!(lines[2].spans.iter().all(|span| span.style == code_block_style()));
+        assert_eq!(lines[2].width(), 20);
+    }
+
+    #[test]
+    fn inline_emphasis_and_code() {
+        let spans = inline_spans("a **bold** _word_ and `snake_case`", Style::default());
+        let parts: Vec<(&str, bool, bool)> = spans
+            .iter()
+            .map(|span| {
+                let modifiers = span.style.add_modifier;
+                (span.content.as_ref(), modifiers.contains(Modifier::BOLD), modifiers.contains(Modifier::ITALIC))
+            })
+            .collect();
+        assert_eq!(parts, [
+            ("a ", false, false),
+            ("bold", true, false),
+            (" ", false, false),
+            ("word", false, true),
+            (" and ", false, false),
+            ("snake_case", false, false),
+        ]);
+        assert_eq!(spans[5].style, inline_code_style());
+    }
+
+    #[test]
+    fn stray_markers_stay_literal() {
+        let spans = inline_spans("2 * 3 and my_var_name", Style::default());
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].content, "2 * 3 and my_var_name");
+    }
+
+    #[test]
+    fn code_is_highlighted_only_when_enabled_and_known() {
+        let keyword_colored = |text: &str, highlight: bool| {
+            render_markdown(text, 30, Style::default(), highlight)
+                .iter()
+                .flat_map(|line| line.spans.iter())
+                .any(|span| span.content == "fn" && span.style.fg == Some(Color::LightMagenta))
+        };
+
+        assert!(keyword_colored("```rust\nfn main() {}\n```", true));
+        assert!(!keyword_colored("```rust\nfn main() {}\n```", false));
+        assert!(!keyword_colored("```text\nfn main() {}\n```", true));
+    }
+}
@@ -2,12 +2,15 @@
 
 pub mod commands;
 pub mod composer;
+pub mod diff_view;
+pub mod highlight;
 pub mod history;
 pub mod manager;
-pub mod streaming;
+pub mod markdown;
+pub mod wrap;
 
 pub use commands::{SlashCommand, ParsedCommand, get_help_text};
 pub use composer::ConversationComposer;
+pub use diff_view::{DiffDecision, DiffView};
 pub use history::ConversationHistory;
 pub use manager::ConversationManager;
-pub use streaming::StreamingResponse;
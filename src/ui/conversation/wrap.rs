@@ -0,0 +1,155 @@
+//! Line wrapping shared by the conversation views
+
+use ratatui::text::{Line, Span};
+
+/// Wrap `text` to `width` columns.
+///
+/// Newlines are hard breaks, so paragraphs and code keep their shape; spacing inside a line is
+/// kept, and a token longer than the width is split across lines. A width of 0 only splits
+/// on newlines.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = if width == 0 { usize::MAX } else { width };
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        wrap_line(line.strip_suffix('\r').unwrap_or(line), width, &mut lines);
+    }
+    lines
+}
+
+fn wrap_line(line: &str, width: usize, lines: &mut Vec<String>) {
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for token in tokens(line) {
+        let token_width = token.chars().count();
+        if current_width + token_width <= width {
+            current.push_str(token);
+            current_width += token_width;
+            continue;
+        }
+
+        // The token doesn't fit: break before it, dropping spaces that would end or start a line
+        if !current.trim().is_empty() {
+            lines.push(current.trim_end().to_string());
+        }
+        current.clear();
+        current_width = 0;
+        if token.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        for c in token.chars() {
+            if current_width == width {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += 1;
+        }
+    }
+
+    lines.push(current);
+}
+
+/// Wrap one line of styled spans to `width` columns, like [`wrap_text`] without the newline handling
+pub fn wrap_spans(spans: &[Span<'_>], width: usize) -> Vec<Line<'static>> {
+    let width = if width == 0 { usize::MAX } else { width };
+    let mut lines = Vec::new();
+    let mut current: Vec<Span<'static>> = Vec::new();
+    let mut current_width = 0;
+
+    for span in spans {
+        for token in tokens(&span.content) {
+            let token_width = token.chars().count();
+            if current_width + token_width <= width {
+                current.push(Span::styled(token.to_string(), span.style));
+                current_width += token_width;
+                continue;
+            }
+
+            while current.last().is_some_and(|last| last.content.trim().is_empty()) {
+                current.pop();
+            }
+            if !current.is_empty() {
+                lines.push(Line::from(std::mem::take(&mut current)));
+            }
+            current_width = 0;
+            if token.starts_with(char::is_whitespace) {
+                continue;
+            }
+
+            let mut piece = String::new();
+            for c in token.chars() {
+                if current_width == width {
+                    current.push(Span::styled(std::mem::take(&mut piece), span.style));
+                    lines.push(Line::from(std::mem::take(&mut current)));
+                    current_width = 0;
+                }
+                piece.push(c);
+                current_width += 1;
+            }
+            current.push(Span::styled(piece, span.style));
+        }
+    }
+
+    lines.push(Line::from(current));
+    lines
+}
+
+/// Alternating runs of whitespace and non-whitespace
+fn tokens(line: &str) -> impl Iterator<Item = &str> {
+    let mut rest = line;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_whitespace() != first.is_whitespace())
+            .unwrap_or(rest.len());
+        let (token, remainder) = rest.split_at(end);
+        rest = remainder;
+        Some(token)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+
+    #[test]
+    fn paragraphs_keep_their_breaks() {
+        assert_eq!(
+            wrap_text("First paragraph here.\n\nSecond one.", 10),
+            ["First", "paragraph", "here.", "", "Second", "one."]
+        );
+    }
+
+    #[test]
+    fn spacing_inside_a_line_is_kept() {
+        assert_eq!(wrap_text("    let x = 1;\r\n    x", 40), ["    let x = 1;", "    x"]);
+    }
+
+    #[test]
+    fn long_token_is_split() {
+        assert_eq!(
+            wrap_text("see https://example.com/a/very/long/path ok", 12),
+            ["see", "https://exam", "ple.com/a/ve", "ry/long/path", "ok"]
+        );
+    }
+
+    #[test]
+    fn width_one_puts_each_character_on_a_line() {
+        assert_eq!(wrap_text("ab c", 1), ["a", "b", "c"]);
+        assert_eq!(wrap_text("ab c", 0), ["ab c"]);
+        assert_eq!(wrap_text("", 1), [""]);
+    }
+
+    #[test]
+    fn spans_keep_their_styles_across_breaks() {
+        let red = Style::default().fg(Color::Red);
+        let lines = wrap_spans(&[Span::raw("plain "), Span::styled("redword", red)], 9);
+
+        let text: Vec<String> = lines.iter().map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect()).collect();
+        assert_eq!(text, ["plain", "redword"]);
+        assert!(lines[1].spans.iter().all(|span| span.style == red));
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::persist::{write_atomic, FileLock};
+
+const USAGE_FILE: &str = "usage.json";
+
+/// Free-tier messages sent on one day, kept under the Bindr home
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub count: u32,
+}
+
+impl DailyUsage {
+    fn path(bindr_home: &Path) -> PathBuf {
+        bindr_home.join(USAGE_FILE)
+    }
+
+    /// Load the counter, if one was recorded and is readable
+    pub fn load(bindr_home: &Path) -> Option<Self> {
+        fs::read_to_string(Self::path(bindr_home))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Messages counted on `today`; a counter from an earlier day counts as none
+    pub fn used_on(&self, today: NaiveDate) -> u32 {
+        if self.date == today { self.count } else { 0 }
+    }
+
+    /// Count one more message on `today`, starting over when the date has rolled; returns the new count
+    pub fn record(bindr_home: &Path, today: NaiveDate) -> Result<u32> {
+        let path = Self::path(bindr_home);
+        let _lock = FileLock::acquire(&path)?;
+        let count = Self::load(bindr_home).map_or(0, |usage| usage.used_on(today)) + 1;
+        let content = serde_json::to_string(&Self { date: today, count })
+            .context("Failed to serialize usage counter")?;
+        write_atomic(&path, content).context("Failed to write usage counter")?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn record_counts_up_within_a_day() {
+        let home = tempfile::tempdir().unwrap();
+        assert_eq!(DailyUsage::record(home.path(), day(1)).unwrap(), 1);
+        assert_eq!(DailyUsage::record(home.path(), day(1)).unwrap(), 2);
+        assert_eq!(DailyUsage::load(home.path()), Some(DailyUsage { date: day(1), count: 2 }));
+    }
+
+    #[test]
+    fn new_day_starts_over() {
+        let home = tempfile::tempdir().unwrap();
+        DailyUsage::record(home.path(), day(1)).unwrap();
+        DailyUsage::record(home.path(), day(1)).unwrap();
+
+        let usage = DailyUsage::load(home.path()).unwrap();
+        assert_eq!(usage.used_on(day(2)), 0);
+        assert_eq!(DailyUsage::record(home.path(), day(2)).unwrap(), 1);
+    }
+
+    #[test]
+    fn missing_or_damaged_counter_counts_as_none() {
+        let home = tempfile::tempdir().unwrap();
+        assert_eq!(DailyUsage::load(home.path()), None);
+        fs::write(home.path().join(USAGE_FILE), "{not json").unwrap();
+        assert_eq!(DailyUsage::record(home.path(), day(1)).unwrap(), 1);
+    }
+}